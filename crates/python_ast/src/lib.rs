@@ -10,6 +10,7 @@ mod int;
 pub mod name;
 mod node;
 mod nodes;
+pub mod statement_visitor;
 pub mod str_prefix;
 pub mod visitor;
 
@@ -0,0 +1,107 @@
+use python_ast::visitor::preorder::{PreorderVisitor, TraversalSignal};
+use python_ast::{AnyNodeRef, ModModule};
+use ruff_text_size::{Ranged, TextSize};
+
+/// Returns the nodes containing `offset`, from the outermost to the innermost.
+///
+/// An offset at the boundary between two nodes, e.g. right after `foo` in `foo(x)`, belongs to
+/// the node that comes first in source order.
+pub fn nodes_at_offset(module: &ModModule, offset: TextSize) -> Vec<AnyNodeRef<'_>> {
+    let mut visitor = NodesAtOffset {
+        offset,
+        nodes: Vec::new(),
+    };
+    if let TraversalSignal::Traverse = visitor.enter_node(AnyNodeRef::from(module)) {
+        visitor.visit_body(&module.body);
+    }
+    visitor.nodes
+}
+
+/// Returns the innermost node containing `offset`.
+pub fn node_at_offset(module: &ModModule, offset: TextSize) -> Option<AnyNodeRef<'_>> {
+    nodes_at_offset(module, offset).pop()
+}
+
+struct NodesAtOffset<'a> {
+    offset: TextSize,
+    nodes: Vec<AnyNodeRef<'a>>,
+}
+
+impl<'a> PreorderVisitor<'a> for NodesAtOffset<'a> {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        if !node.range().contains_inclusive(self.offset) {
+            return TraversalSignal::Skip;
+        }
+
+        // Only descend into the children of the innermost node found so far, this skips the
+        // following sibling of a node ending at the offset.
+        if self
+            .nodes
+            .last()
+            .is_some_and(|last| !last.range().contains_range(node.range()))
+        {
+            return TraversalSignal::Skip;
+        }
+
+        self.nodes.push(node);
+        TraversalSignal::Traverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use python_ast::{AnyNodeRef, NodeKind};
+    use python_parser::parse_module;
+    use ruff_text_size::TextSize;
+
+    use super::nodes_at_offset;
+
+    fn kinds(source: &str, offset: u32) -> Vec<NodeKind> {
+        let parsed = parse_module(source);
+        nodes_at_offset(parsed.syntax(), TextSize::new(offset))
+            .into_iter()
+            .map(AnyNodeRef::kind)
+            .collect()
+    }
+
+    #[test]
+    fn innermost_node_last() {
+        assert_eq!(
+            kinds("foo.bar(x)\n", 5),
+            vec![
+                NodeKind::ModModule,
+                NodeKind::ExprStmt,
+                NodeKind::CallExpr,
+                NodeKind::AttributeExpr,
+            ]
+        );
+        assert_eq!(
+            kinds("foo.bar(x)\n", 1),
+            vec![
+                NodeKind::ModModule,
+                NodeKind::ExprStmt,
+                NodeKind::CallExpr,
+                NodeKind::AttributeExpr,
+                NodeKind::NameExpr,
+            ]
+        );
+    }
+
+    #[test]
+    fn boundary_prefers_first_node() {
+        assert_eq!(
+            kinds("foo(x)\n", 3),
+            vec![
+                NodeKind::ModModule,
+                NodeKind::ExprStmt,
+                NodeKind::CallExpr,
+                NodeKind::NameExpr,
+            ]
+        );
+    }
+
+    #[test]
+    fn outside_of_any_statement() {
+        assert_eq!(kinds("x = 1\n\n\n", 8), vec![NodeKind::ModModule]);
+    }
+}
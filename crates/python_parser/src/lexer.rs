@@ -116,7 +116,7 @@ impl<'src> Lexer<'src> {
     }
 
     /// Returns the range of the current token.
-    pub(crate) fn current_range(&self) -> TextRange {
+    pub fn current_range(&self) -> TextRange {
         self.current_range
     }
 
@@ -1652,6 +1652,27 @@ impl<'a> LexedText<'a> {
     }
 }
 
+/// Returns `true` if `name` is a valid identifier according to the rules the lexer uses to lex
+/// names. Soft keywords, e.g. `match`, are valid identifiers whereas the other keywords aren't.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+
+    if !(is_ascii_identifier_start(first) || is_unicode_identifier_start(first)) {
+        return false;
+    }
+
+    let mut is_ascii = first.is_ascii();
+    if !chars.all(|c| is_identifier_continuation(c, &mut is_ascii)) {
+        return false;
+    }
+
+    let kind = lex(name, Mode::Module).next_token();
+    kind == TokenKind::Name || kind.is_soft_keyword()
+}
+
 /// Create a new [`Lexer`] for the given source code and [`Mode`].
 pub fn lex(source: &str, mode: Mode) -> Lexer {
     Lexer::new(source, mode, TextSize::default())
@@ -2446,4 +2467,16 @@ f"{(lambda x:{x})}"
             UnterminatedTripleQuotedString
         );
     }
+
+    #[test]
+    fn identifiers() {
+        for name in ["foo", "_", "__all__", "x1", "ñame", "名前", "match", "type"] {
+            assert!(is_identifier(name), "`{name}` should be an identifier");
+        }
+        for name in [
+            "", "1x", "foo bar", "foo.bar", "class", "None", "a-b", "f'x'",
+        ] {
+            assert!(!is_identifier(name), "`{name}` shouldn't be an identifier");
+        }
+    }
 }
@@ -0,0 +1,277 @@
+//! Discovery of the Python environment of a workspace, without running the interpreter.
+
+use std::path::{Path, PathBuf};
+
+use ruff_python_resolver::host::Host;
+use ruff_python_resolver::python_platform::PythonPlatform;
+use ruff_python_resolver::python_version::PythonVersion;
+use ruff_python_resolver::search::site_packages_paths;
+
+/// The configuration file at the root of every virtual environment.
+const PYVENV_CFG: &str = "pyvenv.cfg";
+
+/// The virtual environment directories looked up in the workspace root, in order.
+const VENV_DIRECTORIES: [&str; 2] = [".venv", "venv"];
+
+/// A Python installation or virtual environment.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PythonEnvironment {
+    /// The root of the environment, the directory containing its `lib` directory.
+    pub prefix: PathBuf,
+    /// The version of the environment, if it could be determined.
+    pub version: Option<PythonVersion>,
+}
+
+impl PythonEnvironment {
+    /// Returns the environment of the interpreter at `interpreter`, e.g. `.venv/bin/python`.
+    pub fn from_interpreter(interpreter: &Path) -> Option<Self> {
+        let directory = interpreter.parent()?;
+        // Interpreters live in the `bin` or `Scripts` directory of virtual environments and
+        // Unix installations, and in the prefix itself on Windows.
+        let is_scripts_directory = directory
+            .file_name()
+            .is_some_and(|name| name == "bin" || name == "Scripts");
+        let prefix = if is_scripts_directory {
+            directory.parent()?
+        } else {
+            directory
+        };
+
+        let version = read_pyvenv_cfg_version(prefix).or_else(|| {
+            interpreter
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix("python"))
+                .and_then(|version| version.strip_suffix(".exe").or(Some(version)))
+                .and_then(PythonVersion::from_version_str)
+        });
+
+        Some(Self {
+            prefix: prefix.to_path_buf(),
+            version,
+        })
+    }
+
+    /// Returns the virtual environment at `prefix`, if it contains a `pyvenv.cfg` file.
+    pub fn from_virtual_environment(prefix: &Path) -> Option<Self> {
+        if !prefix.join(PYVENV_CFG).is_file() {
+            return None;
+        }
+
+        Some(Self {
+            prefix: prefix.to_path_buf(),
+            version: read_pyvenv_cfg_version(prefix),
+        })
+    }
+
+    /// Returns the `site-packages` directories of the environment, along with the directories
+    /// listed in their `.pth` files.
+    pub fn search_paths(&self, platform: PythonPlatform) -> Vec<PathBuf> {
+        site_packages_paths(&self.prefix, platform, self.version)
+    }
+}
+
+/// Finds the Python environment of the workspace at `root`. The first match of the following
+/// is used:
+///
+/// 1. The first of the configured `interpreters` that exists.
+/// 2. The virtual environment activated with the `VIRTUAL_ENV` environment variable.
+/// 3. A `.venv` or `venv` virtual environment in the workspace root.
+/// 4. The workspace root itself, if it's a virtual environment.
+pub fn find_python_environment(root: &Path, interpreters: &[PathBuf]) -> Option<PythonEnvironment> {
+    let virtual_env = std::env::var_os("VIRTUAL_ENV").map(PathBuf::from);
+    find_python_environment_with(root, interpreters, virtual_env.as_deref())
+}
+
+fn find_python_environment_with(
+    root: &Path,
+    interpreters: &[PathBuf],
+    virtual_env: Option<&Path>,
+) -> Option<PythonEnvironment> {
+    if let Some(interpreter) = interpreters
+        .iter()
+        .find(|interpreter| interpreter.is_file())
+    {
+        if let Some(environment) = PythonEnvironment::from_interpreter(interpreter) {
+            return Some(environment);
+        }
+    }
+    if !interpreters.is_empty() {
+        tracing::warn!("None of the configured Python interpreters exist: {interpreters:?}");
+    }
+
+    virtual_env
+        .into_iter()
+        .map(Path::to_path_buf)
+        .chain(
+            VENV_DIRECTORIES
+                .iter()
+                .map(|directory| root.join(directory)),
+        )
+        .chain(std::iter::once(root.to_path_buf()))
+        .find_map(|prefix| PythonEnvironment::from_virtual_environment(&prefix))
+}
+
+/// Reads the Python version from the `pyvenv.cfg` file of the virtual environment at `prefix`.
+fn read_pyvenv_cfg_version(prefix: &Path) -> Option<PythonVersion> {
+    let contents = std::fs::read_to_string(prefix.join(PYVENV_CFG)).ok()?;
+    parse_pyvenv_cfg_version(&contents)
+}
+
+/// Returns the `version` of a `pyvenv.cfg` file, written as `version_info` by some tools.
+fn parse_pyvenv_cfg_version(contents: &str) -> Option<PythonVersion> {
+    contents.lines().find_map(|line| {
+        let (key, value) = line.split_once('=')?;
+        match key.trim() {
+            "version" | "version_info" => PythonVersion::from_version_str(value),
+            _ => None,
+        }
+    })
+}
+
+/// Returns the platform the server is running on.
+pub const fn current_platform() -> PythonPlatform {
+    if cfg!(target_os = "windows") {
+        PythonPlatform::Windows
+    } else if cfg!(target_os = "macos") {
+        PythonPlatform::Darwin
+    } else {
+        PythonPlatform::Linux
+    }
+}
+
+/// The host environment of the resolver, a Python environment found on the filesystem.
+#[derive(Debug, Clone)]
+pub struct PythonHost {
+    search_paths: Vec<PathBuf>,
+    version: PythonVersion,
+    platform: PythonPlatform,
+}
+
+impl PythonHost {
+    /// The version assumed when the environment's version is unknown.
+    const DEFAULT_VERSION: PythonVersion = PythonVersion::Py312;
+
+    /// Finds the Python environment of the workspace at `root`, see [`find_python_environment`].
+    pub fn discover(root: &Path, interpreters: &[PathBuf]) -> Self {
+        let environment = find_python_environment(root, interpreters);
+        match &environment {
+            Some(environment) => tracing::info!(
+                "Using the Python environment at `{}`",
+                environment.prefix.display()
+            ),
+            None => tracing::info!(
+                "No Python environment found for `{}`, third-party imports won't be resolved",
+                root.display()
+            ),
+        }
+        Self::from_environment(environment.as_ref())
+    }
+
+    pub fn from_environment(environment: Option<&PythonEnvironment>) -> Self {
+        let platform = current_platform();
+        Self {
+            search_paths: environment
+                .map(|environment| environment.search_paths(platform))
+                .unwrap_or_default(),
+            version: environment
+                .and_then(|environment| environment.version)
+                .unwrap_or(Self::DEFAULT_VERSION),
+            platform,
+        }
+    }
+}
+
+impl Default for PythonHost {
+    fn default() -> Self {
+        Self::from_environment(None)
+    }
+}
+
+impl Host for PythonHost {
+    fn python_search_paths(&self) -> Vec<PathBuf> {
+        self.search_paths.clone()
+    }
+
+    fn python_version(&self) -> PythonVersion {
+        self.version
+    }
+
+    fn python_platform(&self) -> PythonPlatform {
+        self.platform
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use ruff_python_resolver::python_platform::PythonPlatform;
+    use ruff_python_resolver::python_version::PythonVersion;
+
+    use super::{find_python_environment_with, parse_pyvenv_cfg_version, PythonEnvironment};
+
+    fn create_venv(prefix: &Path, cfg: &str) {
+        std::fs::create_dir_all(prefix.join("bin")).unwrap();
+        std::fs::create_dir_all(prefix.join("lib/python3.11/site-packages")).unwrap();
+        std::fs::write(prefix.join("pyvenv.cfg"), cfg).unwrap();
+        std::fs::write(prefix.join("bin/python"), "").unwrap();
+    }
+
+    #[test]
+    fn pyvenv_cfg_version() {
+        let cfg = "home = /usr/bin\ninclude-system-site-packages = false\nversion = 3.11.4\n";
+        assert_eq!(parse_pyvenv_cfg_version(cfg), Some(PythonVersion::Py311));
+        assert_eq!(
+            parse_pyvenv_cfg_version("version_info = 3.13.0.final.0\n"),
+            Some(PythonVersion::Py313)
+        );
+        assert!(parse_pyvenv_cfg_version("home = /usr/bin\n").is_none());
+    }
+
+    #[test]
+    fn environment_discovery() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        create_venv(&root.join(".venv"), "version = 3.11.2\n");
+        create_venv(&root.join("active"), "version = 3.11.2\n");
+
+        // The workspace's virtual environment.
+        let environment = find_python_environment_with(root, &[], None).unwrap();
+        assert_eq!(environment.prefix, root.join(".venv"));
+        assert_eq!(
+            environment.search_paths(PythonPlatform::Linux),
+            vec![root.join(".venv/lib/python3.11/site-packages")]
+        );
+
+        // The activated virtual environment takes precedence.
+        let environment =
+            find_python_environment_with(root, &[], Some(&root.join("active"))).unwrap();
+        assert_eq!(environment.prefix, root.join("active"));
+
+        // And the configured interpreter over both, skipping the missing ones.
+        let interpreters = vec![
+            PathBuf::from("/missing/bin/python"),
+            root.join("active/bin/python"),
+        ];
+        let environment =
+            find_python_environment_with(root, &interpreters, Some(&root.join(".venv"))).unwrap();
+        assert_eq!(environment.prefix, root.join("active"));
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(find_python_environment_with(empty.path(), &[], None).is_none());
+    }
+
+    #[test]
+    fn system_interpreter() {
+        let prefix = tempfile::tempdir().unwrap();
+        let prefix = prefix.path();
+        std::fs::create_dir_all(prefix.join("bin")).unwrap();
+        std::fs::write(prefix.join("bin/python3.12"), "").unwrap();
+
+        let environment =
+            PythonEnvironment::from_interpreter(&prefix.join("bin/python3.12")).unwrap();
+        assert_eq!(environment.prefix, prefix);
+        assert_eq!(environment.version, Some(PythonVersion::Py312));
+    }
+}
@@ -73,6 +73,11 @@ impl ImplicitImports {
                 let py_file_path = path.join("__init__.py");
                 let pyi_file_path = path.join("__init__.pyi");
 
+                // E.g., `foo/__init__.py` becomes `foo`.
+                let Some(name) = path.file_name().and_then(OsStr::to_str) else {
+                    continue;
+                };
+
                 let (path, is_stub_file) = if py_file_path.exists() {
                     (py_file_path, false)
                 } else if pyi_file_path.exists() {
@@ -80,10 +85,6 @@ impl ImplicitImports {
                 } else {
                     continue;
                 };
-
-                let Some(name) = path.file_name().and_then(OsStr::to_str) else {
-                    continue;
-                };
                 submodules.insert(
                     name.to_string(),
                     ImplicitImport {
@@ -1,5 +1,5 @@
 /// Enum to represent a Python version.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum PythonVersion {
     Py37,
     Py38,
@@ -7,6 +7,7 @@ pub enum PythonVersion {
     Py310,
     Py311,
     Py312,
+    Py313,
 }
 
 impl PythonVersion {
@@ -19,6 +20,28 @@ impl PythonVersion {
             PythonVersion::Py310 => "python3.10",
             PythonVersion::Py311 => "python3.11",
             PythonVersion::Py312 => "python3.12",
+            PythonVersion::Py313 => "python3.13",
+        }
+    }
+
+    /// Parses a version string, e.g. `3.11` or `3.11.4`, ignoring everything after the minor
+    /// version. Returns `None` for unsupported versions.
+    pub fn from_version_str(version: &str) -> Option<Self> {
+        let mut parts = version.trim().split('.');
+        let major = parts.next()?;
+        let minor = parts.next()?;
+        if major != "3" {
+            return None;
+        }
+        match minor {
+            "7" => Some(PythonVersion::Py37),
+            "8" => Some(PythonVersion::Py38),
+            "9" => Some(PythonVersion::Py39),
+            "10" => Some(PythonVersion::Py310),
+            "11" => Some(PythonVersion::Py311),
+            "12" => Some(PythonVersion::Py312),
+            "13" => Some(PythonVersion::Py313),
+            _ => None,
         }
     }
 }
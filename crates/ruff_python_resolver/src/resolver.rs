@@ -740,5 +740,6 @@ pub fn resolve_import<Host: host::Host>(
         current = parent;
     }
 
-    ImportResult::not_found()
+    // Keep the strict result, it tells whether the import was partly resolved.
+    import_result
 }
@@ -10,6 +10,7 @@ use log::debug;
 use crate::config::Config;
 use crate::host;
 use crate::module_descriptor::ImportModuleDescriptor;
+use crate::python_platform::PythonPlatform;
 use crate::python_version::PythonVersion;
 
 const SITE_PACKAGES: &str = "site-packages";
@@ -133,29 +134,41 @@ fn find_paths_from_pth_files(parent_dir: &Path) -> io::Result<impl Iterator<Item
         }))
 }
 
+/// Find the `site-packages` directories of the Python environment at `prefix`, along with the
+/// paths listed in their `.pth` files.
+pub fn site_packages_paths(
+    prefix: &Path,
+    python_platform: PythonPlatform,
+    python_version: Option<PythonVersion>,
+) -> Vec<PathBuf> {
+    let mut found_paths = vec![];
+
+    for lib_name in python_platform.lib_names() {
+        let lib_path = prefix.join(lib_name);
+        if let Some(site_packages_path) = find_site_packages_path(&lib_path, python_version) {
+            // Add paths from any `.pth` files in each of the `site-packages` directories.
+            if let Ok(pth_paths) = find_paths_from_pth_files(&site_packages_path) {
+                found_paths.extend(pth_paths);
+            }
+
+            // Add the `site-packages` directory to the search path.
+            found_paths.push(site_packages_path);
+        }
+    }
+
+    found_paths.sort();
+    found_paths.dedup();
+    found_paths
+}
+
 /// Find the Python search paths for the given virtual environment.
 fn find_python_search_paths<Host: host::Host>(config: &Config, host: &Host) -> Vec<PathBuf> {
     if let Some(venv_path) = config.venv_path.as_ref() {
         if let Some(venv) = config.venv.as_ref() {
-            let mut found_paths = vec![];
-
-            for lib_name in host.python_platform().lib_names() {
-                let lib_path = venv_path.join(venv).join(lib_name);
-                if let Some(site_packages_path) = find_site_packages_path(&lib_path, None) {
-                    // Add paths from any `.pth` files in each of the `site-packages` directories.
-                    if let Ok(pth_paths) = find_paths_from_pth_files(&site_packages_path) {
-                        found_paths.extend(pth_paths);
-                    }
-
-                    // Add the `site-packages` directory to the search path.
-                    found_paths.push(site_packages_path);
-                }
-            }
+            let found_paths =
+                site_packages_paths(&venv_path.join(venv), host.python_platform(), None);
 
             if !found_paths.is_empty() {
-                found_paths.sort();
-                found_paths.dedup();
-
                 debug!("Found the following `site-packages` dirs");
                 for path in &found_paths {
                     debug!("  {}", path.display());
@@ -171,7 +184,7 @@ fn find_python_search_paths<Host: host::Host>(config: &Config, host: &Host) -> V
 }
 
 /// Determine the relevant Python search paths.
-pub(crate) fn python_search_paths<Host: host::Host>(config: &Config, host: &Host) -> Vec<PathBuf> {
+pub fn python_search_paths<Host: host::Host>(config: &Config, host: &Host) -> Vec<PathBuf> {
     // TODO(charlie): Cache search paths.
     find_python_search_paths(config, host)
 }
@@ -270,9 +283,6 @@ pub(crate) fn third_party_typeshed_package_paths<Host: host::Host>(
 }
 
 /// Determine the current `typeshed` subdirectory for the standard library.
-pub(crate) fn stdlib_typeshed_path<Host: host::Host>(
-    config: &Config,
-    host: &Host,
-) -> Option<PathBuf> {
+pub fn stdlib_typeshed_path<Host: host::Host>(config: &Config, host: &Host) -> Option<PathBuf> {
     typeshed_subdirectory(true, config, host)
 }
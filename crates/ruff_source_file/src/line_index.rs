@@ -18,6 +18,10 @@ pub struct LineIndex {
     inner: Arc<LineIndexInner>,
 }
 
+/// The expected average length of a line, used to estimate the number of lines of a text from
+/// its length when reserving capacity for the line starts.
+const AVERAGE_LINE_LENGTH: usize = 88;
+
 struct LineIndexInner {
     line_starts: Vec<TextSize>,
     kind: IndexKind,
@@ -26,7 +30,7 @@ struct LineIndexInner {
 impl LineIndex {
     /// Builds the [`LineIndex`] from the source text of a file.
     pub fn from_source_text(text: &str) -> Self {
-        let mut line_starts: Vec<TextSize> = Vec::with_capacity(text.len() / 88);
+        let mut line_starts: Vec<TextSize> = Vec::with_capacity(text.len() / AVERAGE_LINE_LENGTH);
         line_starts.push(TextSize::default());
 
         let bytes = text.as_bytes();
@@ -60,6 +64,72 @@ impl LineIndex {
         }
     }
 
+    /// Updates the index after the text in `range` has been replaced with `replacement`.
+    ///
+    /// `text` is the source text *after* the edit. Only the line starts inside of the edited
+    /// region are recomputed, the line starts after it are shifted by the change in length.
+    ///
+    /// ## Panics
+    ///
+    /// If `range` and `replacement` don't describe an edit that produced `text`.
+    pub fn apply_edit(&mut self, range: TextRange, replacement: &str, text: &str) {
+        let bytes = text.as_bytes();
+        assert!(u32::try_from(bytes.len()).is_ok());
+
+        let start = range.start();
+        let new_end = start + replacement.text_len();
+        assert!(new_end <= text.text_len());
+
+        let line_starts = self.line_starts();
+        // Line starts before the edit only depend on the unchanged text before it.
+        // The first line always starts at offset 0.
+        let unchanged_before =
+            line_starts.partition_point(|&line_start| line_start < start.max(TextSize::from(1)));
+        // Line starts after the edit keep their line break, they only move.
+        let unchanged_after = line_starts.partition_point(|&line_start| line_start <= range.end());
+
+        let mut new_line_starts = Vec::with_capacity(
+            unchanged_before
+                + (line_starts.len() - unchanged_after)
+                + replacement.len() / AVERAGE_LINE_LENGTH,
+        );
+        new_line_starts.extend_from_slice(&line_starts[..unchanged_before]);
+
+        // Recompute the line starts that may have changed: everything in the replacement
+        // and the offsets right before and after it, where a `\r\n` may have been split or joined.
+        let first = usize::from(start).max(1);
+        for offset in first..=usize::from(new_end) {
+            let is_line_start = match bytes[offset - 1] {
+                b'\n' => true,
+                b'\r' => bytes.get(offset) != Some(&b'\n'),
+                _ => false,
+            };
+
+            if is_line_start {
+                // SAFETY: Assertion above guarantees `offset <= u32::MAX`
+                #[allow(clippy::cast_possible_truncation)]
+                new_line_starts.push(TextSize::from(offset as u32));
+            }
+        }
+
+        new_line_starts.extend(
+            line_starts[unchanged_after..]
+                .iter()
+                .map(|&line_start| line_start - range.end() + new_end),
+        );
+
+        let kind = if self.kind().is_ascii() && replacement.is_ascii() {
+            IndexKind::Ascii
+        } else {
+            IndexKind::Utf8
+        };
+
+        self.inner = Arc::new(LineIndexInner {
+            line_starts: new_line_starts,
+            kind,
+        });
+    }
+
     fn kind(&self) -> IndexKind {
         self.inner.kind
     }
@@ -204,6 +274,53 @@ impl LineIndex {
     pub fn line_starts(&self) -> &[TextSize] {
         &self.inner.line_starts
     }
+
+    /// Returns the [byte offset](TextSize) at `line` and `column`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// # use ruff_text_size::TextSize;
+    /// # use ruff_source_file::{LineIndex, OneIndexed};
+    /// let source = "def a():\n    pass";
+    /// let index = LineIndex::from_source_text(source);
+    ///
+    /// assert_eq!(
+    ///     index.offset(OneIndexed::from_zero_indexed(1), OneIndexed::from_zero_indexed(4), source),
+    ///     TextSize::from(13)
+    /// );
+    /// ```
+    pub fn offset(&self, line: OneIndexed, column: OneIndexed, contents: &str) -> TextSize {
+        // If start-of-line position after last line
+        if line.to_zero_indexed() > self.line_starts().len() {
+            return contents.text_len();
+        }
+
+        let line_range = self.line_range(line, contents);
+
+        match self.kind() {
+            IndexKind::Ascii => {
+                line_range.start()
+                    + TextSize::try_from(column.to_zero_indexed())
+                        .unwrap_or(line_range.len())
+                        .clamp(TextSize::new(0), line_range.len())
+            }
+            IndexKind::Utf8 => {
+                let rest = &contents[line_range];
+                let column_offset: TextSize = rest
+                    .chars()
+                    .take(column.to_zero_indexed())
+                    .map(TextLen::text_len)
+                    .sum();
+                line_range.start() + column_offset
+            }
+        }
+    }
+
+    /// Returns `true` if the text only consists of ASCII characters
+    pub fn is_ascii(&self) -> bool {
+        self.kind().is_ascii()
+    }
 }
 
 impl Deref for LineIndex {
@@ -313,7 +430,7 @@ const fn unwrap<T: Copy>(option: Option<T>) -> T {
 
 #[cfg(test)]
 mod tests {
-    use ruff_text_size::TextSize;
+    use ruff_text_size::{TextRange, TextSize};
 
     use crate::line_index::LineIndex;
     use crate::{OneIndexed, SourceLocation};
@@ -592,4 +709,60 @@ mod tests {
             }
         );
     }
+
+    #[track_caller]
+    fn assert_edit(source: &str, range: std::ops::Range<u32>, replacement: &str) {
+        let range = TextRange::new(TextSize::from(range.start), TextSize::from(range.end));
+        let mut text = source.to_string();
+        text.replace_range(
+            usize::from(range.start())..usize::from(range.end()),
+            replacement,
+        );
+
+        let mut index = LineIndex::from_source_text(source);
+        index.apply_edit(range, replacement, &text);
+
+        let expected = LineIndex::from_source_text(&text);
+        assert_eq!(index.line_starts(), expected.line_starts());
+        // Removing the last non-ASCII character is allowed to keep the slower UTF8 index.
+        assert!(!index.kind().is_ascii() || expected.kind().is_ascii());
+    }
+
+    #[test]
+    fn apply_edit() {
+        // Insertions
+        assert_edit("", 0..0, "x = 1\ny = 2\n");
+        assert_edit("x = 1\ny = 2\n", 0..0, "\n");
+        assert_edit("x = 1\ny = 2\n", 6..6, "z = 3\n");
+        assert_edit("x = 1\ny = 2\n", 12..12, "z = 3");
+        assert_edit("x = 1\ny = 2\n", 3..3, "\n\n");
+
+        // Deletions
+        assert_edit("x = 1\ny = 2\nz = 3\n", 5..12, "");
+        assert_edit("x = 1\ny = 2\nz = 3\n", 0..18, "");
+        assert_edit("x = 1\ny = 2\nz = 3\n", 0..6, "");
+
+        // Replacements
+        assert_edit("x = 1\ny = 2\nz = 3\n", 4..11, "[\n    1,\n    2,\n]");
+        assert_edit("x = 1\ny = 2\nz = 3\n", 6..11, "w = 4");
+    }
+
+    #[test]
+    fn apply_edit_carriage_return() {
+        // Split a `\r\n` into two line breaks.
+        assert_edit("x = 1\r\ny = 2\r\n", 6..6, "z = 3");
+        // Join a `\r` and a `\n` into a single line break.
+        assert_edit("x = 1\rz = 3\ny = 2", 6..11, "");
+        assert_edit("x = 1\r", 6..6, "\n");
+        assert_edit("x = 1\n", 5..5, "\r");
+        // Replace a line break at the end of the replacement.
+        assert_edit("x = 1\r\ny = 2\r\n", 0..6, "z\r");
+    }
+
+    #[test]
+    fn apply_edit_utf8() {
+        assert_edit("x = 1\ny = 2\n", 4..5, "'🫣'");
+        assert_edit("x = '🫣'\ny = 2\n", 4..10, "1");
+        assert_edit("x = '🫣'\ny = 2\n", 11..16, "z = '🫣'");
+    }
 }
@@ -0,0 +1,101 @@
+use python_ast::name::Name;
+use ruff_index::newtype_index;
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::scope::ScopeId;
+
+/// Id uniquely identifying a [`Binding`] in a [`SemanticModel`](crate::SemanticModel).
+#[newtype_index]
+#[derive(PartialOrd, Ord)]
+pub struct BindingId;
+
+/// A name bound to a value in a [`Scope`](crate::Scope).
+#[derive(Debug, Clone)]
+pub struct Binding {
+    /// The name being bound.
+    pub name: Name,
+    /// What created the binding.
+    pub kind: BindingKind,
+    /// The range of the bound identifier, e.g. `x` in `x = 1` or `foo` in `def foo(): ...`.
+    pub range: TextRange,
+    /// The range of the node defining the binding, e.g. the whole function definition for
+    /// `def foo(): ...`.
+    pub definition_range: TextRange,
+    /// The scope the binding belongs to. This may differ from the scope in which the binding
+    /// was created if the name was declared `global` or `nonlocal`.
+    pub scope: ScopeId,
+}
+
+impl Binding {
+    /// Returns `true` if the binding introduces a name through an import statement.
+    pub const fn is_import(&self) -> bool {
+        matches!(
+            self.kind,
+            BindingKind::Import(_) | BindingKind::FromImport(_)
+        )
+    }
+}
+
+impl Ranged for Binding {
+    fn range(&self) -> TextRange {
+        self.range
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, is_macro::Is)]
+pub enum BindingKind {
+    /// A binding created by an assignment, e.g. `x = 1`, `x += 1` or `x, y = 1, 2`.
+    Assignment,
+    /// A binding created by an annotated assignment with a value, e.g. `x: int = 1`.
+    AnnotatedAssignment,
+    /// An annotation without a value, e.g. `x: int`. The name isn't bound to any value.
+    Annotation,
+    /// A binding created by an assignment expression, e.g. `(x := 1)`.
+    NamedExprAssignment,
+    /// The target of a `for` loop, e.g. `x` in `for x in range(10)`.
+    LoopVar,
+    /// The target of a comprehension, e.g. `x` in `[x for x in range(10)]`.
+    ComprehensionVar,
+    /// The target of a `with` item, e.g. `f` in `with open(path) as f`.
+    WithItemVar,
+    /// The name bound by an exception handler, e.g. `e` in `except ValueError as e`.
+    BoundException,
+    /// A name captured by a `match` pattern, e.g. `x` in `case [x, *rest]`.
+    MatchCapture,
+    /// A function parameter, including the parameters of a lambda.
+    Parameter,
+    /// A PEP 695 type parameter, e.g. `T` in `def foo[T](x: T): ...`.
+    TypeParam,
+    /// A PEP 695 type alias, e.g. `Alias` in `type Alias = int`.
+    TypeAlias,
+    /// A function definition, holding the scope of the function's body.
+    FunctionDefinition(ScopeId),
+    /// A class definition, holding the scope of the class's body.
+    ClassDefinition(ScopeId),
+    /// A module imported with `import foo.bar` or `import foo.bar as baz`.
+    Import(Import),
+    /// A member imported with `from foo import bar` or `from foo import bar as baz`.
+    FromImport(FromImport),
+    /// The deletion of a name, e.g. `del x`.
+    Deletion,
+}
+
+/// A binding created by an `import` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// The full name of the imported module, e.g. `foo.bar` for `import foo.bar`.
+    ///
+    /// Note that without an alias, only the first segment (`foo`) is bound.
+    pub qualified_name: Name,
+}
+
+/// A binding created by a `from ... import` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FromImport {
+    /// The module the member is imported from, e.g. `foo` for `from .foo import bar`.
+    pub module: Option<Name>,
+    /// The number of leading dots of a relative import.
+    pub level: u32,
+    /// The name of the imported member, e.g. `bar` for `from foo import bar as baz`.
+    pub member: Name,
+}
@@ -0,0 +1,598 @@
+use python_ast::name::Name;
+use python_ast::visitor::{self, Visitor};
+use python_ast::{
+    self as ast, Comprehension, ExceptHandler, Expr, ModModule, Parameters, Pattern, Stmt,
+    TypeParam,
+};
+use ruff_index::IndexVec;
+use ruff_text_size::{Ranged, TextLen, TextRange};
+
+use crate::binding::{Binding, BindingId, BindingKind, FromImport, Import};
+use crate::model::SemanticModel;
+use crate::scope::{Declaration, DeclarationKind, Scope, ScopeId, ScopeKind, StarImport};
+
+/// Builds a [`SemanticModel`] by walking a module in evaluation order.
+pub(crate) struct SemanticModelBuilder {
+    scopes: IndexVec<ScopeId, Scope>,
+    bindings: IndexVec<BindingId, Binding>,
+    current_scope: ScopeId,
+    /// The binding created for the names stored while visiting an assignment target.
+    target: Option<Target>,
+}
+
+#[derive(Clone)]
+struct Target {
+    kind: BindingKind,
+    definition_range: TextRange,
+}
+
+impl SemanticModelBuilder {
+    pub(crate) fn new(module: &ModModule) -> Self {
+        let mut scopes = IndexVec::new();
+        let current_scope = scopes.push(Scope::new(ScopeKind::Module, None, module.range));
+
+        Self {
+            scopes,
+            bindings: IndexVec::new(),
+            current_scope,
+            target: None,
+        }
+    }
+
+    pub(crate) fn build(mut self, module: &ModModule) -> SemanticModel {
+        self.visit_body(&module.body);
+
+        SemanticModel {
+            scopes: self.scopes,
+            bindings: self.bindings,
+        }
+    }
+
+    fn push_scope(&mut self, kind: ScopeKind, range: TextRange) -> ScopeId {
+        let id = self
+            .scopes
+            .push(Scope::new(kind, Some(self.current_scope), range));
+        self.current_scope = id;
+        id
+    }
+
+    fn pop_scope(&mut self) {
+        self.current_scope = self.scopes[self.current_scope]
+            .parent
+            .expect("Attempted to pop the module scope");
+    }
+
+    fn add_binding(
+        &mut self,
+        name: &Name,
+        kind: BindingKind,
+        range: TextRange,
+        definition_range: TextRange,
+    ) -> BindingId {
+        let scope = self.binding_scope(name, kind.is_named_expr_assignment());
+        let id = self.bindings.push(Binding {
+            name: name.clone(),
+            kind,
+            range,
+            definition_range,
+            scope,
+        });
+        self.scopes[scope].add_binding(name.clone(), id);
+        id
+    }
+
+    /// Returns the scope a binding of `name` created in the current scope belongs to.
+    fn binding_scope(&self, name: &str, is_named_expr: bool) -> ScopeId {
+        let mut scope_id = self.current_scope;
+
+        // Assignment expressions in a comprehension bind in the enclosing scope (PEP 572).
+        if is_named_expr {
+            while self.scopes[scope_id].kind.is_comprehension() {
+                let Some(parent) = self.scopes[scope_id].parent else {
+                    break;
+                };
+                scope_id = parent;
+            }
+        }
+
+        let scope = &self.scopes[scope_id];
+        match scope.declaration(name).map(|declaration| declaration.kind) {
+            Some(DeclarationKind::Global) => ScopeId::global(),
+            Some(DeclarationKind::Nonlocal) => {
+                let mut enclosing_functions =
+                    std::iter::successors(scope.parent, |&id| self.scopes[id].parent).filter(
+                        |&id| {
+                            matches!(
+                                self.scopes[id].kind,
+                                ScopeKind::Function | ScopeKind::Lambda
+                            )
+                        },
+                    );
+                let nearest = enclosing_functions.clone().next();
+
+                // Skip the functions that redirect the name further out themselves.
+                enclosing_functions
+                    .find(|&id| {
+                        let scope = &self.scopes[id];
+                        scope.has(name) && scope.declaration(name).is_none()
+                    })
+                    .or(nearest)
+                    .unwrap_or(scope_id)
+            }
+            None => scope_id,
+        }
+    }
+
+    /// Visits an assignment target, binding the stored names to `kind`.
+    fn visit_target(&mut self, target: &Expr, kind: BindingKind, definition_range: TextRange) {
+        let previous = self.target.replace(Target {
+            kind,
+            definition_range,
+        });
+        self.visit_expr(target);
+        self.target = previous;
+    }
+
+    fn visit_function_def(&mut self, function_def: &ast::FunctionDefStmt) {
+        let ast::FunctionDefStmt {
+            decorator_list,
+            name,
+            type_params,
+            parameters,
+            returns,
+            body,
+            range,
+            ..
+        } = function_def;
+
+        for decorator in decorator_list {
+            self.visit_decorator(decorator);
+        }
+
+        // Default values are evaluated in the enclosing scope.
+        for parameter in parameters.iter_non_variadic_params() {
+            if let Some(default) = &parameter.default {
+                self.visit_expr(default);
+            }
+        }
+
+        if let Some(type_params) = type_params {
+            self.push_scope(
+                ScopeKind::TypeParams,
+                TextRange::new(type_params.start(), range.end()),
+            );
+            self.visit_type_params(type_params);
+        }
+
+        for parameter in parameters.iter() {
+            if let Some(annotation) = parameter.annotation() {
+                self.visit_annotation(annotation);
+            }
+        }
+        if let Some(returns) = returns {
+            self.visit_annotation(returns);
+        }
+
+        let signature_end = returns
+            .as_ref()
+            .map_or(parameters.end(), |returns| returns.end());
+        let scope = self.push_scope(
+            ScopeKind::Function,
+            TextRange::new(signature_end, range.end()),
+        );
+        self.bind_parameters(parameters);
+        self.visit_body(body);
+        self.pop_scope();
+
+        if type_params.is_some() {
+            self.pop_scope();
+        }
+
+        self.add_binding(
+            &name.id,
+            BindingKind::FunctionDefinition(scope),
+            name.range,
+            *range,
+        );
+    }
+
+    fn visit_class_def(&mut self, class_def: &ast::ClassDefStmt) {
+        let ast::ClassDefStmt {
+            decorator_list,
+            name,
+            type_params,
+            arguments,
+            body,
+            range,
+        } = class_def;
+
+        for decorator in decorator_list {
+            self.visit_decorator(decorator);
+        }
+
+        if let Some(type_params) = type_params {
+            self.push_scope(
+                ScopeKind::TypeParams,
+                TextRange::new(type_params.start(), range.end()),
+            );
+            self.visit_type_params(type_params);
+        }
+
+        if let Some(arguments) = arguments {
+            self.visit_arguments(arguments);
+        }
+
+        let header_end = arguments
+            .as_ref()
+            .map(|arguments| arguments.end())
+            .or(type_params.as_ref().map(|type_params| type_params.end()))
+            .unwrap_or(name.end());
+        let scope = self.push_scope(ScopeKind::Class, TextRange::new(header_end, range.end()));
+        self.visit_body(body);
+        self.pop_scope();
+
+        if type_params.is_some() {
+            self.pop_scope();
+        }
+
+        self.add_binding(
+            &name.id,
+            BindingKind::ClassDefinition(scope),
+            name.range,
+            *range,
+        );
+    }
+
+    fn bind_parameters(&mut self, parameters: &Parameters) {
+        for parameter in parameters.iter() {
+            let parameter = parameter.as_parameter();
+            self.add_binding(
+                &parameter.name.id,
+                BindingKind::Parameter,
+                parameter.name.range,
+                parameter.range,
+            );
+        }
+    }
+
+    /// Visits the generators of a comprehension and its elements in a new comprehension scope.
+    fn visit_generators(
+        &mut self,
+        generators: &[Comprehension],
+        elements: &[&Expr],
+        range: TextRange,
+    ) {
+        let Some(first) = generators.first() else {
+            for element in elements {
+                self.visit_expr(element);
+            }
+            return;
+        };
+
+        // The first iterable is evaluated in the enclosing scope.
+        self.visit_expr(&first.iter);
+        let scope = self.push_scope(ScopeKind::Comprehension, range);
+        self.scopes[scope].exclude(first.iter.range());
+
+        for (index, generator) in generators.iter().enumerate() {
+            if index > 0 {
+                self.visit_expr(&generator.iter);
+            }
+            self.visit_target(
+                &generator.target,
+                BindingKind::ComprehensionVar,
+                generator.range,
+            );
+            for condition in &generator.ifs {
+                self.visit_expr(condition);
+            }
+        }
+
+        for element in elements {
+            self.visit_expr(element);
+        }
+
+        self.pop_scope();
+    }
+}
+
+impl<'a> Visitor<'a> for SemanticModelBuilder {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::FunctionDef(function_def) => self.visit_function_def(function_def),
+            Stmt::ClassDef(class_def) => self.visit_class_def(class_def),
+            Stmt::TypeAlias(ast::TypeAliasStmt {
+                name,
+                type_params,
+                value,
+                range,
+            }) => {
+                if let Some(type_params) = type_params {
+                    self.push_scope(
+                        ScopeKind::TypeParams,
+                        TextRange::new(type_params.start(), range.end()),
+                    );
+                    self.visit_type_params(type_params);
+                }
+                self.visit_expr(value);
+                if type_params.is_some() {
+                    self.pop_scope();
+                }
+
+                self.visit_target(name, BindingKind::TypeAlias, *range);
+            }
+            Stmt::Assign(ast::AssignStmt {
+                targets,
+                value,
+                range,
+            }) => {
+                self.visit_expr(value);
+                for target in targets {
+                    self.visit_target(target, BindingKind::Assignment, *range);
+                }
+            }
+            Stmt::AugAssign(ast::AugAssignStmt {
+                target,
+                value,
+                range,
+                ..
+            }) => {
+                self.visit_expr(value);
+                self.visit_target(target, BindingKind::Assignment, *range);
+            }
+            Stmt::AnnAssign(ast::AnnAssignStmt {
+                target,
+                annotation,
+                value,
+                range,
+                ..
+            }) => {
+                if let Some(value) = value {
+                    self.visit_expr(value);
+                }
+                self.visit_annotation(annotation);
+
+                let kind = if value.is_some() {
+                    BindingKind::AnnotatedAssignment
+                } else {
+                    BindingKind::Annotation
+                };
+                self.visit_target(target, kind, *range);
+            }
+            Stmt::For(ast::ForStmt {
+                target,
+                iter,
+                body,
+                orelse,
+                range,
+                ..
+            }) => {
+                self.visit_expr(iter);
+                self.visit_target(
+                    target,
+                    BindingKind::LoopVar,
+                    TextRange::new(range.start(), iter.end()),
+                );
+                self.visit_body(body);
+                self.visit_body(orelse);
+            }
+            Stmt::With(ast::WithStmt { items, body, .. }) => {
+                for item in items {
+                    self.visit_expr(&item.context_expr);
+                    if let Some(optional_vars) = &item.optional_vars {
+                        self.visit_target(optional_vars, BindingKind::WithItemVar, item.range);
+                    }
+                }
+                self.visit_body(body);
+            }
+            Stmt::If(ast::IfStmt {
+                test,
+                body,
+                elif_else_clauses,
+                ..
+            }) => {
+                self.visit_expr(test);
+                self.visit_body(body);
+                for clause in elif_else_clauses {
+                    self.visit_elif_else_clause(clause);
+                }
+            }
+            Stmt::Delete(ast::DeleteStmt { targets, range }) => {
+                for target in targets {
+                    self.visit_target(target, BindingKind::Deletion, *range);
+                }
+            }
+            Stmt::Import(ast::ImportStmt { names, .. }) => {
+                for alias in names {
+                    let kind = BindingKind::Import(Import {
+                        qualified_name: alias.name.id.clone(),
+                    });
+
+                    if let Some(asname) = &alias.asname {
+                        self.add_binding(&asname.id, kind, asname.range, alias.range);
+                    } else {
+                        // `import foo.bar` binds `foo`.
+                        let module = alias.name.split('.').next().unwrap_or_default();
+                        self.add_binding(
+                            &Name::new(module),
+                            kind,
+                            TextRange::at(alias.name.start(), module.text_len()),
+                            alias.range,
+                        );
+                    }
+                }
+            }
+            Stmt::ImportFrom(ast::ImportFromStmt {
+                module,
+                names,
+                level,
+                range,
+            }) => {
+                let module = module.as_ref().map(|module| module.id.clone());
+
+                for alias in names {
+                    if alias.name.as_str() == "*" {
+                        self.scopes[self.current_scope].add_star_import(StarImport {
+                            module: module.clone(),
+                            level: *level,
+                            range: *range,
+                        });
+                        continue;
+                    }
+
+                    let kind = BindingKind::FromImport(FromImport {
+                        module: module.clone(),
+                        level: *level,
+                        member: alias.name.id.clone(),
+                    });
+                    let bound = alias.asname.as_ref().unwrap_or(&alias.name);
+                    self.add_binding(&bound.id, kind, bound.range, alias.range);
+                }
+            }
+            Stmt::Global(ast::GlobalStmt { names, .. }) => {
+                for name in names {
+                    self.scopes[self.current_scope].add_declaration(
+                        name.id.clone(),
+                        Declaration {
+                            kind: DeclarationKind::Global,
+                            range: name.range,
+                        },
+                    );
+                }
+            }
+            Stmt::Nonlocal(ast::NonlocalStmt { names, .. }) => {
+                for name in names {
+                    self.scopes[self.current_scope].add_declaration(
+                        name.id.clone(),
+                        Declaration {
+                            kind: DeclarationKind::Nonlocal,
+                            range: name.range,
+                        },
+                    );
+                }
+            }
+            _ => visitor::walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'a Expr) {
+        match expr {
+            Expr::Name(ast::NameExpr { id, ctx, range }) => {
+                if ctx.is_store() || ctx.is_del() {
+                    let Target {
+                        kind,
+                        definition_range,
+                    } = self.target.clone().unwrap_or(Target {
+                        kind: BindingKind::Assignment,
+                        definition_range: *range,
+                    });
+                    self.add_binding(id, kind, *range, definition_range);
+                }
+            }
+            Expr::Named(ast::NamedExpr {
+                target,
+                value,
+                range,
+            }) => {
+                self.visit_expr(value);
+                self.visit_target(target, BindingKind::NamedExprAssignment, *range);
+            }
+            Expr::Lambda(ast::LambdaExpr {
+                parameters, body, ..
+            }) => {
+                if let Some(parameters) = parameters {
+                    for parameter in parameters.iter_non_variadic_params() {
+                        if let Some(default) = &parameter.default {
+                            self.visit_expr(default);
+                        }
+                    }
+                }
+
+                self.push_scope(ScopeKind::Lambda, body.range());
+                if let Some(parameters) = parameters {
+                    self.bind_parameters(parameters);
+                }
+                self.visit_expr(body);
+                self.pop_scope();
+            }
+            Expr::ListComp(ast::ListCompExpr {
+                elt,
+                generators,
+                range,
+            })
+            | Expr::SetComp(ast::SetCompExpr {
+                elt,
+                generators,
+                range,
+            })
+            | Expr::Generator(ast::GeneratorExpr {
+                elt,
+                generators,
+                range,
+                ..
+            }) => self.visit_generators(generators, &[elt], *range),
+            Expr::DictComp(ast::DictCompExpr {
+                key,
+                value,
+                generators,
+                range,
+            }) => self.visit_generators(generators, &[key, value], *range),
+            _ => visitor::walk_expr(self, expr),
+        }
+    }
+
+    fn visit_type_param(&mut self, type_param: &'a TypeParam) {
+        let (TypeParam::TypeVar(ast::TypeParamTypeVar { name, range, .. })
+        | TypeParam::TypeVarTuple(ast::TypeParamTypeVarTuple { name, range, .. })
+        | TypeParam::ParamSpec(ast::TypeParamParamSpec { name, range, .. })) = type_param;
+
+        self.add_binding(&name.id, BindingKind::TypeParam, name.range, *range);
+        visitor::walk_type_param(self, type_param);
+    }
+
+    fn visit_except_handler(&mut self, except_handler: &'a ExceptHandler) {
+        let ExceptHandler::ExceptHandler(ast::ExceptHandlerExceptHandler {
+            type_,
+            name,
+            body,
+            range,
+        }) = except_handler;
+
+        if let Some(type_) = type_ {
+            self.visit_expr(type_);
+        }
+        if let Some(name) = name {
+            self.add_binding(
+                &name.id,
+                BindingKind::BoundException,
+                name.range,
+                TextRange::new(range.start(), name.end()),
+            );
+        }
+        self.visit_body(body);
+    }
+
+    fn visit_pattern(&mut self, pattern: &'a Pattern) {
+        visitor::walk_pattern(self, pattern);
+
+        let (Pattern::MatchAs(ast::PatternMatchAs {
+            name: Some(name),
+            range,
+            ..
+        })
+        | Pattern::MatchStar(ast::PatternMatchStar {
+            name: Some(name),
+            range,
+        })
+        | Pattern::MatchMapping(ast::PatternMatchMapping {
+            rest: Some(name),
+            range,
+            ..
+        })) = pattern
+        else {
+            return;
+        };
+
+        self.add_binding(&name.id, BindingKind::MatchCapture, name.range, *range);
+    }
+}
@@ -0,0 +1,10 @@
+pub use binding::{Binding, BindingId, BindingKind, FromImport, Import};
+pub use model::SemanticModel;
+pub use scope::{Declaration, DeclarationKind, Scope, ScopeId, ScopeKind, StarImport};
+
+mod binding;
+mod builder;
+pub mod builtins;
+pub mod db;
+mod model;
+mod scope;
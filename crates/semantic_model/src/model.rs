@@ -0,0 +1,401 @@
+use python_ast::ModModule;
+use ruff_index::IndexVec;
+use ruff_text_size::TextSize;
+
+use crate::binding::{Binding, BindingId};
+use crate::builder::SemanticModelBuilder;
+use crate::scope::{DeclarationKind, Scope, ScopeId};
+
+/// The scopes and bindings of a single Python module.
+#[derive(Debug, Clone)]
+pub struct SemanticModel {
+    pub(crate) scopes: IndexVec<ScopeId, Scope>,
+    pub(crate) bindings: IndexVec<BindingId, Binding>,
+}
+
+impl SemanticModel {
+    /// Builds the semantic model of `module`.
+    pub fn new(module: &ModModule) -> Self {
+        SemanticModelBuilder::new(module).build(module)
+    }
+
+    /// Returns the module scope.
+    pub fn global_scope(&self) -> &Scope {
+        &self.scopes[ScopeId::global()]
+    }
+
+    pub fn scope(&self, id: ScopeId) -> &Scope {
+        &self.scopes[id]
+    }
+
+    /// Returns an iterator over all the scopes of the module. Enclosing scopes always come
+    /// before the scopes nested inside of them.
+    pub fn scopes(&self) -> impl Iterator<Item = (ScopeId, &Scope)> + '_ {
+        self.scopes.iter_enumerated()
+    }
+
+    pub fn binding(&self, id: BindingId) -> &Binding {
+        &self.bindings[id]
+    }
+
+    /// Returns an iterator over all the bindings of the module, in the order they were created.
+    pub fn bindings(&self) -> impl Iterator<Item = (BindingId, &Binding)> + '_ {
+        self.bindings.iter_enumerated()
+    }
+
+    /// Returns an iterator over `scope` and all of its enclosing scopes, innermost first.
+    pub fn ancestors(&self, scope: ScopeId) -> impl Iterator<Item = ScopeId> + '_ {
+        std::iter::successors(Some(scope), |&id| self.scopes[id].parent)
+    }
+
+    /// Returns the innermost scope containing `offset`.
+    pub fn scope_at(&self, offset: TextSize) -> ScopeId {
+        // Scopes don't necessarily nest in creation order, e.g. a lambda used as a parameter
+        // default is created before the function it appears in. The smallest scope containing
+        // the offset is the innermost one.
+        self.scopes
+            .iter_enumerated()
+            .filter(|(_, scope)| scope.contains(offset))
+            .min_by_key(|(id, scope)| (scope.range.len(), std::cmp::Reverse(*id)))
+            .map_or(ScopeId::global(), |(id, _)| id)
+    }
+
+    /// Resolves a reference to `name` at `offset` in `scope`, following Python's scoping rules:
+    /// class scopes aren't visible from nested scopes and `global` and `nonlocal` declarations
+    /// redirect the lookup to the module or the enclosing function.
+    ///
+    /// If a scope binds the name multiple times, the last binding before `offset` is returned,
+    /// falling back to the first one if all of them come after `offset`.
+    pub fn lookup(&self, name: &str, scope: ScopeId, offset: TextSize) -> Option<BindingId> {
+        let mut current = Some(scope);
+        let mut is_innermost = true;
+
+        while let Some(id) = current {
+            let scope = &self.scopes[id];
+            current = scope.parent;
+
+            // Names bound in a class body aren't accessible from the scopes nested inside it.
+            if scope.kind.is_class() && !is_innermost {
+                continue;
+            }
+            is_innermost = false;
+
+            match scope.declaration(name).map(|declaration| declaration.kind) {
+                Some(DeclarationKind::Global) => {
+                    return self.binding_before(self.global_scope(), name, offset);
+                }
+                Some(DeclarationKind::Nonlocal) => continue,
+                None => {}
+            }
+
+            if let Some(binding) = self.binding_before(scope, name, offset) {
+                return Some(binding);
+            }
+        }
+
+        None
+    }
+
+    fn binding_before(&self, scope: &Scope, name: &str, offset: TextSize) -> Option<BindingId> {
+        let bindings = scope.get_all(name);
+        bindings
+            .iter()
+            .rev()
+            .find(|&&id| self.bindings[id].range.start() <= offset)
+            .or_else(|| bindings.first())
+            .copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use python_parser::parse_module;
+    use ruff_text_size::TextSize;
+
+    use crate::binding::{BindingKind, FromImport, Import};
+    use crate::scope::{DeclarationKind, ScopeId, ScopeKind};
+
+    use super::SemanticModel;
+
+    fn model(source: &str) -> SemanticModel {
+        let parsed = parse_module(source);
+        assert!(parsed.is_valid());
+        SemanticModel::new(parsed.syntax())
+    }
+
+    fn offset(source: &str, needle: &str) -> TextSize {
+        TextSize::try_from(source.find(needle).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn nested_scopes() {
+        let source = r#"
+class Foo[T]:
+    def method(self, x=lambda y: y):
+        return [z for z in range(10)]
+"#;
+        let model = model(source);
+
+        let kinds: Vec<_> = model.scopes().map(|(_, scope)| scope.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ScopeKind::Module,
+                ScopeKind::TypeParams,
+                ScopeKind::Class,
+                ScopeKind::Lambda,
+                ScopeKind::Function,
+                ScopeKind::Comprehension,
+            ]
+        );
+
+        let comprehension = model.scope_at(offset(source, "z for"));
+        assert_eq!(model.scope(comprehension).kind, ScopeKind::Comprehension);
+        let ancestors: Vec<_> = model
+            .ancestors(comprehension)
+            .map(|id| model.scope(id).kind)
+            .collect();
+        assert_eq!(
+            ancestors,
+            vec![
+                ScopeKind::Comprehension,
+                ScopeKind::Function,
+                ScopeKind::Class,
+                ScopeKind::TypeParams,
+                ScopeKind::Module,
+            ]
+        );
+    }
+
+    #[test]
+    fn bindings() {
+        let source = r#"
+import os.path
+from . import utils as u
+from typing import *
+
+def foo(a, /, b: int = 1, *args, c, **kwargs) -> None:
+    x: int
+    y: int = 2
+    for i in range(10):
+        pass
+    with open(a) as f:
+        pass
+    try:
+        pass
+    except ValueError as e:
+        del e
+
+class Bar:
+    attr = 1
+"#;
+        let model = model(source);
+        let global = model.global_scope();
+
+        let os = model.binding(global.get("os").unwrap());
+        assert_eq!(
+            os.kind,
+            BindingKind::Import(Import {
+                qualified_name: "os.path".into()
+            })
+        );
+        assert_eq!(&source[os.range], "os");
+        assert_eq!(&source[os.definition_range], "os.path");
+
+        let utils = model.binding(global.get("u").unwrap());
+        assert_eq!(
+            utils.kind,
+            BindingKind::FromImport(FromImport {
+                module: None,
+                level: 1,
+                member: "utils".into()
+            })
+        );
+        assert_eq!(global.star_imports().len(), 1);
+
+        let foo = model.binding(global.get("foo").unwrap());
+        let BindingKind::FunctionDefinition(function_scope) = foo.kind else {
+            panic!("expected a function definition, got {:?}", foo.kind);
+        };
+        assert_eq!(&source[foo.range], "foo");
+        assert!(source[foo.definition_range].starts_with("def foo"));
+
+        let function_scope = model.scope(function_scope);
+        let kind = |name: &str| &model.binding(function_scope.get(name).unwrap()).kind;
+        for parameter in ["a", "b", "args", "c", "kwargs"] {
+            assert_eq!(kind(parameter), &BindingKind::Parameter);
+        }
+        assert_eq!(kind("x"), &BindingKind::Annotation);
+        assert_eq!(kind("y"), &BindingKind::AnnotatedAssignment);
+        assert_eq!(kind("i"), &BindingKind::LoopVar);
+        assert_eq!(kind("f"), &BindingKind::WithItemVar);
+        assert_eq!(kind("e"), &BindingKind::Deletion);
+        assert_eq!(
+            function_scope
+                .get_all("e")
+                .iter()
+                .map(|&id| &model.binding(id).kind)
+                .collect::<Vec<_>>(),
+            vec![&BindingKind::BoundException, &BindingKind::Deletion]
+        );
+
+        let bar = model.binding(global.get("Bar").unwrap());
+        let BindingKind::ClassDefinition(class_scope) = bar.kind else {
+            panic!("expected a class definition, got {:?}", bar.kind);
+        };
+        assert!(model.scope(class_scope).has("attr"));
+        assert!(!global.has("attr"));
+    }
+
+    #[test]
+    fn global_and_nonlocal() {
+        let source = r#"
+def outer():
+    counter = 0
+
+    def inner():
+        nonlocal counter
+        global total
+        counter += 1
+        total = counter
+
+    return inner
+"#;
+        let model = model(source);
+        let global = model.global_scope();
+
+        let total = model.binding(global.get("total").unwrap());
+        assert_eq!(total.kind, BindingKind::Assignment);
+        assert_eq!(total.scope, ScopeId::global());
+
+        let outer = model.binding(global.get("outer").unwrap());
+        let BindingKind::FunctionDefinition(outer_scope) = outer.kind else {
+            panic!("expected a function definition, got {:?}", outer.kind);
+        };
+        assert_eq!(model.scope(outer_scope).get_all("counter").len(), 2);
+
+        let inner = model.scope_at(offset(source, "counter += 1"));
+        let inner_scope = model.scope(inner);
+        assert!(!inner_scope.has("counter"));
+        assert!(!inner_scope.has("total"));
+        assert_eq!(
+            inner_scope.declaration("counter").map(|d| d.kind),
+            Some(DeclarationKind::Nonlocal)
+        );
+
+        let counter = model
+            .lookup("counter", inner, offset(source, "counter\n\n    return"))
+            .unwrap();
+        assert_eq!(model.binding(counter).scope, outer_scope);
+    }
+
+    #[test]
+    fn lookup_follows_python_scoping() {
+        let source = r#"
+x = 1
+
+class Foo:
+    x = 2
+    y = [x for _ in range(3)]
+    z = [i for i in y]
+
+    def method(self):
+        return x
+
+x = 3
+print(x)
+"#;
+        let model = model(source);
+
+        // Class scopes are skipped when resolving names from nested scopes.
+        let in_method = offset(source, "x\n\nx = 3");
+        let binding = model
+            .lookup("x", model.scope_at(in_method), in_method)
+            .unwrap();
+        assert_eq!(model.binding(binding).scope, ScopeId::global());
+
+        let in_comprehension = offset(source, "x for");
+        let binding = model
+            .lookup("x", model.scope_at(in_comprehension), in_comprehension)
+            .unwrap();
+        assert_eq!(model.binding(binding).scope, ScopeId::global());
+
+        // The class body sees its own bindings.
+        let in_class = offset(source, "y = ");
+        let binding = model
+            .lookup("x", model.scope_at(in_class), in_class)
+            .unwrap();
+        assert_eq!(&source[model.binding(binding).definition_range], "x = 2");
+
+        // The first iterable of a comprehension is evaluated in the enclosing scope.
+        let in_iterable = offset(source, "y]");
+        let binding = model
+            .lookup("y", model.scope_at(in_iterable), in_iterable)
+            .unwrap();
+        assert_eq!(
+            model.scope(model.binding(binding).scope).kind,
+            ScopeKind::Class
+        );
+
+        // The most recent binding before the reference wins.
+        let at_print = offset(source, "x)");
+        let binding = model
+            .lookup("x", model.scope_at(at_print), at_print)
+            .unwrap();
+        assert_eq!(&source[model.binding(binding).definition_range], "x = 3");
+
+        assert_eq!(model.lookup("print", ScopeId::global(), at_print), None);
+    }
+
+    #[test]
+    fn comprehension_scopes() {
+        let source = r#"
+values = [y := x for x in range(10) if x]
+pairs = {k: v for k, v in items}
+"#;
+        let model = model(source);
+        let global = model.global_scope();
+
+        // Assignment expressions bind in the enclosing scope of the comprehension.
+        assert_eq!(
+            model.binding(global.get("y").unwrap()).kind,
+            BindingKind::NamedExprAssignment
+        );
+        assert!(!global.has("x"));
+        assert!(!global.has("k"));
+
+        let comprehension = model.scope(model.scope_at(offset(source, "k: v")));
+        assert_eq!(comprehension.kind, ScopeKind::Comprehension);
+        assert_eq!(
+            model.binding(comprehension.get("v").unwrap()).kind,
+            BindingKind::ComprehensionVar
+        );
+    }
+
+    #[test]
+    fn type_params() {
+        let source = r#"
+type Pair[T] = tuple[T, T]
+
+def first[U](pair: Pair[U]) -> U:
+    return pair[0]
+"#;
+        let model = model(source);
+        let global = model.global_scope();
+
+        assert_eq!(
+            model.binding(global.get("Pair").unwrap()).kind,
+            BindingKind::TypeAlias
+        );
+        assert!(!global.has("T"));
+        assert!(!global.has("U"));
+
+        let annotation = offset(source, "U]) ->");
+        let binding = model
+            .lookup("U", model.scope_at(annotation), annotation)
+            .unwrap();
+        let binding = model.binding(binding);
+        assert_eq!(binding.kind, BindingKind::TypeParam);
+        assert_eq!(model.scope(binding.scope).kind, ScopeKind::TypeParams);
+    }
+}
@@ -0,0 +1,160 @@
+use python_ast::name::Name;
+use ruff_index::newtype_index;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use rustc_hash::FxHashMap;
+
+use crate::binding::BindingId;
+
+/// Id uniquely identifying a [`Scope`] in a [`SemanticModel`](crate::SemanticModel).
+#[newtype_index]
+#[derive(PartialOrd, Ord)]
+pub struct ScopeId;
+
+impl ScopeId {
+    /// Returns the ID of the module scope.
+    #[inline]
+    pub const fn global() -> Self {
+        ScopeId::from_u32(0)
+    }
+
+    /// Returns `true` if this is the ID of the module scope.
+    #[inline]
+    pub const fn is_global(self) -> bool {
+        self.as_u32() == 0
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, is_macro::Is)]
+pub enum ScopeKind {
+    Module,
+    Class,
+    Function,
+    Lambda,
+    Comprehension,
+    /// The annotation scope introduced by PEP 695 type parameters, e.g. `[T]` in
+    /// `class Foo[T]: ...`.
+    TypeParams,
+}
+
+#[derive(Debug, Clone)]
+pub struct Scope {
+    pub kind: ScopeKind,
+    /// The enclosing scope, `None` for the module scope.
+    pub parent: Option<ScopeId>,
+    /// The source range covered by the scope.
+    pub range: TextRange,
+    /// A range inside `range` that's evaluated in the enclosing scope, e.g. the first iterable
+    /// of a comprehension.
+    excluded_range: Option<TextRange>,
+    /// All the bindings of a name in this scope, in source order.
+    bindings: FxHashMap<Name, Vec<BindingId>>,
+    /// Names declared `global` or `nonlocal` in this scope.
+    declarations: FxHashMap<Name, Declaration>,
+    /// The `from ... import *` statements of this scope.
+    star_imports: Vec<StarImport>,
+}
+
+impl Scope {
+    pub(crate) fn new(kind: ScopeKind, parent: Option<ScopeId>, range: TextRange) -> Self {
+        Self {
+            kind,
+            parent,
+            range,
+            excluded_range: None,
+            bindings: FxHashMap::default(),
+            declarations: FxHashMap::default(),
+            star_imports: Vec::new(),
+        }
+    }
+
+    /// Returns `true` if the code at `offset` is evaluated in this scope.
+    pub fn contains(&self, offset: TextSize) -> bool {
+        self.range.contains_inclusive(offset)
+            && !self
+                .excluded_range
+                .is_some_and(|excluded| excluded.contains_inclusive(offset))
+    }
+
+    /// Returns the most recent binding of `name` in this scope.
+    pub fn get(&self, name: &str) -> Option<BindingId> {
+        self.bindings.get(name)?.last().copied()
+    }
+
+    /// Returns all the bindings of `name` in this scope, in source order.
+    pub fn get_all(&self, name: &str) -> &[BindingId] {
+        self.bindings.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Returns `true` if `name` is bound in this scope.
+    pub fn has(&self, name: &str) -> bool {
+        self.bindings.contains_key(name)
+    }
+
+    /// Returns an iterator over the names bound in this scope and their most recent binding.
+    pub fn bindings(&self) -> impl Iterator<Item = (&str, BindingId)> + '_ {
+        self.bindings
+            .iter()
+            .filter_map(|(name, ids)| Some((name.as_str(), *ids.last()?)))
+    }
+
+    /// Returns the `global` or `nonlocal` declaration of `name` in this scope.
+    pub fn declaration(&self, name: &str) -> Option<&Declaration> {
+        self.declarations.get(name)
+    }
+
+    /// Returns an iterator over the `global` and `nonlocal` declarations of this scope.
+    pub fn declarations(&self) -> impl Iterator<Item = (&str, &Declaration)> + '_ {
+        self.declarations
+            .iter()
+            .map(|(name, declaration)| (name.as_str(), declaration))
+    }
+
+    /// Returns the `from ... import *` statements of this scope.
+    pub fn star_imports(&self) -> &[StarImport] {
+        &self.star_imports
+    }
+
+    pub(crate) fn exclude(&mut self, range: TextRange) {
+        self.excluded_range = Some(range);
+    }
+
+    pub(crate) fn add_binding(&mut self, name: Name, id: BindingId) {
+        self.bindings.entry(name).or_default().push(id);
+    }
+
+    pub(crate) fn add_declaration(&mut self, name: Name, declaration: Declaration) {
+        self.declarations.entry(name).or_insert(declaration);
+    }
+
+    pub(crate) fn add_star_import(&mut self, star_import: StarImport) {
+        self.star_imports.push(star_import);
+    }
+}
+
+impl Ranged for Scope {
+    fn range(&self) -> TextRange {
+        self.range
+    }
+}
+
+/// A `global` or `nonlocal` declaration of a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Declaration {
+    pub kind: DeclarationKind,
+    /// The range of the declared identifier in the `global` or `nonlocal` statement.
+    pub range: TextRange,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, is_macro::Is)]
+pub enum DeclarationKind {
+    Global,
+    Nonlocal,
+}
+
+/// A `from ... import *` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StarImport {
+    pub module: Option<Name>,
+    pub level: u32,
+    pub range: TextRange,
+}
@@ -0,0 +1,265 @@
+//! Semantic analysis of the Python modules in a workspace.
+
+mod auto_import;
+mod call_hierarchy;
+mod completion;
+mod definition;
+mod hover;
+mod imports;
+mod inlay_hints;
+mod organize_imports;
+mod references;
+mod rename;
+mod semantic_tokens;
+mod signature;
+mod signature_help;
+mod symbols;
+mod undefined;
+
+use std::path::{Path, PathBuf};
+
+use lsp_types::{Location, Url};
+use python_ast::{self as ast, Expr, ModModule, PySourceType, Stmt, StringFlags};
+use python_parser::{ParseError, Parsed};
+use ruff_source_file::LineIndex;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use semantic_model::SemanticModel;
+
+use crate::edit::{Document, ToLocation};
+use crate::PositionEncoding;
+
+pub(crate) use auto_import::{import_fixes, ImportFix};
+pub(crate) use call_hierarchy::{
+    incoming_calls, outgoing_calls, prepare_call_hierarchy, CallHierarchyItem, Calls,
+};
+pub(crate) use completion::completions;
+pub(crate) use definition::{Definition, DefinitionKind, DefinitionResolver};
+pub(crate) use hover::{hover, Hover};
+pub(crate) use inlay_hints::{inlay_hints, InlayHintKind};
+pub(crate) use organize_imports::organize_imports;
+pub(crate) use references::find_references;
+pub(crate) use rename::{prepare_rename, rename, RenameError};
+pub(crate) use semantic_tokens::{semantic_tokens, TOKEN_MODIFIERS, TOKEN_TYPES};
+pub(crate) use signature_help::signature_help;
+pub(crate) use symbols::{document_symbols, Symbol};
+pub(crate) use undefined::undefined_names;
+
+/// A Python module, parsed along with its semantic model.
+pub(crate) struct ParsedModule {
+    path: PathBuf,
+    source: String,
+    index: LineIndex,
+    parsed: Parsed<ModModule>,
+    model: SemanticModel,
+}
+
+impl ParsedModule {
+    pub(crate) fn new(path: PathBuf, source: String, index: LineIndex) -> Self {
+        let parsed = python_parser::parse_unchecked_source(&source, PySourceType::from(&path));
+        let model = SemanticModel::new(parsed.syntax());
+
+        Self {
+            path,
+            source,
+            index,
+            parsed,
+            model,
+        }
+    }
+
+    /// Parses the current contents of an open document.
+    pub(crate) fn from_document(path: PathBuf, document: &Document) -> Self {
+        Self::new(
+            path,
+            document.contents().to_string(),
+            document.index().clone(),
+        )
+    }
+
+    /// Reads and parses the module at `path`, returning `None` if it can't be read.
+    pub(crate) fn read(path: &Path) -> Option<Self> {
+        let source = std::fs::read_to_string(path)
+            .inspect_err(|err| {
+                tracing::debug!("Failed to read module `{}`: {err}", path.display());
+            })
+            .ok()?;
+        let index = LineIndex::from_source_text(&source);
+
+        Some(Self::new(path.to_path_buf(), source, index))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub(crate) fn index(&self) -> &LineIndex {
+        &self.index
+    }
+
+    pub(crate) fn syntax(&self) -> &ModModule {
+        self.parsed.syntax()
+    }
+
+    /// Returns the syntax errors found while parsing the module.
+    pub(crate) fn parse_errors(&self) -> &[ParseError] {
+        self.parsed.errors()
+    }
+
+    pub(crate) fn model(&self) -> &SemanticModel {
+        &self.model
+    }
+
+    /// Returns the LSP location of `range` in this module.
+    pub(crate) fn location(
+        &self,
+        range: TextRange,
+        encoding: PositionEncoding,
+    ) -> Option<Location> {
+        let url = Url::from_file_path(&self.path).ok()?;
+        Some(range.to_location(url, &self.source, &self.index, encoding))
+    }
+
+    /// Returns the range of the contents of the strings in the module's `__all__`, e.g.
+    /// `__all__ = ["name"]`, `__all__ += ["name"]` or `__all__.append("name")`.
+    pub(crate) fn dunder_all(&self) -> Vec<TextRange> {
+        let mut entries = Vec::new();
+
+        for stmt in &self.syntax().body {
+            let exported = match stmt {
+                Stmt::Assign(ast::AssignStmt { targets, value, .. }) => {
+                    targets.iter().any(is_dunder_all).then_some(value.as_ref())
+                }
+                Stmt::AugAssign(ast::AugAssignStmt { target, value, .. }) => {
+                    is_dunder_all(target).then_some(value.as_ref())
+                }
+                Stmt::AnnAssign(ast::AnnAssignStmt {
+                    target,
+                    value: Some(value),
+                    ..
+                }) => is_dunder_all(target).then_some(value.as_ref()),
+                Stmt::Expr(ast::ExprStmt { value, .. }) => match value.as_ref() {
+                    Expr::Call(ast::CallExpr {
+                        func, arguments, ..
+                    }) => match func.as_ref() {
+                        Expr::Attribute(ast::AttributeExpr { value, attr, .. })
+                            if is_dunder_all(value)
+                                && matches!(attr.as_str(), "append" | "extend") =>
+                        {
+                            arguments.args.first()
+                        }
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            };
+
+            let elements = match exported {
+                Some(
+                    Expr::List(ast::ListExpr { elts, .. })
+                    | Expr::Tuple(ast::TupleExpr { elts, .. }),
+                ) => elts.as_slice(),
+                Some(expr @ Expr::StringLiteral(_)) => std::slice::from_ref(expr),
+                _ => continue,
+            };
+
+            for element in elements {
+                let Expr::StringLiteral(ast::StringLiteralExpr { value, .. }) = element else {
+                    continue;
+                };
+                if value.is_implicit_concatenated() {
+                    continue;
+                }
+
+                for literal in value {
+                    let contents = TextRange::new(
+                        literal.start() + literal.flags.opener_len(),
+                        literal.end() - literal.flags.closer_len(),
+                    );
+                    entries.push(contents);
+                }
+            }
+        }
+
+        entries
+    }
+}
+
+fn is_dunder_all(expr: &Expr) -> bool {
+    matches!(expr, Expr::Name(ast::NameExpr { id, .. }) if id == "__all__")
+}
+
+/// A change to the source of a module, replacing `range` with `content`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Edit {
+    pub(crate) range: TextRange,
+    pub(crate) content: String,
+}
+
+impl Edit {
+    /// Returns the edit inserting `content` at `offset`.
+    pub(crate) fn insertion(content: String, offset: TextSize) -> Self {
+        Self {
+            range: TextRange::empty(offset),
+            content,
+        }
+    }
+
+    /// Returns the edit replacing `range` with `content`.
+    pub(crate) fn replacement(content: String, range: TextRange) -> Self {
+        Self { range, content }
+    }
+
+    /// Returns the edit deleting `range`.
+    pub(crate) fn deletion(range: TextRange) -> Self {
+        Self {
+            range,
+            content: String::new(),
+        }
+    }
+}
+
+/// Returns the Python source and stub files in `root`, skipping hidden directories and the
+/// files ignored by `.gitignore`.
+pub(crate) fn python_files(root: &Path) -> Vec<PathBuf> {
+    walk_python_files(&ignore::WalkBuilder::new(root))
+}
+
+/// Returns `true` if `path` is one of the [`python_files`] of `root`, following the same
+/// ignore files, e.g. the `.gitignore` of a subdirectory, without walking the whole of `root`.
+pub(crate) fn is_workspace_python_file(root: &Path, path: &Path) -> bool {
+    if !path.starts_with(root) {
+        return false;
+    }
+
+    let target = path.to_path_buf();
+    let mut builder = ignore::WalkBuilder::new(root);
+    // Only the directories leading to `path` are read.
+    builder.filter_entry(move |entry| target.starts_with(entry.path()));
+    walk_python_files(&builder).iter().any(|file| file == path)
+}
+
+fn walk_python_files(builder: &ignore::WalkBuilder) -> Vec<PathBuf> {
+    builder
+        .build()
+        .filter_map(|entry| {
+            entry
+                .inspect_err(|err| tracing::debug!("Failed to walk the workspace: {err}"))
+                .ok()
+        })
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+        })
+        .map(ignore::DirEntry::into_path)
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "py" || extension == "pyi")
+        })
+        .collect()
+}
@@ -0,0 +1,167 @@
+//! Quick fixes importing the names that aren't defined in a module.
+
+use ruff_text_size::TextRange;
+use rustc_hash::FxHashSet;
+use semantic_model::builtins::BUILTIN_MODULES;
+
+use crate::resolve::{modules_in, ImportResolver};
+use crate::session::SymbolIndex;
+
+use super::imports::{add_import, NewImport};
+use super::{undefined_names, DefinitionResolver, Edit, ParsedModule};
+
+/// A quick fix importing the undefined name at `range`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ImportFix {
+    pub(crate) name: String,
+    pub(crate) range: TextRange,
+    pub(crate) module: String,
+    /// `true` for `from module import name`, `false` for `import module`.
+    pub(crate) is_from_import: bool,
+    pub(crate) edit: Edit,
+}
+
+/// Returns the fixes importing the undefined names in `range`, with one fix per module the
+/// name can be imported from.
+pub(crate) fn import_fixes(
+    module: &ParsedModule,
+    import_resolver: &ImportResolver,
+    symbol_index: &SymbolIndex,
+    range: TextRange,
+) -> Vec<ImportFix> {
+    let mut resolver = DefinitionResolver::new(import_resolver);
+    let names: Vec<_> = undefined_names(module, &mut resolver)
+        .into_iter()
+        .filter(|(_, name_range)| name_range.intersect(range).is_some())
+        .collect();
+    if names.is_empty() {
+        return Vec::new();
+    }
+
+    // Listing the installed packages reads their directories, do it once for every name.
+    let third_party_modules = third_party_modules(import_resolver);
+    names
+        .into_iter()
+        .flat_map(|(name, name_range)| {
+            import_candidates(module, symbol_index, &third_party_modules, &name)
+                .into_iter()
+                .map(move |import| ImportFix {
+                    edit: add_import(module, import_resolver, &import),
+                    name: name.clone(),
+                    range: name_range,
+                    module: import.module,
+                    is_from_import: import.name.is_some(),
+                })
+        })
+        .collect()
+}
+
+/// Returns the names of the top-level modules of the installed packages.
+fn third_party_modules(import_resolver: &ImportResolver) -> FxHashSet<String> {
+    import_resolver
+        .third_party_roots()
+        .iter()
+        .flat_map(|root| modules_in(root))
+        .map(|(module, _)| module)
+        .collect()
+}
+
+/// Returns the imports that would bind `name`, in order of preference: symbols of the
+/// workspace, then modules of the standard library and of the installed packages.
+fn import_candidates(
+    module: &ParsedModule,
+    symbol_index: &SymbolIndex,
+    third_party_modules: &FxHashSet<String>,
+    name: &str,
+) -> Vec<NewImport> {
+    let mut candidates: Vec<_> = symbol_index
+        .files()
+        .filter(|(path, file)| {
+            *path != module.path()
+                && !file.module.is_empty()
+                && file.symbols.iter().any(|symbol| symbol.name == name)
+        })
+        .map(|(_, file)| NewImport {
+            module: file.module.clone(),
+            name: Some(name.to_string()),
+        })
+        .collect();
+    // The index isn't ordered.
+    candidates.sort_by(|a, b| a.module.cmp(&b.module));
+
+    let is_stdlib_module = !name.starts_with('_') && BUILTIN_MODULES.contains(&name);
+    if is_stdlib_module || third_party_modules.contains(name) {
+        candidates.push(NewImport {
+            module: name.to_string(),
+            name: None,
+        });
+    }
+
+    let mut seen = FxHashSet::default();
+    candidates.retain(|candidate| seen.insert(candidate.clone()));
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::{TextRange, TextSize};
+
+    use crate::analysis::ParsedModule;
+    use crate::resolve::ImportResolver;
+    use crate::session::SymbolIndex;
+    use crate::PositionEncoding;
+
+    use super::import_fixes;
+
+    fn parse(path: std::path::PathBuf, source: &str) -> ParsedModule {
+        ParsedModule::new(
+            path,
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        )
+    }
+
+    #[test]
+    fn fixes_import_from_workspace_and_stdlib() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("pkg")).unwrap();
+        std::fs::write(root.join("pkg/__init__.py"), "").unwrap();
+        std::fs::write(root.join("pkg/util.py"), "def helper(): ...\n").unwrap();
+
+        let source = "import os\n\nhelper(json, os)\n";
+        let module = parse(root.join("main.py"), source);
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let symbol_index = SymbolIndex::build(root, PositionEncoding::UTF16);
+        let range = TextRange::new(TextSize::default(), TextSize::of(source));
+
+        let fixes: Vec<_> = import_fixes(&module, &import_resolver, &symbol_index, range)
+            .into_iter()
+            .map(|fix| {
+                let mut result = source.to_string();
+                result.replace_range(
+                    std::ops::Range::<usize>::from(fix.edit.range),
+                    &fix.edit.content,
+                );
+                (fix.name, fix.module, result)
+            })
+            .collect();
+
+        assert_eq!(
+            fixes,
+            [
+                (
+                    "helper".to_string(),
+                    "pkg.util".to_string(),
+                    "import os\n\nfrom pkg.util import helper\n\nhelper(json, os)\n".to_string()
+                ),
+                (
+                    "json".to_string(),
+                    "json".to_string(),
+                    "import os\nimport json\n\nhelper(json, os)\n".to_string()
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! The calls made to and from the functions and classes of a workspace.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use lsp_types::SymbolKind;
+use python_ast::visitor::preorder::{PreorderVisitor, TraversalSignal};
+use python_ast::{self as ast, AnyNodeRef, Expr, Stmt};
+use ruff_text_size::{TextRange, TextSize};
+use rustc_hash::{FxHashMap, FxHashSet};
+use semantic_model::{BindingId, BindingKind, ScopeId, ScopeKind};
+
+use super::definition::{defining_node, module_name};
+use super::{find_references, DefinitionKind, DefinitionResolver, ParsedModule};
+
+/// A function, class or module that calls or is called.
+#[derive(Clone)]
+pub(crate) struct CallHierarchyItem {
+    pub(crate) module: Arc<ParsedModule>,
+    pub(crate) name: String,
+    pub(crate) kind: SymbolKind,
+    /// The class of a method, or the module of a function or a class.
+    pub(crate) detail: Option<String>,
+    /// The range of the whole definition, the whole file for a module.
+    pub(crate) range: TextRange,
+    /// The range of the defined name, an empty range at the start of the file for a module.
+    pub(crate) selection_range: TextRange,
+}
+
+impl CallHierarchyItem {
+    fn new(module: &Arc<ParsedModule>, id: BindingId) -> Option<Self> {
+        let model = module.model();
+        let binding = model.binding(id);
+        let (range, kind) = match (&binding.kind, defining_node(module, binding)?) {
+            (BindingKind::FunctionDefinition(_), AnyNodeRef::StmtFunctionDef(function)) => {
+                let kind = match model.scope(binding.scope).kind {
+                    ScopeKind::Class if binding.name.as_str() == "__init__" => {
+                        SymbolKind::CONSTRUCTOR
+                    }
+                    ScopeKind::Class => SymbolKind::METHOD,
+                    _ => SymbolKind::FUNCTION,
+                };
+                (function.range, kind)
+            }
+            (BindingKind::ClassDefinition(_), AnyNodeRef::StmtClassDef(class)) => {
+                (class.range, SymbolKind::CLASS)
+            }
+            _ => return None,
+        };
+
+        let detail = if model.scope(binding.scope).kind == ScopeKind::Class {
+            defining_binding(module, binding.scope)
+                .map(|class| model.binding(class).name.to_string())
+        } else {
+            module_name(module.path()).map(ToString::to_string)
+        };
+
+        Some(Self {
+            module: module.clone(),
+            name: binding.name.to_string(),
+            kind,
+            detail,
+            range,
+            selection_range: binding.range,
+        })
+    }
+
+    fn module(module: &Arc<ParsedModule>) -> Self {
+        Self {
+            module: module.clone(),
+            name: module_name(module.path()).unwrap_or_default().to_string(),
+            kind: SymbolKind::MODULE,
+            detail: None,
+            range: TextRange::up_to(TextSize::of(module.source())),
+            selection_range: TextRange::default(),
+        }
+    }
+
+    fn key(&self) -> (PathBuf, TextRange) {
+        (self.module.path().to_path_buf(), self.selection_range)
+    }
+}
+
+/// The calls between a caller and a callee.
+pub(crate) struct Calls {
+    /// The caller of an incoming call, or the callee of an outgoing call.
+    pub(crate) item: CallHierarchyItem,
+    /// The ranges of the called names, in the caller's module.
+    pub(crate) ranges: Vec<TextRange>,
+}
+
+/// Returns the functions or classes defined or referred to at `offset` in `module`.
+pub(crate) fn prepare_call_hierarchy(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    offset: TextSize,
+) -> Vec<CallHierarchyItem> {
+    let mut seen = FxHashSet::default();
+    resolver
+        .definitions_at(module, offset)
+        .into_iter()
+        .filter_map(|definition| match definition.kind {
+            DefinitionKind::Binding(id) => CallHierarchyItem::new(&definition.module, id),
+            DefinitionKind::Module => None,
+        })
+        .filter(|item| seen.insert(item.key()))
+        .collect()
+}
+
+/// Returns the calls to the item selected at `selection_range` in `module`, grouped by the
+/// function, class or module they're made from, searching `module` and the modules at `paths`.
+pub(crate) fn incoming_calls(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    selection_range: TextRange,
+    paths: &[PathBuf],
+) -> Vec<Calls> {
+    // Modules aren't called, and a stale item may not match a function or class anymore.
+    if selection_range.is_empty() || item_node(module, selection_range).is_none() {
+        return Vec::new();
+    }
+
+    // The ranges of the names of the functions called in each module.
+    let mut called_names: FxHashMap<PathBuf, FxHashSet<TextRange>> = FxHashMap::default();
+    let mut incoming: Vec<Calls> = Vec::new();
+
+    for reference in find_references(resolver, module, selection_range.start(), paths) {
+        let caller_module = &reference.module;
+        let is_call = called_names
+            .entry(caller_module.path().to_path_buf())
+            .or_insert_with(|| {
+                calls_in(&caller_module.syntax().body, false)
+                    .into_iter()
+                    .filter_map(|call| called_name(&call.func))
+                    .collect()
+            })
+            .contains(&reference.range);
+        if !is_call {
+            continue;
+        }
+
+        let caller = caller(caller_module, reference.range.start());
+        match incoming
+            .iter_mut()
+            .find(|calls| calls.item.key() == caller.key())
+        {
+            Some(calls) => calls.ranges.push(reference.range),
+            None => incoming.push(Calls {
+                item: caller,
+                ranges: vec![reference.range],
+            }),
+        }
+    }
+
+    incoming
+}
+
+/// Returns the calls made by the item selected at `selection_range` in `module`, grouped by
+/// the called function or class.
+///
+/// The calls made by the functions and classes nested in the caller are their own. There are
+/// no calls if the item doesn't match a function or class of `module` anymore, e.g. after an
+/// edit.
+pub(crate) fn outgoing_calls(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    selection_range: TextRange,
+) -> Vec<Calls> {
+    // The items of modules have an empty selection range.
+    let body = if selection_range.is_empty() {
+        &module.syntax().body
+    } else {
+        match item_node(module, selection_range) {
+            Some(AnyNodeRef::StmtFunctionDef(function)) => &function.body,
+            Some(AnyNodeRef::StmtClassDef(class)) => &class.body,
+            _ => return Vec::new(),
+        }
+    };
+
+    let mut outgoing: Vec<Calls> = Vec::new();
+    for call in calls_in(body, true) {
+        let Some(range) = called_name(&call.func) else {
+            continue;
+        };
+        let callee = resolver
+            .resolve_expression(module, &call.func, 0)
+            .into_iter()
+            .find_map(|definition| match definition.kind {
+                DefinitionKind::Binding(id) => CallHierarchyItem::new(&definition.module, id),
+                DefinitionKind::Module => None,
+            });
+        let Some(callee) = callee else {
+            continue;
+        };
+
+        match outgoing
+            .iter_mut()
+            .find(|calls| calls.item.key() == callee.key())
+        {
+            Some(calls) => calls.ranges.push(range),
+            None => outgoing.push(Calls {
+                item: callee,
+                ranges: vec![range],
+            }),
+        }
+    }
+
+    outgoing
+}
+
+/// Returns the node defining the function or class whose name is at `selection_range` in
+/// `module`.
+fn item_node(module: &ParsedModule, selection_range: TextRange) -> Option<AnyNodeRef<'_>> {
+    module
+        .model()
+        .bindings()
+        .find(|(_, binding)| binding.range == selection_range)
+        .and_then(|(_, binding)| defining_node(module, binding))
+}
+
+/// Returns the innermost function or class containing `offset` in `module`, or the module
+/// itself at its top level.
+fn caller(module: &Arc<ParsedModule>, offset: TextSize) -> CallHierarchyItem {
+    let model = module.model();
+    model
+        .ancestors(model.scope_at(offset))
+        .find(|&id| matches!(model.scope(id).kind, ScopeKind::Function | ScopeKind::Class))
+        .and_then(|scope| defining_binding(module, scope))
+        .and_then(|id| CallHierarchyItem::new(module, id))
+        .unwrap_or_else(|| CallHierarchyItem::module(module))
+}
+
+/// Returns the binding of the function or class defining `scope`.
+fn defining_binding(module: &ParsedModule, scope: ScopeId) -> Option<BindingId> {
+    module
+        .model()
+        .bindings()
+        .find(|(_, binding)| match binding.kind {
+            BindingKind::FunctionDefinition(id) | BindingKind::ClassDefinition(id) => id == scope,
+            _ => false,
+        })
+        .map(|(id, _)| id)
+}
+
+/// Returns the range of the name a call is made through, e.g. `method` in `self.method()`.
+fn called_name(func: &Expr) -> Option<TextRange> {
+    match func {
+        Expr::Name(name) => Some(name.range),
+        Expr::Attribute(attribute) => Some(attribute.attr.range),
+        _ => None,
+    }
+}
+
+/// Returns the calls in `body`, leaving out the ones in nested functions and classes if
+/// `skip_definitions` is set.
+fn calls_in(body: &[Stmt], skip_definitions: bool) -> Vec<&ast::CallExpr> {
+    let mut collector = CallCollector {
+        calls: Vec::new(),
+        skip_definitions,
+    };
+    collector.visit_body(body);
+    collector.calls
+}
+
+struct CallCollector<'a> {
+    calls: Vec<&'a ast::CallExpr>,
+    skip_definitions: bool,
+}
+
+impl<'a> PreorderVisitor<'a> for CallCollector<'a> {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        match node {
+            AnyNodeRef::StmtFunctionDef(_) | AnyNodeRef::StmtClassDef(_)
+                if self.skip_definitions =>
+            {
+                return TraversalSignal::Skip;
+            }
+            AnyNodeRef::CallExpr(call) => self.calls.push(call),
+            _ => {}
+        }
+
+        TraversalSignal::Traverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::sync::Arc;
+
+    use lsp_types::SymbolKind;
+    use ruff_text_size::{TextRange, TextSize};
+
+    use crate::analysis::{python_files, DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+
+    use super::{incoming_calls, outgoing_calls, prepare_call_hierarchy, CallHierarchyItem, Calls};
+
+    const MAIN: &str = r#"from util import helper
+
+class Service:
+    def run(self):
+        return self.step() + helper()
+
+    def step(self):
+        return helper()
+
+def main():
+    Service().run()
+
+main()
+"#;
+
+    /// Runs `test` with the resolver of a workspace made of `MAIN` and the `util` module it
+    /// imports, the parsed `main` module and the paths of the workspace's files.
+    fn with_workspace(test: impl FnOnce(&mut DefinitionResolver, &Arc<ParsedModule>, &[PathBuf])) {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::write(root.join("util.py"), "def helper():\n    return 1\n").unwrap();
+        std::fs::write(root.join("main.py"), MAIN).unwrap();
+
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        let main = resolver.module(&root.join("main.py")).unwrap();
+        test(&mut resolver, &main, &python_files(root));
+    }
+
+    fn offset(text: &str) -> TextSize {
+        TextSize::try_from(MAIN.find(text).unwrap()).unwrap()
+    }
+
+    /// Returns the single item prepared at `text` in `MAIN`.
+    fn item_at(
+        resolver: &mut DefinitionResolver,
+        main: &Arc<ParsedModule>,
+        text: &str,
+    ) -> CallHierarchyItem {
+        let items = prepare_call_hierarchy(resolver, main, offset(text));
+        assert_eq!(items.len(), 1);
+        items.into_iter().next().unwrap()
+    }
+
+    /// Returns the name, kind and detail of each item, with the number of calls.
+    fn summary(calls: Vec<Calls>) -> Vec<(String, SymbolKind, String, usize)> {
+        calls
+            .into_iter()
+            .map(|calls| {
+                (
+                    calls.item.name,
+                    calls.item.kind,
+                    calls.item.detail.unwrap_or_default(),
+                    calls.ranges.len(),
+                )
+            })
+            .collect()
+    }
+
+    fn method(name: &str, calls: usize) -> (String, SymbolKind, String, usize) {
+        (
+            name.to_string(),
+            SymbolKind::METHOD,
+            "Service".to_string(),
+            calls,
+        )
+    }
+
+    #[test]
+    fn incoming() {
+        with_workspace(|resolver, main, paths| {
+            let helper = item_at(resolver, main, "helper()");
+            assert_eq!(helper.module.path(), main.path().with_file_name("util.py"));
+            assert_eq!(
+                (helper.name.as_str(), helper.kind, helper.detail.as_deref()),
+                ("helper", SymbolKind::FUNCTION, Some("util"))
+            );
+            let util = helper.module.clone();
+            assert_eq!(
+                summary(incoming_calls(
+                    resolver,
+                    &util,
+                    helper.selection_range,
+                    paths
+                )),
+                [method("run", 1), method("step", 1)]
+            );
+
+            let step = item_at(resolver, main, "step()");
+            assert_eq!(
+                summary(incoming_calls(resolver, main, step.selection_range, paths)),
+                [method("run", 1)]
+            );
+
+            let function = item_at(resolver, main, "main()");
+            assert_eq!(
+                summary(incoming_calls(
+                    resolver,
+                    main,
+                    function.selection_range,
+                    paths
+                )),
+                [("main".to_string(), SymbolKind::MODULE, String::new(), 1)]
+            );
+        });
+    }
+
+    #[test]
+    fn outgoing() {
+        with_workspace(|resolver, main, _| {
+            let run = item_at(resolver, main, "run");
+            assert_eq!(
+                summary(outgoing_calls(resolver, main, run.selection_range)),
+                [
+                    method("step", 1),
+                    (
+                        "helper".to_string(),
+                        SymbolKind::FUNCTION,
+                        "util".to_string(),
+                        1
+                    ),
+                ]
+            );
+
+            let function = item_at(resolver, main, "main()");
+            assert_eq!(
+                summary(outgoing_calls(resolver, main, function.selection_range)),
+                [(
+                    "Service".to_string(),
+                    SymbolKind::CLASS,
+                    "main".to_string(),
+                    1
+                )]
+            );
+        });
+    }
+
+    #[test]
+    fn module_items() {
+        with_workspace(|resolver, main, paths| {
+            let module = CallHierarchyItem::module(main);
+            assert_eq!(
+                summary(outgoing_calls(resolver, main, module.selection_range)),
+                [(
+                    "main".to_string(),
+                    SymbolKind::FUNCTION,
+                    "main".to_string(),
+                    1
+                )]
+            );
+            assert!(incoming_calls(resolver, main, module.selection_range, paths).is_empty());
+        });
+    }
+
+    #[test]
+    fn stale_items() {
+        with_workspace(|resolver, main, paths| {
+            // The range of `Service().run()`, which isn't the name of a function or class.
+            let range = TextRange::at(offset("Service().run()"), TextSize::new(15));
+            assert!(outgoing_calls(resolver, main, range).is_empty());
+            assert!(incoming_calls(resolver, main, range, paths).is_empty());
+        });
+    }
+}
@@ -0,0 +1,548 @@
+//! Completion of names, attributes and imported modules.
+
+use std::sync::Arc;
+
+use lsp_types::CompletionItemKind;
+use python_ast::{self as ast, AnyNodeRef};
+use ruff_text_size::{Ranged, TextSize};
+use rustc_hash::FxHashSet;
+use semantic_model::builtins::{BUILTIN_KEYWORDS, BUILTIN_SYMBOLS};
+use semantic_model::{Binding, BindingKind, ScopeId, SemanticModel};
+
+use crate::resolve::modules_in;
+
+use super::definition::class_scope;
+use super::{DefinitionKind, DefinitionResolver, ParsedModule};
+
+/// A completion candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Completion {
+    pub(crate) label: String,
+    pub(crate) kind: CompletionItemKind,
+}
+
+impl Completion {
+    fn new(label: impl Into<String>, kind: CompletionItemKind) -> Self {
+        Self {
+            label: label.into(),
+            kind,
+        }
+    }
+}
+
+/// What is being completed at the cursor.
+#[derive(Debug, PartialEq, Eq)]
+enum CompletionContext<'a> {
+    /// A module name, e.g. `import foo.` or `from .foo.`. `module` is the package containing
+    /// the module being typed, an empty `module` stands for the top-level modules.
+    Module { level: u32, module: &'a str },
+    /// A name imported from a module, e.g. `from foo import `.
+    ImportedName { level: u32, module: &'a str },
+    /// The attribute of an expression, e.g. `foo.`.
+    Attribute(&'a ast::Expr),
+    /// A name in an expression or a statement.
+    Name,
+}
+
+/// Returns the completions at `offset` in `module`.
+///
+/// The clients filter the completions with the identifier typed so far, so all the candidates
+/// are returned. The module is parsed with error recovery and half-typed lines like `foo.` or
+/// `from foo import ` still produce a syntax tree good enough to find the context.
+pub(crate) fn completions(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    offset: TextSize,
+) -> Vec<Completion> {
+    let Some(context) = completion_context(module, offset) else {
+        return Vec::new();
+    };
+
+    match context {
+        CompletionContext::Module {
+            level,
+            module: package,
+        } => module_completions(resolver, module, level, package),
+        CompletionContext::ImportedName {
+            level,
+            module: package,
+        } => {
+            let mut completions = module_completions(resolver, module, level, package);
+            if let Some(definition) = resolver
+                .resolve_module(module.path(), level, package)
+                .into_iter()
+                .next()
+            {
+                completions.extend(scope_completions(
+                    definition.module.model(),
+                    ScopeId::global(),
+                ));
+            }
+            dedup(completions)
+        }
+        CompletionContext::Attribute(value) => {
+            let Some(definition) = resolver
+                .resolve_expression(module, value, 0)
+                .into_iter()
+                .next()
+            else {
+                return Vec::new();
+            };
+
+            if definition.kind == DefinitionKind::Module {
+                let mut completions =
+                    scope_completions(definition.module.model(), ScopeId::global());
+                if let Some(package) = definition
+                    .module
+                    .path()
+                    .parent()
+                    .filter(|_| is_package(definition.module.path()))
+                {
+                    completions.extend(
+                        modules_in(package)
+                            .into_iter()
+                            .map(|(name, _)| Completion::new(name, CompletionItemKind::MODULE)),
+                    );
+                }
+                return dedup(completions);
+            }
+
+            class_scope(&definition)
+                .map(|scope| scope_completions(definition.module.model(), scope))
+                .unwrap_or_default()
+        }
+        CompletionContext::Name => {
+            let model = module.model();
+            let mut completions = Vec::new();
+            let mut is_innermost = true;
+            for scope in model.ancestors(scope_at(module, offset)) {
+                // Names bound in a class body aren't accessible from the scopes nested inside it.
+                if model.scope(scope).kind.is_class() && !is_innermost {
+                    continue;
+                }
+                is_innermost = false;
+                completions.extend(scope_completions(model, scope));
+            }
+
+            completions.extend(BUILTIN_SYMBOLS.iter().map(|&name| {
+                let kind = if name.starts_with(char::is_uppercase) {
+                    CompletionItemKind::CLASS
+                } else if name.starts_with("__") {
+                    CompletionItemKind::VARIABLE
+                } else {
+                    CompletionItemKind::FUNCTION
+                };
+                Completion::new(name, kind)
+            }));
+            completions.extend(
+                BUILTIN_KEYWORDS
+                    .iter()
+                    .map(|&keyword| Completion::new(keyword, CompletionItemKind::KEYWORD)),
+            );
+
+            dedup(completions)
+        }
+    }
+}
+
+/// Returns the scope of a name typed at `offset`.
+///
+/// A new statement on an empty line after the end of a function belongs to the function as
+/// long as it's indented more than the `def`, even though the function's range ends with its
+/// last statement.
+fn scope_at(module: &ParsedModule, offset: TextSize) -> ScopeId {
+    let model = module.model();
+    let source = module.source();
+    let (line_start, line) = line_before(source, offset);
+    if !line.trim().is_empty() {
+        return model.scope_at(offset);
+    }
+
+    let before = TextSize::of(source[..line_start].trim_end());
+    let indentation = line.len();
+    let mut scope = model.scope_at(before);
+
+    while let Some(parent) = model.scope(scope).parent {
+        let definition = model
+            .bindings()
+            .find_map(|(_, binding)| match binding.kind {
+                BindingKind::FunctionDefinition(id) | BindingKind::ClassDefinition(id)
+                    if id == scope =>
+                {
+                    Some(binding.definition_range.start())
+                }
+                _ => None,
+            });
+
+        // Leave lambdas, comprehensions and the scopes of the definitions the line isn't
+        // indented in.
+        let is_inside = definition.is_some_and(|start| {
+            let (_, indented) = line_before(source, start);
+            indented.len() < indentation
+        });
+        if is_inside {
+            break;
+        }
+        scope = parent;
+    }
+
+    scope
+}
+
+/// Returns the start of the line containing `offset` and the text from there up to `offset`.
+fn line_before(source: &str, offset: TextSize) -> (usize, &str) {
+    let line_start = source[..offset.to_usize()]
+        .rfind(['\n', '\r'])
+        .map_or(0, |index| index + 1);
+    (line_start, &source[line_start..offset.to_usize()])
+}
+
+/// Returns the modules in the package `package`, or the top-level modules if it's empty.
+fn module_completions(
+    resolver: &DefinitionResolver,
+    module: &ParsedModule,
+    level: u32,
+    package: &str,
+) -> Vec<Completion> {
+    let completions = resolver
+        .import_resolver()
+        .package_directories(module.path(), level, package)
+        .iter()
+        .flat_map(|directory| modules_in(directory))
+        .map(|(name, _)| Completion::new(name, CompletionItemKind::MODULE))
+        .collect();
+
+    dedup(completions)
+}
+
+/// Returns the names bound in `scope`.
+fn scope_completions(model: &SemanticModel, scope: ScopeId) -> Vec<Completion> {
+    model
+        .scope(scope)
+        .bindings()
+        .map(|(name, id)| Completion::new(name, completion_kind(model, model.binding(id))))
+        .collect()
+}
+
+fn completion_kind(model: &SemanticModel, binding: &Binding) -> CompletionItemKind {
+    match binding.kind {
+        BindingKind::FunctionDefinition(_) if model.scope(binding.scope).kind.is_class() => {
+            CompletionItemKind::METHOD
+        }
+        BindingKind::FunctionDefinition(_) => CompletionItemKind::FUNCTION,
+        BindingKind::ClassDefinition(_) => CompletionItemKind::CLASS,
+        BindingKind::Import(_) => CompletionItemKind::MODULE,
+        BindingKind::TypeParam => CompletionItemKind::TYPE_PARAMETER,
+        _ if model.scope(binding.scope).kind.is_class() => CompletionItemKind::FIELD,
+        _ => CompletionItemKind::VARIABLE,
+    }
+}
+
+/// Removes the completions with the same label, keeping the first one.
+fn dedup(mut completions: Vec<Completion>) -> Vec<Completion> {
+    let mut seen = FxHashSet::default();
+    completions.retain(|completion| seen.insert(completion.label.clone()));
+    completions
+}
+
+fn is_package(path: &std::path::Path) -> bool {
+    path.file_stem().is_some_and(|stem| stem == "__init__")
+}
+
+fn completion_context(module: &ParsedModule, offset: TextSize) -> Option<CompletionContext<'_>> {
+    let (_, line) = line_before(module.source(), offset);
+    if is_in_comment(line) {
+        return None;
+    }
+
+    let nodes = python_ast_utils::nodes_at_offset(module.syntax(), offset);
+    let is_string = nodes.last().is_some_and(|node| {
+        matches!(
+            node,
+            AnyNodeRef::StringLiteralExpr(_)
+                | AnyNodeRef::StringLiteral(_)
+                | AnyNodeRef::BytesLiteralExpr(_)
+                | AnyNodeRef::BytesLiteral(_)
+                | AnyNodeRef::FStringLiteralElement(_)
+        )
+    });
+    if is_string {
+        return None;
+    }
+
+    if let Some(context) = import_context(line.trim_start()) {
+        return context;
+    }
+
+    for node in nodes.into_iter().rev() {
+        match node {
+            AnyNodeRef::AttributeExpr(attribute)
+                if attribute.attr.range.contains_inclusive(offset) =>
+            {
+                return Some(CompletionContext::Attribute(&attribute.value));
+            }
+            // The names of a parenthesized import spanning multiple lines.
+            AnyNodeRef::StmtImportFrom(import_from)
+                if import_from
+                    .module
+                    .as_ref()
+                    .map_or(import_from.start(), Ranged::end)
+                    < offset =>
+            {
+                return Some(CompletionContext::ImportedName {
+                    level: import_from.level,
+                    module: import_from.module.as_deref().unwrap_or(""),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    Some(CompletionContext::Name)
+}
+
+/// Returns the context of an `import` or `from ... import` statement, or `None` if `line` isn't
+/// one. The inner `None` means there's nothing to complete, e.g. after `import foo as `.
+fn import_context(line: &str) -> Option<Option<CompletionContext<'_>>> {
+    if let Some(modules) = line.strip_prefix("import ") {
+        let module = modules.rsplit(',').next().unwrap_or_default().trim_start();
+        if module.contains(char::is_whitespace) {
+            return Some(None);
+        }
+        let (_, package) = split_module(module);
+        return Some(Some(CompletionContext::Module {
+            level: 0,
+            module: package,
+        }));
+    }
+
+    let rest = line.strip_prefix("from ")?.trim_start();
+    let (from, names) = match rest.split_once(char::is_whitespace) {
+        Some((from, names)) => (from, Some(names.trim_start())),
+        None => (rest, None),
+    };
+    let module = from.trim_start_matches('.');
+    let level = u32::try_from(from.len() - module.len()).unwrap_or(u32::MAX);
+
+    let Some(names) = names else {
+        let (_, package) = split_module(module);
+        return Some(Some(CompletionContext::Module {
+            level,
+            module: package,
+        }));
+    };
+
+    let Some(names) = names.strip_prefix("import") else {
+        // The `import` keyword is still being typed.
+        return Some(None);
+    };
+    if !names.is_empty() && !names.starts_with(char::is_whitespace) && !names.starts_with('(') {
+        return Some(None);
+    }
+
+    let name = names
+        .rsplit(',')
+        .next()
+        .unwrap_or_default()
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '(');
+    if name.contains(char::is_whitespace) {
+        return Some(None);
+    }
+
+    Some(Some(CompletionContext::ImportedName { level, module }))
+}
+
+/// Splits `foo.bar.ba` into the name being typed, `ba`, and its package, `foo.bar`.
+fn split_module(module: &str) -> (&str, &str) {
+    match module.rsplit_once('.') {
+        Some((package, name)) => (name, package),
+        None => (module, ""),
+    }
+}
+
+/// Returns `true` if the end of `line` is inside a comment.
+fn is_in_comment(line: &str) -> bool {
+    let mut quote = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match (c, quote) {
+            ('\\', Some(_)) => {
+                chars.next();
+            }
+            ('\'' | '"', None) => quote = Some(c),
+            (c, Some(open)) if c == open => quote = None,
+            ('#', None) => return true,
+            _ => {}
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use lsp_types::CompletionItemKind;
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::TextSize;
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+
+    use super::{completions, import_context, CompletionContext};
+
+    /// Returns the completions at the `|` marker in `source`.
+    fn labels(root: &Path, source: &str) -> Vec<(String, CompletionItemKind)> {
+        let offset = source.find('|').unwrap();
+        let source = source.replace('|', "");
+        let module = Arc::new(ParsedModule::new(
+            root.join("main.py"),
+            source.clone(),
+            LineIndex::from_source_text(&source),
+        ));
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        completions(&mut resolver, &module, TextSize::try_from(offset).unwrap())
+            .into_iter()
+            .map(|completion| (completion.label, completion.kind))
+            .collect()
+    }
+
+    fn has(completions: &[(String, CompletionItemKind)], label: &str) -> bool {
+        completions.iter().any(|(other, _)| other == label)
+    }
+
+    #[test]
+    fn import_contexts() {
+        assert_eq!(
+            import_context("import foo.ba"),
+            Some(Some(CompletionContext::Module {
+                level: 0,
+                module: "foo"
+            }))
+        );
+        assert_eq!(
+            import_context("import os, sy"),
+            Some(Some(CompletionContext::Module {
+                level: 0,
+                module: ""
+            }))
+        );
+        assert_eq!(import_context("import foo as "), Some(None));
+        assert_eq!(
+            import_context("from ..pkg.su"),
+            Some(Some(CompletionContext::Module {
+                level: 2,
+                module: "pkg"
+            }))
+        );
+        assert_eq!(import_context("from pkg imp"), Some(None));
+        assert_eq!(
+            import_context("from pkg import a, "),
+            Some(Some(CompletionContext::ImportedName {
+                level: 0,
+                module: "pkg"
+            }))
+        );
+        assert_eq!(import_context("from pkg import a as "), Some(None));
+        assert_eq!(import_context("x = 1"), None);
+    }
+
+    #[test]
+    fn names_in_scope() {
+        let source = r#"
+import os
+
+CONSTANT = 1
+
+class Foo:
+    attribute = 1
+
+    def method(self):
+        local = 2
+        |
+
+def other():
+    pass
+"#;
+        let completions = labels(Path::new("/project"), source);
+
+        assert!(has(&completions, "local"));
+        assert!(has(&completions, "self"));
+        assert!(has(&completions, "CONSTANT"));
+        assert!(has(&completions, "other"));
+        assert!(has(&completions, "os"));
+        assert!(has(&completions, "print"));
+        assert!(has(&completions, "return"));
+        // Class attributes aren't accessible from the methods.
+        assert!(!has(&completions, "attribute"));
+    }
+
+    #[test]
+    fn attributes() {
+        let source = r#"
+class Foo:
+    attribute = 1
+
+    def method(self):
+        return self.|
+
+Foo.
+"#;
+        let mut completions = labels(Path::new("/project"), source);
+        completions.sort_by(|(a, _), (b, _)| a.cmp(b));
+        assert_eq!(
+            completions,
+            vec![
+                ("attribute".to_string(), CompletionItemKind::FIELD),
+                ("method".to_string(), CompletionItemKind::METHOD),
+            ]
+        );
+
+        let source = source.replace('|', "").replace("Foo.\n", "Foo.|\n");
+        let completions = labels(Path::new("/project"), &source);
+        assert!(has(&completions, "attribute"));
+        assert!(has(&completions, "method"));
+    }
+
+    #[test]
+    fn nothing_in_comments_and_strings() {
+        assert!(labels(Path::new("/project"), "x = 1  # x|\n").is_empty());
+        assert!(labels(Path::new("/project"), "x = 'x|'\n").is_empty());
+    }
+
+    #[test]
+    fn modules_and_imported_names() {
+        let root = tempfile::tempdir().unwrap();
+        let package = root.path().join("pkg");
+        std::fs::create_dir_all(package.join("sub")).unwrap();
+        std::fs::write(package.join("__init__.py"), "VERSION = 1\n").unwrap();
+        std::fs::write(package.join("sub").join("__init__.py"), "").unwrap();
+        std::fs::write(package.join("helpers.py"), "def helper():\n    pass\n").unwrap();
+
+        let completions = labels(root.path(), "import p|\n");
+        assert!(has(&completions, "pkg"));
+
+        let completions = labels(root.path(), "import pkg.|\n");
+        assert!(has(&completions, "helpers"));
+        assert!(has(&completions, "sub"));
+
+        let completions = labels(root.path(), "from pkg import |\n");
+        assert!(has(&completions, "helpers"));
+        assert!(has(&completions, "sub"));
+        assert!(has(&completions, "VERSION"));
+
+        let completions = labels(root.path(), "from pkg.helpers import (\n    |\n)\n");
+        assert_eq!(
+            completions,
+            vec![("helper".to_string(), CompletionItemKind::FUNCTION)]
+        );
+
+        let completions = labels(root.path(), "import pkg\n\npkg.|\n");
+        assert!(has(&completions, "VERSION"));
+        assert!(has(&completions, "helpers"));
+    }
+}
@@ -0,0 +1,817 @@
+//! Resolution of names to the place they are defined, following imports across modules.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use python_ast::{self as ast, AnyNodeRef, Expr};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use rustc_hash::{FxHashMap, FxHashSet};
+use semantic_model::builtins::BUILTIN_SYMBOLS;
+use semantic_model::{Binding, BindingId, BindingKind, Import, ScopeId, ScopeKind};
+
+use crate::edit::Document;
+use crate::resolve::{module_files, ImportResolver};
+
+use super::ParsedModule;
+
+/// How many imports are followed before giving up, this protects against import cycles.
+const MAX_IMPORT_DEPTH: usize = 16;
+
+/// The place a symbol is defined.
+#[derive(Clone)]
+pub(crate) struct Definition {
+    /// The module containing the definition.
+    pub(crate) module: Arc<ParsedModule>,
+    /// The range of the defined name. Modules are defined by the whole file, in which case
+    /// this is an empty range at the start of it.
+    pub(crate) range: TextRange,
+    pub(crate) kind: DefinitionKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum DefinitionKind {
+    Module,
+    Binding(BindingId),
+}
+
+impl Definition {
+    fn module(module: Arc<ParsedModule>) -> Self {
+        Self {
+            module,
+            range: TextRange::default(),
+            kind: DefinitionKind::Module,
+        }
+    }
+
+    fn binding(module: Arc<ParsedModule>, id: BindingId) -> Self {
+        let range = module.model().binding(id).range;
+        Self {
+            module,
+            range,
+            kind: DefinitionKind::Binding(id),
+        }
+    }
+
+    /// Returns the binding defining the symbol, `None` for modules.
+    pub(crate) fn as_binding(&self) -> Option<&Binding> {
+        match self.kind {
+            DefinitionKind::Module => None,
+            DefinitionKind::Binding(id) => Some(self.module.model().binding(id)),
+        }
+    }
+}
+
+/// Resolves symbols to their definitions. The modules read while following imports are cached,
+/// so a resolver should be reused for all the lookups of a request.
+pub(crate) struct DefinitionResolver<'a> {
+    import_resolver: &'a ImportResolver,
+    modules: FxHashMap<PathBuf, Option<Arc<ParsedModule>>>,
+    /// The open documents, whose unsaved contents are read instead of the files on disk.
+    documents: FxHashMap<PathBuf, &'a Document>,
+    /// Whether an import binding a member under another name was followed, only recorded
+    /// while [`Self::resolves_through_alias`] runs.
+    followed_alias: Option<bool>,
+}
+
+impl<'a> DefinitionResolver<'a> {
+    pub(crate) fn new(import_resolver: &'a ImportResolver) -> Self {
+        Self {
+            import_resolver,
+            modules: FxHashMap::default(),
+            documents: FxHashMap::default(),
+            followed_alias: None,
+        }
+    }
+
+    /// Reads the modules of the open `documents` from their contents rather than from disk.
+    #[must_use]
+    pub(crate) fn with_documents(
+        mut self,
+        documents: impl IntoIterator<Item = (PathBuf, &'a Document)>,
+    ) -> Self {
+        self.documents.extend(documents);
+        self
+    }
+
+    pub(crate) fn import_resolver(&self) -> &'a ImportResolver {
+        self.import_resolver
+    }
+
+    /// The root of the workspace the imports are resolved from.
+    pub(crate) fn root(&self) -> &Path {
+        self.import_resolver.root()
+    }
+
+    /// Returns the module at `path`, reading it from its open document or from disk unless it's
+    /// already known.
+    pub(crate) fn module(&mut self, path: &Path) -> Option<Arc<ParsedModule>> {
+        let document = self.documents.get(path).copied();
+        self.modules
+            .entry(path.to_path_buf())
+            .or_insert_with(|| match document {
+                Some(document) => Some(Arc::new(ParsedModule::from_document(
+                    path.to_path_buf(),
+                    document,
+                ))),
+                None => ParsedModule::read(path).map(Arc::new),
+            })
+            .clone()
+    }
+
+    /// Returns the open document at `path`, unless its module was already parsed.
+    pub(crate) fn document(&self, path: &Path) -> Option<&'a Document> {
+        if self.contains(path) {
+            return None;
+        }
+        self.documents.get(path).copied()
+    }
+
+    /// Returns `true` if the module at `path` was already read or inserted.
+    pub(crate) fn contains(&self, path: &Path) -> bool {
+        self.modules.contains_key(path)
+    }
+
+    /// Makes `module` known to the resolver, e.g. an open document with unsaved changes.
+    pub(crate) fn insert(&mut self, module: Arc<ParsedModule>) {
+        self.modules
+            .insert(module.path().to_path_buf(), Some(module));
+    }
+
+    /// Returns `true` if resolving the symbol at `offset` in `module` follows an import that
+    /// binds a member under another name, e.g. `from foo import bar as baz`.
+    pub(crate) fn resolves_through_alias(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        offset: TextSize,
+    ) -> bool {
+        self.followed_alias = Some(false);
+        self.definitions_at(module, offset);
+        self.followed_alias.take().unwrap_or_default()
+    }
+
+    /// Returns the definitions of the symbol at `offset` in `module`.
+    pub(crate) fn definitions_at(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        offset: TextSize,
+    ) -> Vec<Definition> {
+        let nodes = python_ast_utils::nodes_at_offset(module.syntax(), offset);
+
+        match nodes.last() {
+            Some(AnyNodeRef::NameExpr(name)) => {
+                self.resolve_name(module, &name.id, name.start(), 0)
+            }
+            Some(AnyNodeRef::AttributeExpr(attribute))
+                if attribute.attr.range.contains_inclusive(offset) =>
+            {
+                self.resolve_attribute(module, attribute, 0)
+            }
+            Some(AnyNodeRef::Alias(alias)) => {
+                match nodes.iter().rev().nth(1) {
+                    Some(AnyNodeRef::StmtImport(_)) => {
+                        // Resolve `import foo.bar` to `foo` or `foo.bar`, depending on the
+                        // segment under the cursor.
+                        let name = if alias.name.range.contains_inclusive(offset) {
+                            module_prefix(&alias.name, offset)
+                        } else {
+                            alias.name.as_str()
+                        };
+                        self.resolve_module(module.path(), 0, name)
+                    }
+                    Some(AnyNodeRef::StmtImportFrom(import_from)) => {
+                        let from = import_from.module.as_ref().map(ast::Identifier::as_str);
+                        if alias.name.as_str() == "*" {
+                            self.resolve_module(
+                                module.path(),
+                                import_from.level,
+                                from.unwrap_or(""),
+                            )
+                        } else {
+                            self.resolve_member(
+                                module.path(),
+                                from,
+                                import_from.level,
+                                &alias.name,
+                                0,
+                            )
+                        }
+                    }
+                    _ => Vec::new(),
+                }
+            }
+            Some(AnyNodeRef::StmtImportFrom(ast::ImportFromStmt {
+                module: Some(from),
+                level,
+                ..
+            })) if from.range.contains_inclusive(offset) => {
+                self.resolve_module(module.path(), *level, module_prefix(from, offset))
+            }
+            Some(AnyNodeRef::Keyword(ast::Keyword { arg: Some(arg), .. }))
+                if arg.range.contains_inclusive(offset) =>
+            {
+                let Some(call) = nodes.iter().rev().find_map(|node| match node {
+                    AnyNodeRef::CallExpr(call) => Some(*call),
+                    _ => None,
+                }) else {
+                    return Vec::new();
+                };
+                self.resolve_keyword(module, call, arg)
+            }
+            Some(AnyNodeRef::StmtGlobal(ast::GlobalStmt { names, .. })) => {
+                let Some(name) = names
+                    .iter()
+                    .find(|name| name.range.contains_inclusive(offset))
+                else {
+                    return Vec::new();
+                };
+                let global = module.model().global_scope();
+                global
+                    .get(name)
+                    .map(|id| self.definitions_for_binding(module, id, 0))
+                    .unwrap_or_default()
+            }
+            Some(AnyNodeRef::StmtNonlocal(ast::NonlocalStmt { names, .. })) => {
+                let Some(name) = names
+                    .iter()
+                    .find(|name| name.range.contains_inclusive(offset))
+                else {
+                    return Vec::new();
+                };
+                let model = module.model();
+                let scope = model.scope_at(offset);
+                model
+                    .scope(scope)
+                    .parent
+                    .and_then(|parent| model.lookup(name, parent, offset))
+                    .map(|id| self.definitions_for_binding(module, id, 0))
+                    .unwrap_or_default()
+            }
+            Some(_) => {
+                // The cursor is on the name of a definition, e.g. a function or a parameter.
+                module
+                    .model()
+                    .bindings()
+                    .find(|(_, binding)| binding.range.contains_inclusive(offset))
+                    .map(|(id, _)| vec![Definition::binding(module.clone(), id)])
+                    .unwrap_or_default()
+            }
+            None => Vec::new(),
+        }
+    }
+
+    /// Resolves a reference to `name` at `offset` in `module`.
+    pub(crate) fn resolve_name(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        name: &str,
+        offset: TextSize,
+        depth: usize,
+    ) -> Vec<Definition> {
+        let model = module.model();
+        if let Some(id) = model.lookup(name, model.scope_at(offset), offset) {
+            return self.definitions_for_binding(module, id, depth);
+        }
+
+        if BUILTIN_SYMBOLS.contains(&name) {
+            return self.resolve_member(module.path(), Some("builtins"), 0, name, depth);
+        }
+
+        Vec::new()
+    }
+
+    /// Resolves the attribute `value.attr`, e.g. a function of an imported module or a method
+    /// of a class.
+    pub(crate) fn resolve_attribute(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        attribute: &ast::AttributeExpr,
+        depth: usize,
+    ) -> Vec<Definition> {
+        // A module resolves to its source file and its stub, the attribute may only be defined
+        // in the stub.
+        for value in self.resolve_expression(module, &attribute.value, depth) {
+            let definitions = self.resolve_member_of(&value, &attribute.attr, depth);
+            if !definitions.is_empty() || value.kind != DefinitionKind::Module {
+                return definitions;
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// Resolves the keyword argument `name` of `call` to the parameter of the called function.
+    /// Calling a class passes the arguments to its `__init__` method.
+    fn resolve_keyword(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        call: &ast::CallExpr,
+        name: &str,
+    ) -> Vec<Definition> {
+        let Some(callee) = self
+            .resolve_expression(module, &call.func, 0)
+            .into_iter()
+            .next()
+        else {
+            return Vec::new();
+        };
+        let Some(binding) = callee.as_binding() else {
+            return Vec::new();
+        };
+
+        let model = callee.module.model();
+        let function_scope = match binding.kind {
+            BindingKind::FunctionDefinition(scope) => Some(scope),
+            BindingKind::ClassDefinition(scope) => {
+                model
+                    .scope(scope)
+                    .get("__init__")
+                    .and_then(|id| match model.binding(id).kind {
+                        BindingKind::FunctionDefinition(scope) => Some(scope),
+                        _ => None,
+                    })
+            }
+            _ => None,
+        };
+
+        function_scope
+            .and_then(|scope| model.scope(scope).get(name))
+            .filter(|&id| model.binding(id).kind.is_parameter())
+            .map(|id| vec![Definition::binding(callee.module.clone(), id)])
+            .unwrap_or_default()
+    }
+
+    /// Resolves a name or an attribute expression.
+    pub(crate) fn resolve_expression(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        expr: &Expr,
+        depth: usize,
+    ) -> Vec<Definition> {
+        match expr {
+            Expr::Name(name) => self.resolve_name(module, &name.id, name.start(), depth),
+            Expr::Attribute(attribute) => self.resolve_attribute(module, attribute, depth),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Resolves the member `name` of the symbol defined by `definition`.
+    fn resolve_member_of(
+        &mut self,
+        definition: &Definition,
+        name: &str,
+        depth: usize,
+    ) -> Vec<Definition> {
+        let module = &definition.module;
+        if definition.kind == DefinitionKind::Module {
+            return self.resolve_module_member(module, name, depth);
+        }
+
+        class_scope(definition)
+            .and_then(|scope| module.model().scope(scope).get(name))
+            .map(|id| self.definitions_for_binding(module, id, depth))
+            .unwrap_or_default()
+    }
+
+    /// Returns the definitions a binding refers to. Imports are followed to the imported
+    /// module or member, any other binding is its own definition.
+    pub(crate) fn definitions_for_binding(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        id: BindingId,
+        depth: usize,
+    ) -> Vec<Definition> {
+        let binding = module.model().binding(id);
+        if depth > MAX_IMPORT_DEPTH {
+            return vec![Definition::binding(module.clone(), id)];
+        }
+
+        let definitions = match &binding.kind {
+            BindingKind::Import(import) => {
+                self.resolve_module(module.path(), 0, imported_module_name(binding, import))
+            }
+            BindingKind::FromImport(import) => {
+                if let Some(followed_alias) = &mut self.followed_alias {
+                    *followed_alias |= binding.name != import.member;
+                }
+                self.resolve_member(
+                    module.path(),
+                    import.module.as_deref(),
+                    import.level,
+                    &import.member,
+                    depth + 1,
+                )
+            }
+            _ => return vec![Definition::binding(module.clone(), id)],
+        };
+
+        if definitions.is_empty() {
+            // Land on the import itself if it can't be resolved.
+            vec![Definition::binding(module.clone(), id)]
+        } else {
+            definitions
+        }
+    }
+
+    /// Resolves the module `name` imported from `source_file` to its files, in order of
+    /// preference: its source file, then its stub.
+    pub(crate) fn resolve_module(
+        &mut self,
+        source_file: &Path,
+        level: u32,
+        name: &str,
+    ) -> Vec<Definition> {
+        let import_result = self.import_resolver.resolve(source_file, level, name);
+
+        module_files(&import_result)
+            .iter()
+            .filter_map(|path| self.module(path))
+            .map(Definition::module)
+            .collect()
+    }
+
+    /// Returns the names bound by `from module import *` in `source_file`, or `None` if the
+    /// module can't be resolved.
+    pub(crate) fn star_import_names(
+        &mut self,
+        source_file: &Path,
+        level: u32,
+        module: &str,
+    ) -> Option<FxHashSet<String>> {
+        let definitions = self.resolve_module(source_file, level, module);
+        if definitions.is_empty() {
+            return None;
+        }
+
+        let mut names = FxHashSet::default();
+        for definition in &definitions {
+            self.collect_exported_names(&definition.module, &mut names, 0);
+        }
+        Some(names)
+    }
+
+    /// Collects the names a wildcard import of `module` binds: the names in its `__all__`, or
+    /// else its public global names, including the ones it imports with a wildcard.
+    fn collect_exported_names(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        names: &mut FxHashSet<String>,
+        depth: usize,
+    ) {
+        if depth > MAX_IMPORT_DEPTH {
+            return;
+        }
+
+        let dunder_all = module.dunder_all();
+        if !dunder_all.is_empty() {
+            names.extend(
+                dunder_all
+                    .into_iter()
+                    .map(|range| module.source()[range].to_string()),
+            );
+            return;
+        }
+
+        let global = module.model().global_scope();
+        names.extend(
+            global
+                .bindings()
+                .map(|(name, _)| name)
+                .filter(|name| !name.starts_with('_'))
+                .map(ToString::to_string),
+        );
+        for star_import in global.star_imports() {
+            for imported in self.resolve_module(
+                module.path(),
+                star_import.level,
+                star_import.module.as_deref().unwrap_or(""),
+            ) {
+                self.collect_exported_names(&imported.module, names, depth + 1);
+            }
+        }
+    }
+
+    /// Resolves `from module import member`, which is either a symbol defined in `module` or a
+    /// submodule of it.
+    fn resolve_member(
+        &mut self,
+        source_file: &Path,
+        module: Option<&str>,
+        level: u32,
+        member: &str,
+        depth: usize,
+    ) -> Vec<Definition> {
+        // The member may only be defined in the module's stub.
+        for definition in self.resolve_module(source_file, level, module.unwrap_or("")) {
+            let definitions = self.resolve_module_member(&definition.module, member, depth);
+            if !definitions.is_empty() {
+                return definitions;
+            }
+        }
+
+        // The member may be a submodule of a namespace package.
+        let submodule = match module {
+            Some(module) => format!("{module}.{member}"),
+            None => member.to_string(),
+        };
+        self.resolve_module(source_file, level, &submodule)
+    }
+
+    /// Resolves the symbol `name` defined in the global scope of `module`.
+    fn resolve_module_member(
+        &mut self,
+        module: &Arc<ParsedModule>,
+        name: &str,
+        depth: usize,
+    ) -> Vec<Definition> {
+        if depth > MAX_IMPORT_DEPTH {
+            return Vec::new();
+        }
+
+        let global = module.model().global_scope();
+        if let Some(id) = global.get(name) {
+            return self.definitions_for_binding(module, id, depth + 1);
+        }
+
+        // A package's submodules are accessible as its attributes.
+        if module
+            .path()
+            .file_stem()
+            .is_some_and(|stem| stem == "__init__")
+        {
+            let definitions = self.resolve_module(module.path(), 1, name);
+            if !definitions.is_empty() {
+                return definitions;
+            }
+        }
+
+        for star_import in global.star_imports() {
+            for imported in self.resolve_module(
+                module.path(),
+                star_import.level,
+                star_import.module.as_deref().unwrap_or(""),
+            ) {
+                let definitions = self.resolve_module_member(&imported.module, name, depth + 1);
+                if !definitions.is_empty() {
+                    return definitions;
+                }
+            }
+        }
+
+        Vec::new()
+    }
+}
+
+/// Returns the scope of the class whose members are accessible as attributes of `definition`,
+/// either the class itself or the `self` or `cls` parameter of one of its methods.
+pub(crate) fn class_scope(definition: &Definition) -> Option<ScopeId> {
+    let binding = definition.as_binding()?;
+    match binding.kind {
+        BindingKind::ClassDefinition(scope) => Some(scope),
+        BindingKind::Parameter => method_class(&definition.module, definition.kind, binding),
+        _ => None,
+    }
+}
+
+/// Returns the scope of the class `binding` is the first parameter (`self` or `cls`) of a
+/// method of.
+fn method_class(module: &ParsedModule, kind: DefinitionKind, binding: &Binding) -> Option<ScopeId> {
+    let model = module.model();
+    let function = model.scope(binding.scope);
+    if !function.kind.is_function() {
+        return None;
+    }
+
+    let first_parameter = model
+        .bindings()
+        .find(|(_, other)| other.scope == binding.scope && other.kind.is_parameter())
+        .map(|(id, _)| DefinitionKind::Binding(id));
+    if first_parameter != Some(kind) {
+        return None;
+    }
+
+    model
+        .ancestors(binding.scope)
+        .skip(1)
+        .find(|&id| !model.scope(id).kind.is_type_params())
+        .filter(|&id| model.scope(id).kind == ScopeKind::Class)
+}
+
+/// Returns the name of the module bound by the `import` statement `binding`: `import foo.bar`
+/// binds `foo`, whereas `import foo.bar as baz` binds `foo.bar`.
+pub(super) fn imported_module_name<'a>(binding: &'a Binding, import: &'a Import) -> &'a str {
+    // Without an alias, the bound name is the first segment, at the start of the import.
+    let is_aliased = binding.range.start() != binding.definition_range.start();
+    if is_aliased {
+        import.qualified_name.as_str()
+    } else {
+        binding.name.as_str()
+    }
+}
+
+/// Returns the node defining `binding` in `module`, e.g. the function definition of a
+/// function's name.
+pub(super) fn defining_node<'a>(
+    module: &'a ParsedModule,
+    binding: &Binding,
+) -> Option<AnyNodeRef<'a>> {
+    python_ast_utils::nodes_at_offset(module.syntax(), binding.range.start())
+        .into_iter()
+        .find(|node| node.range() == binding.definition_range)
+}
+
+/// Returns the name of the module defined by the file at `path`, e.g. `foo` for both
+/// `foo.py` and `foo/__init__.py`.
+pub(super) fn module_name(path: &Path) -> Option<&str> {
+    let stem = path.file_stem()?;
+    let name = if stem == "__init__" {
+        path.parent()?.file_name()?
+    } else {
+        stem
+    };
+    name.to_str()
+}
+
+/// Returns the dotted module name up to and including the segment at `offset`, e.g. `foo.bar`
+/// for an offset in `bar` of `foo.bar.baz`.
+pub(super) fn module_prefix(name: &ast::Identifier, offset: TextSize) -> &str {
+    let relative = (offset - name.start()).to_usize();
+    let end = name.as_str()[relative..]
+        .find('.')
+        .map_or(name.len(), |index| relative + index);
+    &name.as_str()[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::TextSize;
+
+    use crate::analysis::ParsedModule;
+    use crate::resolve::ImportResolver;
+
+    use super::{DefinitionKind, DefinitionResolver};
+
+    fn definition_text(source: &str, cursor: &str) -> Option<String> {
+        let module = Arc::new(ParsedModule::new(
+            Path::new("/project/main.py").to_path_buf(),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        ));
+        let import_resolver = ImportResolver::new(Path::new("/project").to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+
+        let offset = TextSize::try_from(source.find(cursor).unwrap()).unwrap();
+        let definition = resolver
+            .definitions_at(&module, offset)
+            .into_iter()
+            .next()?;
+        let binding = definition.as_binding()?;
+
+        Some(module.source()[binding.definition_range].to_string())
+    }
+
+    #[test]
+    fn local_definitions() {
+        let source = r#"
+def foo(x):
+    return x
+
+class Bar:
+    def method(self):
+        return self.other()
+
+    def other(self):
+        return foo(1)
+
+value = Bar()
+"#;
+
+        assert_eq!(
+            definition_text(source, "foo(1)").as_deref(),
+            Some("def foo(x):\n    return x")
+        );
+        assert_eq!(definition_text(source, "x\n").as_deref(), Some("x"));
+        assert!(definition_text(source, "Bar()")
+            .unwrap()
+            .starts_with("class Bar:"));
+        assert!(definition_text(source, "other()")
+            .unwrap()
+            .starts_with("def other(self):"));
+        assert!(definition_text(source, "method")
+            .unwrap()
+            .starts_with("def method(self):"));
+    }
+
+    #[test]
+    fn keyword_arguments() {
+        let source = r#"
+def foo(first, second=None):
+    pass
+
+class Bar:
+    def __init__(self, value):
+        self.value = value
+
+foo(1, second=2)
+Bar(value=3)
+"#;
+
+        assert_eq!(
+            definition_text(source, "second=2").as_deref(),
+            Some("second")
+        );
+        assert_eq!(definition_text(source, "value=3").as_deref(), Some("value"));
+    }
+
+    #[test]
+    fn unresolved_import_lands_on_import() {
+        let source = "from missing import thing\n\nthing()\n";
+
+        assert_eq!(definition_text(source, "thing()").as_deref(), Some("thing"));
+    }
+
+    #[test]
+    fn imported_definitions() {
+        let root = tempfile::tempdir().unwrap();
+        let package = root.path().join("pkg");
+        std::fs::create_dir(&package).unwrap();
+        std::fs::write(package.join("__init__.py"), "").unwrap();
+        std::fs::write(
+            package.join("mod.py"),
+            "import os\n\ndef helper():\n    pass\n",
+        )
+        .unwrap();
+
+        let source = "from pkg.mod import helper\nimport pkg.mod as m\n\nhelper()\nm.helper()\n";
+        let module = Arc::new(ParsedModule::new(
+            root.path().join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        ));
+        let import_resolver = ImportResolver::new(root.path().to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+
+        for cursor in ["helper()", "helper()\n", "helper\nimport"] {
+            let offset = TextSize::try_from(source.rfind(cursor).unwrap()).unwrap();
+            let definitions = resolver.definitions_at(&module, offset);
+            let [definition] = definitions.as_slice() else {
+                panic!(
+                    "expected a single definition for `{cursor}`, got {}",
+                    definitions.len()
+                );
+            };
+
+            assert_eq!(definition.module.path(), package.join("mod.py"));
+            assert_eq!(&definition.module.source()[definition.range], "helper");
+        }
+
+        let offset = TextSize::try_from(source.find("m.helper").unwrap()).unwrap();
+        let definitions = resolver.definitions_at(&module, offset);
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].kind, DefinitionKind::Module);
+        assert_eq!(definitions[0].module.path(), package.join("mod.py"));
+    }
+
+    #[test]
+    fn stub_only_definitions() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join("lib.py"), "def helper():\n    pass\n").unwrap();
+        std::fs::write(
+            root.path().join("lib.pyi"),
+            "def helper() -> None: ...\ndef typed() -> int: ...\n",
+        )
+        .unwrap();
+
+        let source = "import lib\nfrom lib import helper, typed\n\nlib.typed()\n";
+        let module = Arc::new(ParsedModule::new(
+            root.path().join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        ));
+        let import_resolver = ImportResolver::new(root.path().to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+
+        let definition_path = |resolver: &mut DefinitionResolver, cursor: &str| {
+            let offset = TextSize::try_from(source.find(cursor).unwrap()).unwrap();
+            let definitions = resolver.definitions_at(&module, offset);
+            definitions[0].module.path().to_path_buf()
+        };
+
+        // The source file is preferred, the stub is the fallback.
+        assert_eq!(
+            definition_path(&mut resolver, "helper,"),
+            root.path().join("lib.py")
+        );
+        assert_eq!(
+            definition_path(&mut resolver, "typed\n"),
+            root.path().join("lib.pyi")
+        );
+        assert_eq!(
+            definition_path(&mut resolver, "typed()"),
+            root.path().join("lib.pyi")
+        );
+    }
+}
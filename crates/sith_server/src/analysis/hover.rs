@@ -0,0 +1,267 @@
+//! Information shown when hovering a symbol: its signature, documentation and, for modules,
+//! the file it's resolved to.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use python_ast::{self as ast, AnyNodeRef};
+use ruff_python_resolver::import_result::ImportType;
+use ruff_text_size::{TextRange, TextSize};
+use semantic_model::{Binding, BindingKind};
+
+use crate::resolve::resolved_file;
+
+use super::definition::{defining_node, imported_module_name, module_name, module_prefix};
+use super::references::identifier_at;
+use super::signature::{class_signature, docstring, function_signature, source_text};
+use super::{Definition, DefinitionKind, DefinitionResolver, ParsedModule};
+
+pub(crate) struct Hover {
+    /// The Python code describing the symbol, e.g. the signature of a function.
+    pub(crate) signature: String,
+    pub(crate) documentation: Option<String>,
+    /// The file an imported module is resolved to and where it was found.
+    pub(crate) import: Option<(PathBuf, ImportType)>,
+    /// The range of the hovered identifier.
+    pub(crate) range: TextRange,
+}
+
+/// Returns the hover information of the symbol at `offset` in `module`.
+pub(crate) fn hover(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    offset: TextSize,
+) -> Option<Hover> {
+    let range = identifier_at(module, offset)?;
+    let definition = resolver.definitions_at(module, offset).into_iter().next()?;
+
+    let (signature, documentation) = match definition.as_binding() {
+        None => (
+            format!("(module) {}", module_name(definition.module.path())?),
+            docstring(&definition.module.syntax().body),
+        ),
+        Some(binding) => describe_binding(&definition, binding)?,
+    };
+
+    let import = if definition.kind == DefinitionKind::Module {
+        imported_module(module, offset).and_then(|(level, name)| {
+            let import_result = resolver
+                .import_resolver()
+                .resolve(module.path(), level, &name);
+            Some((resolved_file(&import_result)?, import_result.import_type))
+        })
+    } else {
+        None
+    };
+
+    Some(Hover {
+        signature,
+        documentation,
+        import,
+        range,
+    })
+}
+
+/// Returns the signature and the documentation of the symbol bound by `binding`.
+fn describe_binding(
+    definition: &Definition,
+    binding: &Binding,
+) -> Option<(String, Option<String>)> {
+    let module = &definition.module;
+    let source = module.source();
+    let node = defining_node(module, binding);
+
+    let described = match (&binding.kind, node) {
+        (BindingKind::FunctionDefinition(_), Some(AnyNodeRef::StmtFunctionDef(function))) => (
+            function_signature(function, source),
+            docstring(&function.body),
+        ),
+        (BindingKind::ClassDefinition(_), Some(AnyNodeRef::StmtClassDef(class))) => {
+            (class_signature(class, source), docstring(&class.body))
+        }
+        (
+            BindingKind::AnnotatedAssignment | BindingKind::Annotation,
+            Some(AnyNodeRef::StmtAnnAssign(ast::AnnAssignStmt { annotation, .. })),
+        ) => (
+            format!(
+                "(variable) {}: {}",
+                binding.name,
+                source_text(annotation.as_ref(), source)
+            ),
+            None,
+        ),
+        (BindingKind::Parameter, _) => (
+            format!(
+                "(parameter) {}",
+                source_text(binding.definition_range, source)
+            ),
+            None,
+        ),
+        (BindingKind::TypeParam, _) => (format!("(type parameter) {}", binding.name), None),
+        (BindingKind::TypeAlias, _) => (source_text(binding.definition_range, source), None),
+        (BindingKind::Import(import), _) => (format!("(module) {}", import.qualified_name), None),
+        (BindingKind::FromImport(import), _) => (format!("(import) {}", import.member), None),
+        (BindingKind::Deletion, _) => return None,
+        _ => (format!("(variable) {}", binding.name), None),
+    };
+
+    Some(described)
+}
+
+/// Returns the level and the name of the module imported by the identifier at `offset`, either
+/// in an import statement or a name bound by one.
+fn imported_module(module: &ParsedModule, offset: TextSize) -> Option<(u32, String)> {
+    let nodes = python_ast_utils::nodes_at_offset(module.syntax(), offset);
+    let mut ancestors = nodes.iter().rev();
+
+    match ancestors.next()? {
+        AnyNodeRef::Alias(alias) => match ancestors.next()? {
+            AnyNodeRef::StmtImport(_) => {
+                let name = if alias.name.range.contains_inclusive(offset) {
+                    module_prefix(&alias.name, offset)
+                } else {
+                    alias.name.as_str()
+                };
+                Some((0, name.to_string()))
+            }
+            AnyNodeRef::StmtImportFrom(import_from) => Some((
+                import_from.level,
+                submodule(import_from.module.as_deref(), &alias.name),
+            )),
+            _ => None,
+        },
+        AnyNodeRef::StmtImportFrom(ast::ImportFromStmt {
+            module: Some(from),
+            level,
+            ..
+        }) if from.range.contains_inclusive(offset) => {
+            Some((*level, module_prefix(from, offset).to_string()))
+        }
+        AnyNodeRef::NameExpr(name) => {
+            let model = module.model();
+            let binding = model.binding(model.lookup(&name.id, model.scope_at(offset), offset)?);
+            match &binding.kind {
+                BindingKind::Import(import) => {
+                    Some((0, imported_module_name(binding, import).to_string()))
+                }
+                BindingKind::FromImport(import) => Some((
+                    import.level,
+                    submodule(import.module.as_deref(), &import.member),
+                )),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Returns the name of the submodule `member` of `module`.
+fn submodule(module: Option<&str>, member: &str) -> String {
+    match module {
+        Some(module) => format!("{module}.{member}"),
+        None => member.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use ruff_python_resolver::import_result::ImportType;
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::TextSize;
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+
+    use super::{hover, Hover};
+
+    fn hover_at(root: &Path, source: &str, cursor: &str) -> Option<Hover> {
+        let module = Arc::new(ParsedModule::new(
+            root.join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        ));
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let offset = TextSize::try_from(source.find(cursor).unwrap()).unwrap();
+        hover(&mut resolver, &module, offset)
+    }
+
+    #[test]
+    fn functions_and_classes() {
+        let source = r#"class Point[T](Base):
+    """A point.
+
+    With coordinates.
+    """
+
+    def move(self, dx: T, /, *, dy: T = 0) -> "Point[T]":
+        ...
+
+def distance(a, b=1, *points, **options) -> float:
+    '''Distance between points.'''
+
+Point().move(1)
+distance(1)
+"#;
+        let root = Path::new("/project");
+
+        let class = hover_at(root, source, "Point()").unwrap();
+        assert_eq!(class.signature, "class Point[T](Base)");
+        assert_eq!(
+            class.documentation.as_deref(),
+            Some("A point.\n\nWith coordinates.")
+        );
+
+        let method = hover_at(root, source, "move(self").unwrap();
+        assert_eq!(
+            method.signature,
+            r#"def move(self, dx: T, /, *, dy: T = 0) -> "Point[T]""#
+        );
+        assert_eq!(method.documentation, None);
+
+        let function = hover_at(root, source, "distance(1)").unwrap();
+        assert_eq!(
+            function.signature,
+            "def distance(a, b=1, *points, **options) -> float"
+        );
+        assert_eq!(
+            function.documentation.as_deref(),
+            Some("Distance between points.")
+        );
+    }
+
+    #[test]
+    fn variables_and_parameters() {
+        let source = "limit: int = 10\n\ndef f(value: str = ''):\n    return value, limit\n";
+        let root = Path::new("/project");
+
+        assert_eq!(
+            hover_at(root, source, "limit\n").unwrap().signature,
+            "(variable) limit: int"
+        );
+        assert_eq!(
+            hover_at(root, source, "value, ").unwrap().signature,
+            "(parameter) value: str"
+        );
+    }
+
+    #[test]
+    fn imported_modules() {
+        let root = tempfile::tempdir().unwrap();
+        let helpers = root.path().join("helpers.py");
+        std::fs::write(&helpers, "\"\"\"Helpers.\"\"\"\n").unwrap();
+
+        let source = "import helpers as h\n\nprint(h)\n";
+        for cursor in ["helpers", "h\n", "h)"] {
+            let hover = hover_at(root.path(), source, cursor).unwrap();
+            assert_eq!(hover.signature, "(module) helpers");
+            assert_eq!(hover.documentation.as_deref(), Some("Helpers."));
+            assert_eq!(hover.import, Some((helpers.clone(), ImportType::Local)));
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! The top-level imports of a module and the insertion of new ones.
+
+use std::fmt;
+use std::path::Path;
+
+use python_ast::{self as ast, Stmt};
+use ruff_python_resolver::import_result::ImportType;
+use ruff_source_file::Locator;
+use ruff_text_size::{Ranged, TextSize};
+use semantic_model::builtins::BUILTIN_MODULES;
+
+use crate::resolve::ImportResolver;
+
+use super::{Edit, ParsedModule};
+
+/// The groups imports are sorted in, separated by a blank line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum ImportSection {
+    Future,
+    StandardLibrary,
+    ThirdParty,
+    Local,
+}
+
+/// Returns the section of the module `module` imported with `level` leading dots from
+/// `source_file`. Modules that can't be resolved are assumed to be third-party modules.
+pub(crate) fn import_section(
+    import_resolver: &ImportResolver,
+    source_file: &Path,
+    level: u32,
+    module: &str,
+) -> ImportSection {
+    if level > 0 {
+        return ImportSection::Local;
+    }
+
+    let top_level = module.split('.').next().unwrap_or(module);
+    if top_level == "__future__" {
+        return ImportSection::Future;
+    }
+    if BUILTIN_MODULES.contains(&top_level) {
+        return ImportSection::StandardLibrary;
+    }
+
+    let result = import_resolver.resolve(source_file, level, module);
+    match result.import_type {
+        _ if !result.is_import_found => ImportSection::ThirdParty,
+        ImportType::BuiltIn => ImportSection::StandardLibrary,
+        ImportType::ThirdParty => ImportSection::ThirdParty,
+        ImportType::Local => ImportSection::Local,
+    }
+}
+
+/// An import to add to a module, either `import module` or `from module import name`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct NewImport {
+    pub(crate) module: String,
+    pub(crate) name: Option<String>,
+}
+
+impl fmt::Display for NewImport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "from {} import {name}", self.module),
+            None => write!(f, "import {}", self.module),
+        }
+    }
+}
+
+/// Returns the edit adding `import` to `module`.
+///
+/// A name imported from a module that's already imported from at the top level is added to
+/// the existing import. Otherwise the import is inserted after the last import of its section,
+/// or next to the imports of the closest section.
+pub(crate) fn add_import(
+    module: &ParsedModule,
+    import_resolver: &ImportResolver,
+    import: &NewImport,
+) -> Edit {
+    let body = &module.syntax().body;
+    let locator = Locator::new(module.source());
+
+    if let Some(name) = &import.name {
+        let existing = body.iter().find_map(|stmt| match stmt {
+            Stmt::ImportFrom(ast::ImportFromStmt {
+                module: Some(from),
+                names,
+                level: 0,
+                ..
+            }) if from.as_str() == import.module
+                && names.iter().all(|alias| &alias.name != "*") =>
+            {
+                names.last()
+            }
+            _ => None,
+        });
+        if let Some(last) = existing {
+            return Edit::insertion(format!(", {name}"), last.end());
+        }
+    }
+
+    let section = import_section(import_resolver, module.path(), 0, &import.module);
+    let imports: Vec<_> = body
+        .iter()
+        .filter_map(|stmt| {
+            let stmt_section = match stmt {
+                Stmt::Import(ast::ImportStmt { names, .. }) => import_section(
+                    import_resolver,
+                    module.path(),
+                    0,
+                    names.first()?.name.as_str(),
+                ),
+                Stmt::ImportFrom(ast::ImportFromStmt {
+                    module: from,
+                    level,
+                    ..
+                }) => import_section(
+                    import_resolver,
+                    module.path(),
+                    *level,
+                    from.as_deref().unwrap_or_default(),
+                ),
+                _ => return None,
+            };
+            Some((stmt_section, stmt))
+        })
+        .collect();
+
+    // After the last import of the same section, or of an earlier one.
+    if let Some((stmt_section, stmt)) = imports
+        .iter()
+        .filter(|(stmt_section, _)| *stmt_section <= section)
+        .max_by_key(|(stmt_section, stmt)| (*stmt_section, stmt.start()))
+    {
+        let separator = if *stmt_section == section { "" } else { "\n" };
+        return insert_after_line(&locator, stmt.end(), &format!("{separator}{import}\n"));
+    }
+
+    // Before the first import of a later section.
+    if let Some((_, stmt)) = imports.first() {
+        let start = locator.line_start(stmt.start());
+        return Edit::insertion(format!("{import}\n\n"), start);
+    }
+
+    // After the module's docstring, or before its first statement.
+    match body.first() {
+        Some(Stmt::Expr(ast::ExprStmt { value, range })) if value.is_string_literal_expr() => {
+            insert_after_line(&locator, range.end(), &format!("\n{import}\n"))
+        }
+        Some(stmt) => Edit::insertion(format!("{import}\n\n"), locator.line_start(stmt.start())),
+        None => Edit::insertion(format!("{import}\n"), TextSize::default()),
+    }
+}
+
+/// Returns the edit inserting `content` at the start of the line after `offset`.
+fn insert_after_line(locator: &Locator, offset: TextSize, content: &str) -> Edit {
+    let end = locator.full_line_end(offset);
+    // The last line may not end with a newline.
+    if locator.line_end(offset) == end {
+        Edit::insertion(format!("\n{}", content.trim_end()), end)
+    } else {
+        Edit::insertion(content.to_string(), end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_source_file::LineIndex;
+
+    use crate::analysis::ParsedModule;
+    use crate::resolve::ImportResolver;
+
+    use super::{add_import, NewImport};
+
+    fn apply(source: &str, module: &str, name: Option<&str>) -> String {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("local")).unwrap();
+        std::fs::write(root.join("local/__init__.py"), "").unwrap();
+
+        let module_path = root.join("main.py");
+        let parsed = ParsedModule::new(
+            module_path,
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        );
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let import = NewImport {
+            module: module.to_string(),
+            name: name.map(ToString::to_string),
+        };
+
+        let edit = add_import(&parsed, &import_resolver, &import);
+        let mut result = source.to_string();
+        result.replace_range(std::ops::Range::<usize>::from(edit.range), &edit.content);
+        result
+    }
+
+    #[test]
+    fn adds_to_existing_import() {
+        assert_eq!(
+            apply("from os import path\n\npath\n", "os", Some("sep")),
+            "from os import path, sep\n\npath\n"
+        );
+        assert_eq!(
+            apply("from os import (\n    path,\n)\n", "os", Some("sep")),
+            "from os import (\n    path, sep,\n)\n"
+        );
+    }
+
+    #[test]
+    fn inserts_in_section() {
+        let source = "import os\n\nimport numpy\n\nfrom local import x\n\nprint(x)\n";
+        assert_eq!(
+            apply(source, "sys", None),
+            "import os\nimport sys\n\nimport numpy\n\nfrom local import x\n\nprint(x)\n"
+        );
+        assert_eq!(
+            apply(source, "requests", None),
+            "import os\n\nimport numpy\nimport requests\n\nfrom local import x\n\nprint(x)\n"
+        );
+        assert_eq!(
+            apply(source, "local", Some("y")),
+            "import os\n\nimport numpy\n\nfrom local import x, y\n\nprint(x)\n"
+        );
+        assert_eq!(
+            apply("import numpy\n", "os", None),
+            "import os\n\nimport numpy\n"
+        );
+        assert_eq!(
+            apply("import os\nx = 1", "local", None),
+            "import os\n\nimport local\nx = 1"
+        );
+    }
+
+    #[test]
+    fn inserts_without_imports() {
+        assert_eq!(
+            apply("\"\"\"Docs.\"\"\"\nx = 1\n", "os", None),
+            "\"\"\"Docs.\"\"\"\n\nimport os\nx = 1\n"
+        );
+        assert_eq!(
+            apply("# comment\nx = 1\n", "os", None),
+            "# comment\nimport os\n\nx = 1\n"
+        );
+        assert_eq!(apply("", "os", None), "import os\n");
+    }
+}
@@ -0,0 +1,550 @@
+//! Inlay hints showing the parameters arguments are bound to and the types inferred for
+//! variables and return values.
+
+use std::sync::Arc;
+
+use python_ast::visitor::preorder::{PreorderVisitor, TraversalSignal};
+use python_ast::{self as ast, AnyNodeRef, Expr, Number, Operator, Stmt, UnaryOp};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use semantic_model::BindingKind;
+
+use crate::session::InlayHintsSettings;
+
+use super::definition::defining_node;
+use super::signature::source_text;
+use super::signature_help::called_function;
+use super::{DefinitionResolver, ParsedModule};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum InlayHintKind {
+    /// The name of the parameter an argument is bound to, e.g. `name=` in `greet(name="x")`.
+    Parameter,
+    /// The type of a variable or the return type of a function.
+    Type,
+}
+
+pub(crate) struct InlayHint {
+    pub(crate) offset: TextSize,
+    pub(crate) label: String,
+    pub(crate) kind: InlayHintKind,
+    /// The module and the range of the name of the parameter a parameter hint refers to.
+    pub(crate) target: Option<(Arc<ParsedModule>, TextRange)>,
+}
+
+/// Returns the inlay hints of `module` in `range`, of the kinds enabled in `settings`.
+///
+/// The literal positional arguments of a call, e.g. `1` or `"name"`, are preceded by the name
+/// of their parameter, the other arguments usually tell what they are. The assignments to
+/// variables and the functions without an annotation are followed by their type, when it can
+/// be inferred.
+pub(crate) fn inlay_hints(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    range: TextRange,
+    settings: &InlayHintsSettings,
+) -> Vec<InlayHint> {
+    let mut collector = HintedNodes {
+        range,
+        calls: Vec::new(),
+        assignments: Vec::new(),
+        functions: Vec::new(),
+    };
+    collector.visit_body(&module.syntax().body);
+
+    let mut hints = Vec::new();
+    if settings.parameter_names {
+        for call in collector.calls {
+            hints.extend(parameter_hints(resolver, module, call));
+        }
+    }
+    if settings.types {
+        for assignment in collector.assignments {
+            let Some(ty) = infer_type(resolver, module, &assignment.value) else {
+                continue;
+            };
+            hints.extend(assignment.targets.iter().filter_map(|target| {
+                target.as_name_expr().map(|name| InlayHint {
+                    offset: name.end(),
+                    label: format!(": {ty}"),
+                    kind: InlayHintKind::Type,
+                    target: None,
+                })
+            }));
+        }
+        for function in collector.functions {
+            if let Some(ty) = return_type(resolver, module, function) {
+                hints.push(InlayHint {
+                    offset: function.parameters.end(),
+                    label: format!(" -> {ty}"),
+                    kind: InlayHintKind::Type,
+                    target: None,
+                });
+            }
+        }
+    }
+
+    hints.retain(|hint| range.contains_inclusive(hint.offset));
+    hints.sort_by_key(|hint| hint.offset);
+    hints
+}
+
+/// Returns the hints naming the parameters the literal positional arguments of `call` are
+/// bound to.
+fn parameter_hints(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    call: &ast::CallExpr,
+) -> Vec<InlayHint> {
+    if call.arguments.args.is_empty() {
+        return Vec::new();
+    }
+    let Some(callee) = resolver
+        .resolve_expression(module, &call.func, 0)
+        .into_iter()
+        .next()
+    else {
+        return Vec::new();
+    };
+    let Some((_, function, skip_first)) = called_function(resolver, module, &call.func, &callee)
+    else {
+        return Vec::new();
+    };
+
+    let parameters = function
+        .parameters
+        .posonlyargs
+        .iter()
+        .chain(&function.parameters.args)
+        .skip(usize::from(skip_first))
+        .map(|parameter| &parameter.parameter);
+
+    let mut hints = Vec::new();
+    for (argument, parameter) in call.arguments.args.iter().zip(parameters) {
+        // The parameters following an unpacked argument aren't known.
+        if argument.is_starred_expr() {
+            break;
+        }
+        let name = parameter.name.as_str();
+        if name.starts_with('_') || !is_literal(argument) {
+            continue;
+        }
+        hints.push(InlayHint {
+            offset: argument.start(),
+            label: format!("{name}="),
+            kind: InlayHintKind::Parameter,
+            target: Some((callee.module.clone(), parameter.name.range())),
+        });
+    }
+    hints
+}
+
+/// Returns `true` if `argument` is a literal, a negated literal, e.g. `-1`, or a collection
+/// of literals, e.g. `[1, 2]`.
+fn is_literal(argument: &Expr) -> bool {
+    match argument {
+        Expr::NumberLiteral(_)
+        | Expr::StringLiteral(_)
+        | Expr::FString(_)
+        | Expr::BytesLiteral(_)
+        | Expr::BooleanLiteral(_)
+        | Expr::NoneLiteral(_)
+        | Expr::EllipsisLiteral(_) => true,
+        Expr::UnaryOp(ast::UnaryOpExpr { operand, .. }) => is_literal(operand),
+        Expr::List(ast::ListExpr { elts, .. })
+        | Expr::Tuple(ast::TupleExpr { elts, .. })
+        | Expr::Set(ast::SetExpr { elts, .. }) => elts.iter().all(is_literal),
+        Expr::Dict(ast::DictExpr { items, .. }) => items
+            .iter()
+            .all(|item| item.key.as_ref().is_some_and(is_literal) && is_literal(&item.value)),
+        _ => false,
+    }
+}
+
+/// Returns the type of the value of `expr`, if it can be inferred from the expression itself,
+/// e.g. a literal or the call of a class or of an annotated function.
+fn infer_type(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    expr: &Expr,
+) -> Option<String> {
+    let ty = match expr {
+        Expr::NumberLiteral(ast::NumberLiteralExpr { value, .. }) => match value {
+            Number::Int(_) => "int",
+            Number::Float => "float",
+            Number::Complex => "complex",
+        },
+        Expr::StringLiteral(_) | Expr::FString(_) => "str",
+        Expr::BytesLiteral(_) => "bytes",
+        Expr::BooleanLiteral(_) | Expr::Compare(_) => "bool",
+        Expr::NoneLiteral(_) => "None",
+        Expr::UnaryOp(ast::UnaryOpExpr {
+            op: UnaryOp::Not, ..
+        }) => "bool",
+        Expr::UnaryOp(ast::UnaryOpExpr { operand, .. }) => {
+            return infer_type(resolver, module, operand);
+        }
+        Expr::BinOp(ast::BinOpExpr {
+            left, op, right, ..
+        }) => {
+            let left = infer_type(resolver, module, left)?;
+            let right = infer_type(resolver, module, right)?;
+            return binary_operation_type(&left, *op, &right);
+        }
+        Expr::If(ast::IfExpr { body, orelse, .. }) => {
+            return union(resolver, module, [body.as_ref(), orelse.as_ref()]);
+        }
+        Expr::List(ast::ListExpr { elts, .. }) => {
+            return Some(format!("list[{}]", union(resolver, module, elts)?));
+        }
+        Expr::Set(ast::SetExpr { elts, .. }) => {
+            return Some(format!("set[{}]", union(resolver, module, elts)?));
+        }
+        Expr::Dict(ast::DictExpr { items, .. }) => {
+            // `**mapping` entries don't have a key.
+            let keys: Option<Vec<_>> = items.iter().map(|item| item.key.as_ref()).collect();
+            let key = union(resolver, module, keys?)?;
+            let value = union(resolver, module, items.iter().map(|item| &item.value))?;
+            return Some(format!("dict[{key}, {value}]"));
+        }
+        Expr::Tuple(ast::TupleExpr { elts, .. }) => {
+            if elts.is_empty() {
+                return Some("tuple[()]".to_string());
+            }
+            let elements: Option<Vec<_>> = elts
+                .iter()
+                .map(|element| infer_type(resolver, module, element))
+                .collect();
+            return Some(format!("tuple[{}]", elements?.join(", ")));
+        }
+        Expr::Call(call) => return call_type(resolver, module, call),
+        _ => return None,
+    };
+    Some(ty.to_string())
+}
+
+/// Returns the union of the types of `exprs`, e.g. `int | str`, `None` if there's no
+/// expression or if the type of one of them can't be inferred.
+fn union<'a>(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    exprs: impl IntoIterator<Item = &'a Expr>,
+) -> Option<String> {
+    let mut types: Vec<String> = Vec::new();
+    for expr in exprs {
+        let ty = infer_type(resolver, module, expr)?;
+        if !types.contains(&ty) {
+            types.push(ty);
+        }
+    }
+    (!types.is_empty()).then(|| types.join(" | "))
+}
+
+/// Returns the type of the result of a binary operation between numbers, or between values of
+/// the same type.
+fn binary_operation_type(left: &str, op: Operator, right: &str) -> Option<String> {
+    const NUMBERS: [&str; 3] = ["int", "float", "complex"];
+
+    let left_rank = NUMBERS.iter().position(|ty| *ty == left);
+    let right_rank = NUMBERS.iter().position(|ty| *ty == right);
+    if let (Some(left_rank), Some(right_rank)) = (left_rank, right_rank) {
+        let rank = left_rank.max(right_rank);
+        // The true division of integers is a float.
+        let rank = if op == Operator::Div {
+            rank.max(1)
+        } else {
+            rank
+        };
+        return Some(NUMBERS[rank].to_string());
+    }
+
+    match op {
+        Operator::Add if left == right => Some(left.to_string()),
+        // `"%s" % value`
+        Operator::Mod if left == "str" || left == "bytes" => Some(left.to_string()),
+        _ => None,
+    }
+}
+
+/// Returns the type returned by `call`: an instance of the called class, or the annotated
+/// return type of the called function.
+fn call_type(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    call: &ast::CallExpr,
+) -> Option<String> {
+    let callee = resolver
+        .resolve_expression(module, &call.func, 0)
+        .into_iter()
+        .next()?;
+    let binding = callee.as_binding()?;
+
+    match (&binding.kind, defining_node(&callee.module, binding)?) {
+        (BindingKind::ClassDefinition(_), _) => Some(binding.name.to_string()),
+        (BindingKind::FunctionDefinition(_), AnyNodeRef::StmtFunctionDef(function)) => {
+            let source = callee.module.source();
+            // The return type of an overload depends on the arguments, and the one of a
+            // coroutine function is the awaited type.
+            let is_overload = function
+                .decorator_list
+                .iter()
+                .any(|decorator| source_text(&decorator.expression, source).ends_with("overload"));
+            if function.is_async || is_overload {
+                return None;
+            }
+            let returns = source_text(function.returns.as_deref()?, source);
+            (returns != "Self").then_some(returns)
+        }
+        _ => None,
+    }
+}
+
+/// Returns the type returned by `function`, which has no return annotation, as the union of
+/// the types of its return statements.
+fn return_type(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    function: &ast::FunctionDefStmt,
+) -> Option<String> {
+    let mut returns = Returns::default();
+    returns.visit_body(&function.body);
+    if returns.is_generator {
+        return None;
+    }
+
+    // Stubs, e.g. `def f(): ...`, and functions that only raise have no meaningful return
+    // type.
+    let is_stub = function.body.iter().all(|stmt| {
+        matches!(stmt, Stmt::Expr(ast::ExprStmt { value, .. })
+            if value.is_ellipsis_literal_expr() || value.is_string_literal_expr())
+    });
+    if is_stub || (returns.values.is_empty() && always_exits(&function.body)) {
+        return None;
+    }
+
+    let mut types: Vec<String> = Vec::new();
+    let falls_through = !always_exits(&function.body);
+    for value in returns.values {
+        let ty = match value {
+            Some(value) => infer_type(resolver, module, value)?,
+            None => "None".to_string(),
+        };
+        if !types.contains(&ty) {
+            types.push(ty);
+        }
+    }
+    if falls_through && !types.iter().any(|ty| ty == "None") {
+        types.push("None".to_string());
+    }
+    Some(types.join(" | "))
+}
+
+/// Returns `true` if the end of `body` can't be reached, because all of its branches return or
+/// raise.
+fn always_exits(body: &[Stmt]) -> bool {
+    match body.last() {
+        Some(Stmt::Return(_) | Stmt::Raise(_)) => true,
+        Some(Stmt::If(ast::IfStmt {
+            body,
+            elif_else_clauses,
+            ..
+        })) => {
+            always_exits(body)
+                && elif_else_clauses
+                    .last()
+                    .is_some_and(|clause| clause.test.is_none())
+                && elif_else_clauses
+                    .iter()
+                    .all(|clause| always_exits(&clause.body))
+        }
+        Some(Stmt::With(ast::WithStmt { body, .. })) => always_exits(body),
+        Some(Stmt::Try(ast::TryStmt {
+            body,
+            handlers,
+            orelse,
+            finalbody,
+            ..
+        })) => {
+            always_exits(finalbody)
+                || ((always_exits(body) || always_exits(orelse))
+                    && handlers.iter().all(|handler| {
+                        let ast::ExceptHandler::ExceptHandler(handler) = handler;
+                        always_exits(&handler.body)
+                    }))
+        }
+        _ => false,
+    }
+}
+
+/// Collects the nodes in a range that inlay hints are shown for.
+struct HintedNodes<'a> {
+    range: TextRange,
+    calls: Vec<&'a ast::CallExpr>,
+    assignments: Vec<&'a ast::AssignStmt>,
+    functions: Vec<&'a ast::FunctionDefStmt>,
+}
+
+impl<'a> PreorderVisitor<'a> for HintedNodes<'a> {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        if node.range().intersect(self.range).is_none() {
+            return TraversalSignal::Skip;
+        }
+
+        match node {
+            AnyNodeRef::CallExpr(call) => self.calls.push(call),
+            AnyNodeRef::StmtAssign(assignment) => self.assignments.push(assignment),
+            AnyNodeRef::StmtFunctionDef(function) if function.returns.is_none() => {
+                self.functions.push(function);
+            }
+            _ => {}
+        }
+
+        TraversalSignal::Traverse
+    }
+}
+
+/// Collects the values returned by a function, `None` for a bare `return`, and whether it's a
+/// generator.
+#[derive(Default)]
+struct Returns<'a> {
+    values: Vec<Option<&'a Expr>>,
+    is_generator: bool,
+}
+
+impl<'a> PreorderVisitor<'a> for Returns<'a> {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        match node {
+            // The nested functions return their own values.
+            AnyNodeRef::StmtFunctionDef(_)
+            | AnyNodeRef::StmtClassDef(_)
+            | AnyNodeRef::LambdaExpr(_) => return TraversalSignal::Skip,
+            AnyNodeRef::StmtReturn(ast::ReturnStmt { value, .. }) => {
+                self.values.push(value.as_deref());
+            }
+            AnyNodeRef::YieldExpr(_) | AnyNodeRef::YieldFromExpr(_) => self.is_generator = true,
+            _ => {}
+        }
+
+        TraversalSignal::Traverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::{TextRange, TextSize};
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+    use crate::session::InlayHintsSettings;
+
+    use super::{inlay_hints, InlayHintKind};
+
+    /// Returns the hints of `source` as `(line, label)` pairs, with the parameter hints also
+    /// carrying the line of the parameter they refer to.
+    fn hints(source: &str, settings: &InlayHintsSettings) -> Vec<(usize, String, Option<usize>)> {
+        let index = LineIndex::from_source_text(source);
+        let module = Arc::new(ParsedModule::new(
+            Path::new("/project/main.py").to_path_buf(),
+            source.to_string(),
+            index.clone(),
+        ));
+        let import_resolver = ImportResolver::new(Path::new("/project").to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let range = TextRange::new(TextSize::default(), TextSize::of(source));
+        inlay_hints(&mut resolver, &module, range, settings)
+            .into_iter()
+            .map(|hint| {
+                assert_eq!(hint.kind == InlayHintKind::Parameter, hint.target.is_some());
+                let line = |offset| index.line_index(offset).get();
+                let target = hint.target.map(|(_, range)| line(range.start()));
+                (line(hint.offset), hint.label, target)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn parameter_names() {
+        let source = r#"
+def greet(name, greeting, /, *args, punctuation="!"):
+    return greeting + name
+
+class Greeter:
+    def __init__(self, greeting):
+        self.greeting = greeting
+
+    def greet(self, name):
+        return greet(name, self.greeting)
+
+greeting = "Hi"
+greet("Bob", greeting, "?", punctuation=".")
+Greeter.greet(Greeter("Hello"), "Alice")
+greet(*["Bob", "Hi"])
+greet(greeting, -1)
+"#;
+        let settings = InlayHintsSettings {
+            parameter_names: true,
+            types: false,
+        };
+        assert_eq!(
+            hints(source, &settings),
+            [
+                (13, "name=".to_string(), Some(2)),
+                (14, "greeting=".to_string(), Some(6)),
+                (14, "name=".to_string(), Some(9)),
+                (16, "greeting=".to_string(), Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn inferred_types() {
+        let source = r#"
+class Point: ...
+
+def origin():
+    return Point()
+
+def parse(text: str) -> int | None: ...
+
+def sign(x):
+    if x > 0:
+        return 1
+    elif x < 0:
+        return -1.5
+
+def numbers():
+    yield 1
+
+def fail():
+    raise ValueError
+
+x = y = 1 / 2
+names = ["a", f"b"]
+mapping = {"a": (1, None)}
+point = origin()
+parsed = parse("1")
+unknown = x
+"#;
+        let settings = InlayHintsSettings {
+            parameter_names: false,
+            types: true,
+        };
+        assert_eq!(
+            hints(source, &settings),
+            [
+                (4, " -> Point".to_string(), None),
+                (9, " -> int | float | None".to_string(), None),
+                (21, ": float".to_string(), None),
+                (21, ": float".to_string(), None),
+                (22, ": list[str]".to_string(), None),
+                (23, ": dict[str, tuple[int, None]]".to_string(), None),
+                (25, ": int | None".to_string(), None),
+            ]
+        );
+    }
+}
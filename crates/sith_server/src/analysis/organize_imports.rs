@@ -0,0 +1,472 @@
+//! Sorting and merging the imports at the top of a module.
+
+use std::cmp::Reverse;
+
+use python_ast::{self as ast, Stmt};
+use ruff_source_file::{find_newline, LineEnding, Locator};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::resolve::ImportResolver;
+
+use super::imports::{import_section, ImportSection};
+use super::ParsedModule;
+
+/// The length above which a `from` import is split over multiple lines.
+const LINE_LENGTH: usize = 88;
+
+/// Returns the source of `module` with the imports at its top sorted, or `None` if they're
+/// already sorted.
+///
+/// The imports following the module's docstring are grouped by section, merged and
+/// deduplicated. The comments on the lines above an import and at the end of its line move
+/// with it. Imports after the first other statement, such as the ones in an
+/// `if TYPE_CHECKING:` block, are left as they are.
+pub(crate) fn organize_imports(
+    module: &ParsedModule,
+    import_resolver: &ImportResolver,
+) -> Option<String> {
+    let body = &module.syntax().body;
+    let source = module.source();
+    let locator = Locator::new(source);
+
+    let start = match body.first() {
+        Some(Stmt::Expr(ast::ExprStmt { value, .. })) if value.is_string_literal_expr() => 1,
+        _ => 0,
+    };
+    let stmts: Vec<_> = body[start..]
+        .iter()
+        .take_while(|stmt| matches!(stmt, Stmt::Import(_) | Stmt::ImportFrom(_)))
+        .collect();
+    let (first, last) = (stmts.first()?, stmts.last()?);
+
+    let mut entries = Vec::new();
+    let mut previous_end = None;
+    for stmt in &stmts {
+        let line_start = locator.line_start(stmt.start());
+        // Statements sharing their line with another one, e.g. `import a; import b`.
+        if line_start != stmt.start() {
+            return None;
+        }
+        let trailing = locator.slice(TextRange::new(stmt.end(), locator.line_end(stmt.end())));
+        let trailing = trailing.trim();
+        if !trailing.is_empty() && !trailing.starts_with('#') {
+            return None;
+        }
+
+        // The comments between the previous import and this one, the comments above the first
+        // import stay at the top of the module.
+        let leading_comments = previous_end
+            .map(|end| {
+                locator
+                    .slice(TextRange::new(end, line_start))
+                    .lines()
+                    .map(str::trim)
+                    .filter(|line| line.starts_with('#'))
+                    .map(ToString::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        previous_end = Some(locator.full_line_end(stmt.end()));
+
+        let text = locator.slice(stmt.range());
+        // The comments inside of a statement would be lost when rewriting it.
+        let verbatim = text.contains('#').then(|| text.to_string());
+        let statements = match stmt {
+            // `import a, b` is split in `import a` and `import b`.
+            Stmt::Import(ast::ImportStmt { names, .. }) if verbatim.is_none() => names
+                .iter()
+                .map(|alias| Statement::Import(ImportAlias::from(alias)))
+                .collect(),
+            _ => vec![Statement::from_stmt(stmt)],
+        };
+
+        let mut comments = Some((
+            leading_comments,
+            (!trailing.is_empty()).then(|| trailing.to_string()),
+        ));
+        for statement in statements {
+            let section = match &statement {
+                Statement::Import(alias) => {
+                    import_section(import_resolver, module.path(), 0, &alias.name)
+                }
+                Statement::ImportFrom {
+                    level,
+                    module: from,
+                    ..
+                } => import_section(import_resolver, module.path(), *level, from),
+            };
+            let (leading_comments, trailing_comment) = comments.take().unwrap_or_default();
+            entries.push(ImportEntry {
+                section,
+                statement,
+                leading_comments,
+                trailing_comment,
+                verbatim: verbatim.clone(),
+            });
+        }
+    }
+
+    let mut merged: Vec<ImportEntry> = Vec::new();
+    for entry in entries {
+        match merged
+            .iter_mut()
+            .find(|existing| existing.can_merge(&entry))
+        {
+            Some(existing) => existing.merge(entry),
+            None => merged.push(entry),
+        }
+    }
+    merged.sort_by_cached_key(ImportEntry::sort_key);
+
+    let newline = find_newline(source).map_or(LineEnding::Lf, |(_, ending)| ending);
+    let mut organized = String::new();
+    let mut section = None;
+    for entry in &mut merged {
+        if section.is_some_and(|section| section != entry.section) {
+            organized.push_str(newline.as_str());
+        }
+        section = Some(entry.section);
+        entry.format(&mut organized, newline.as_str());
+    }
+
+    let range = TextRange::new(
+        locator.line_start(first.start()),
+        locator.full_line_end(last.end()),
+    );
+    // The last import may be on the module's last line, without a newline.
+    if locator.line_end(last.end()) == range.end() {
+        organized.truncate(organized.trim_end().len());
+    }
+
+    let mut result = source.to_string();
+    result.replace_range(std::ops::Range::<usize>::from(range), &organized);
+    (result != source).then_some(result)
+}
+
+/// A single import statement, along with its comments.
+struct ImportEntry {
+    section: ImportSection,
+    statement: Statement,
+    leading_comments: Vec<String>,
+    trailing_comment: Option<String>,
+    /// The source of a statement containing comments, which is kept as it is.
+    verbatim: Option<String>,
+}
+
+impl ImportEntry {
+    fn can_merge(&self, other: &Self) -> bool {
+        if self.verbatim.is_some() || other.verbatim.is_some() {
+            return false;
+        }
+        match (&self.statement, &other.statement) {
+            (Statement::Import(alias), Statement::Import(other)) => alias == other,
+            (
+                Statement::ImportFrom {
+                    level,
+                    module,
+                    names,
+                },
+                Statement::ImportFrom {
+                    level: other_level,
+                    module: other_module,
+                    names: other_names,
+                },
+            ) => {
+                // Wildcard imports are only merged with each other.
+                level == other_level
+                    && module == other_module
+                    && is_wildcard(names) == is_wildcard(other_names)
+            }
+            _ => false,
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        self.leading_comments.extend(other.leading_comments);
+        match (&self.trailing_comment, other.trailing_comment) {
+            (None, comment) => self.trailing_comment = comment,
+            (Some(_), Some(comment)) => self.leading_comments.push(comment),
+            (Some(_), None) => {}
+        }
+        if let (
+            Statement::ImportFrom { names, .. },
+            Statement::ImportFrom {
+                names: other_names, ..
+            },
+        ) = (&mut self.statement, other.statement)
+        {
+            for alias in other_names {
+                if !names.contains(&alias) {
+                    names.push(alias);
+                }
+            }
+        }
+    }
+
+    /// Orders the `import` statements of a section before its `from` imports, and the relative
+    /// imports after the absolute ones, from the furthest to the closest module.
+    fn sort_key(&self) -> (ImportSection, bool, bool, Reverse<u32>, String, String) {
+        match &self.statement {
+            Statement::Import(alias) => (
+                self.section,
+                false,
+                false,
+                Reverse(0),
+                alias.name.to_lowercase(),
+                alias.to_string(),
+            ),
+            Statement::ImportFrom { level, module, .. } => (
+                self.section,
+                true,
+                *level > 0,
+                Reverse(*level),
+                module.to_lowercase(),
+                module.clone(),
+            ),
+        }
+    }
+
+    fn format(&mut self, output: &mut String, newline: &str) {
+        for comment in &self.leading_comments {
+            output.push_str(comment);
+            output.push_str(newline);
+        }
+
+        let trailing_comment = self
+            .trailing_comment
+            .as_ref()
+            .map(|comment| format!("  {comment}"))
+            .unwrap_or_default();
+
+        if let Some(verbatim) = &self.verbatim {
+            output.push_str(verbatim);
+            output.push_str(&trailing_comment);
+            output.push_str(newline);
+            return;
+        }
+
+        match &mut self.statement {
+            Statement::Import(alias) => {
+                output.push_str(&format!("import {alias}{trailing_comment}{newline}"));
+            }
+            Statement::ImportFrom {
+                level,
+                module,
+                names,
+            } => {
+                names.sort_by_cached_key(|alias| member_key(&alias.name));
+                let from = format!("from {}{module} import", ".".repeat(*level as usize));
+                let names: Vec<_> = names.iter().map(ToString::to_string).collect();
+
+                let line = format!("{from} {}", names.join(", "));
+                if line.len() + trailing_comment.len() <= LINE_LENGTH || names.len() == 1 {
+                    output.push_str(&format!("{line}{trailing_comment}{newline}"));
+                } else {
+                    output.push_str(&format!("{from} ({trailing_comment}{newline}"));
+                    for name in names {
+                        output.push_str(&format!("    {name},{newline}"));
+                    }
+                    output.push_str(&format!("){newline}"));
+                }
+            }
+        }
+    }
+}
+
+enum Statement {
+    Import(ImportAlias),
+    ImportFrom {
+        level: u32,
+        /// The module, without the leading dots of the relative imports.
+        module: String,
+        names: Vec<ImportAlias>,
+    },
+}
+
+impl Statement {
+    /// Returns the statement of an import, `import a, b` is represented by its first module.
+    fn from_stmt(stmt: &Stmt) -> Self {
+        match stmt {
+            Stmt::Import(ast::ImportStmt { names, .. }) => {
+                Self::Import(ImportAlias::from(&names[0]))
+            }
+            Stmt::ImportFrom(ast::ImportFromStmt {
+                module,
+                names,
+                level,
+                ..
+            }) => Self::ImportFrom {
+                level: *level,
+                module: module.as_deref().unwrap_or_default().to_string(),
+                names: names.iter().map(ImportAlias::from).collect(),
+            },
+            _ => unreachable!("only import statements are organized"),
+        }
+    }
+}
+
+/// An imported name, e.g. `a` or `a as b`.
+#[derive(PartialEq, Eq)]
+struct ImportAlias {
+    name: String,
+    asname: Option<String>,
+}
+
+impl From<&ast::Alias> for ImportAlias {
+    fn from(alias: &ast::Alias) -> Self {
+        Self {
+            name: alias.name.to_string(),
+            asname: alias.asname.as_ref().map(ToString::to_string),
+        }
+    }
+}
+
+impl std::fmt::Display for ImportAlias {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.asname {
+            Some(asname) => write!(f, "{} as {asname}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+fn is_wildcard(names: &[ImportAlias]) -> bool {
+    names.iter().any(|alias| alias.name == "*")
+}
+
+/// Orders the names imported from a module by type: constants, then classes, then the
+/// other names.
+fn member_key(name: &str) -> (u8, String, String) {
+    let kind = if name.len() > 1 && name.chars().all(|c| !c.is_lowercase()) {
+        0
+    } else if name.starts_with(char::is_uppercase) {
+        1
+    } else {
+        2
+    };
+    (kind, name.to_lowercase(), name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use ruff_source_file::LineIndex;
+
+    use crate::analysis::ParsedModule;
+    use crate::resolve::ImportResolver;
+
+    use super::organize_imports;
+
+    fn organize(source: &str) -> Option<String> {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("local")).unwrap();
+        std::fs::write(root.join("local/__init__.py"), "").unwrap();
+
+        let module = ParsedModule::new(
+            root.join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        );
+        organize_imports(&module, &ImportResolver::new(root.to_path_buf()))
+    }
+
+    #[test]
+    fn sorts_and_groups_by_section() {
+        let source = r#""""Docs."""
+from local import b
+import numpy
+import sys, os
+from __future__ import annotations
+from . import sibling
+from local import a as c, b
+
+print(a)
+"#;
+        assert_eq!(
+            organize(source).unwrap(),
+            r#""""Docs."""
+from __future__ import annotations
+
+import os
+import sys
+
+import numpy
+
+from local import a as c, b
+from . import sibling
+
+print(a)
+"#
+        );
+    }
+
+    #[test]
+    fn keeps_comments_and_removes_duplicates() {
+        let source = "\
+# Header.
+import sys  # System.
+# Operating system.
+import os
+import sys
+from typing import (
+    Any,  # Anything.
+)
+from typing import List
+import sys
+";
+        assert_eq!(
+            organize(source).unwrap(),
+            "\
+# Header.
+# Operating system.
+import os
+import sys  # System.
+from typing import (
+    Any,  # Anything.
+)
+from typing import List
+"
+        );
+    }
+
+    #[test]
+    fn leaves_type_checking_blocks() {
+        let source = "\
+import sys
+from typing import TYPE_CHECKING
+
+if TYPE_CHECKING:
+    import os
+    import abc
+";
+        assert_eq!(organize(source), None);
+        assert_eq!(
+            organize("import sys\nimport abc\n\nif True:\n    import os\n    import abc\n")
+                .unwrap(),
+            "import abc\nimport sys\n\nif True:\n    import os\n    import abc\n"
+        );
+    }
+
+    #[test]
+    fn wraps_long_imports() {
+        let source = concat!(
+            "from typing import Any, Callable, Dict, Iterable, Iterator, List, Optional, TypeVar, ",
+            "cast, overload"
+        );
+        assert_eq!(
+            organize(source).unwrap(),
+            "from typing import (
+    Any,
+    Callable,
+    Dict,
+    Iterable,
+    Iterator,
+    List,
+    Optional,
+    TypeVar,
+    cast,
+    overload,
+)"
+        );
+    }
+}
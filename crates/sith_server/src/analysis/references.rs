@@ -0,0 +1,472 @@
+//! Search for the references to a symbol across the modules of a workspace.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use python_ast::name::Name;
+use python_ast::visitor::preorder::{PreorderVisitor, TraversalSignal};
+use python_ast::{self as ast, AnyNodeRef};
+use ruff_source_file::LineIndex;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use rustc_hash::{FxHashMap, FxHashSet};
+use semantic_model::{BindingId, ScopeId};
+
+use super::definition::module_name;
+use super::{Definition, DefinitionKind, DefinitionResolver, ParsedModule};
+
+/// A read, write or deletion of a symbol.
+pub(crate) struct Reference {
+    pub(crate) module: Arc<ParsedModule>,
+    pub(crate) range: TextRange,
+    /// Whether this is the place the symbol is defined.
+    pub(crate) is_declaration: bool,
+}
+
+/// Identifies a symbol independently of which of its bindings a reference resolves to, e.g.
+/// both assignments of `x = 1; x = 2` define the same symbol.
+#[derive(PartialEq, Eq, Hash)]
+enum Symbol {
+    Module(PathBuf),
+    Binding(PathBuf, ScopeId, Name),
+}
+
+impl Symbol {
+    fn new(definition: &Definition) -> Self {
+        let path = definition.module.path().to_path_buf();
+        match definition.as_binding() {
+            None => Symbol::Module(path),
+            Some(binding) => Symbol::Binding(path, binding.scope, binding.name.clone()),
+        }
+    }
+}
+
+/// Finds the references to the symbol at `offset` in `module`, searching `module` and the
+/// modules at `paths`.
+///
+/// Modules refer to a symbol by the name it's defined with or by the aliases it's imported
+/// as, e.g. `from foo import bar as baz`, so a module is only parsed if its source contains
+/// one of those names.
+pub(crate) fn find_references(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    offset: TextSize,
+    paths: &[PathBuf],
+) -> Vec<Reference> {
+    let definitions = resolver.definitions_at(module, offset);
+    if definitions.is_empty() {
+        return Vec::new();
+    }
+
+    let symbols: FxHashSet<Symbol> = definitions.iter().map(Symbol::new).collect();
+    let declarations: FxHashSet<(&Path, TextRange)> = definitions
+        .iter()
+        .filter(|definition| definition.kind != DefinitionKind::Module)
+        .map(|definition| (definition.module.path(), definition.range))
+        .collect();
+
+    let mut names: FxHashSet<String> = definitions.iter().filter_map(symbol_name).collect();
+
+    let paths: Vec<&Path> = std::iter::once(module.path())
+        .chain(
+            paths
+                .iter()
+                .map(PathBuf::as_path)
+                .filter(|path| *path != module.path()),
+        )
+        .collect();
+    let mut candidates_by_path: FxHashMap<&Path, Vec<Candidate>> = FxHashMap::default();
+    // Whether the imported names that have an alias refer to the symbol.
+    let mut imports: FxHashMap<(&Path, TextRange), bool> = FxHashMap::default();
+    // Whether the local bindings the names resolve to refer to the symbol, every use of a
+    // binding in a module resolves to the same definitions.
+    let mut origins: FxHashMap<(&Path, BindingId), bool> = FxHashMap::default();
+
+    // Each alias found can reveal other aliases in modules that were already searched, e.g.
+    // `from other import h as g` after `from helpers import helper as h`. Only the imports are
+    // resolved until no new alias is found.
+    loop {
+        let known_names = names.len();
+
+        for &path in &paths {
+            let Some(module) = module_containing(resolver, path, &names) else {
+                continue;
+            };
+            let candidates = candidates_by_path
+                .entry(path)
+                .or_insert_with(|| candidates(&module));
+
+            for candidate in candidates.iter() {
+                let Some(alias) = candidate.alias else {
+                    continue;
+                };
+                if !names.contains(&module.source()[candidate.range]) {
+                    continue;
+                }
+
+                let is_reference = *imports
+                    .entry((path, candidate.range))
+                    .or_insert_with(|| refers_to(resolver, &module, candidate.range, &symbols));
+                if is_reference {
+                    names.insert(module.source()[alias].to_string());
+                }
+            }
+        }
+
+        if names.len() == known_names {
+            break;
+        }
+    }
+
+    let mut references = Vec::new();
+    for &path in &paths {
+        let Some(module) = module_containing(resolver, path, &names) else {
+            continue;
+        };
+        let candidates = candidates_by_path
+            .entry(path)
+            .or_insert_with(|| candidates(&module));
+
+        for candidate in candidates.iter() {
+            if !names.contains(&module.source()[candidate.range]) {
+                continue;
+            }
+
+            let is_reference = match imports.get(&(path, candidate.range)) {
+                Some(&is_reference) => is_reference,
+                None => match local_binding(&module, candidate) {
+                    Some(id) => *origins
+                        .entry((path, id))
+                        .or_insert_with(|| refers_to(resolver, &module, candidate.range, &symbols)),
+                    None => refers_to(resolver, &module, candidate.range, &symbols),
+                },
+            };
+            if !is_reference {
+                continue;
+            }
+
+            references.push(Reference {
+                is_declaration: declarations.contains(&(module.path(), candidate.range)),
+                module: module.clone(),
+                range: candidate.range,
+            });
+        }
+    }
+
+    references
+}
+
+/// Returns `true` if the identifier at `range` in `module` refers to one of `symbols`.
+fn refers_to(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    range: TextRange,
+    symbols: &FxHashSet<Symbol>,
+) -> bool {
+    resolver
+        .definitions_at(module, range.start())
+        .iter()
+        .any(|definition| symbols.contains(&Symbol::new(definition)))
+}
+
+/// Returns the binding of `module` the name `candidate` resolves to, `None` if it isn't a
+/// name or is unbound.
+fn local_binding(module: &ParsedModule, candidate: &Candidate) -> Option<BindingId> {
+    if !candidate.is_name {
+        return None;
+    }
+    let model = module.model();
+    let offset = candidate.range.start();
+    model.lookup(
+        &module.source()[candidate.range],
+        model.scope_at(offset),
+        offset,
+    )
+}
+
+/// Returns the name a symbol is referred to by in the module defining it or in the modules
+/// importing it.
+fn symbol_name(definition: &Definition) -> Option<String> {
+    if let Some(binding) = definition.as_binding() {
+        return Some(binding.name.to_string());
+    }
+
+    module_name(definition.module.path()).map(ToString::to_string)
+}
+
+/// Returns the module at `path` if its source mentions one of `names`. Open documents are
+/// parsed from their unsaved contents.
+fn module_containing(
+    resolver: &mut DefinitionResolver,
+    path: &Path,
+    names: &FxHashSet<String>,
+) -> Option<Arc<ParsedModule>> {
+    if let Some(document) = resolver.document(path) {
+        if !names
+            .iter()
+            .any(|name| document.contents().contains(name.as_str()))
+        {
+            return None;
+        }
+    } else if !resolver.contains(path) {
+        let source = std::fs::read_to_string(path).ok()?;
+        if !names.iter().any(|name| source.contains(name.as_str())) {
+            return None;
+        }
+        let index = LineIndex::from_source_text(&source);
+        resolver.insert(Arc::new(ParsedModule::new(
+            path.to_path_buf(),
+            source,
+            index,
+        )));
+    }
+
+    let module = resolver.module(path)?;
+    names
+        .iter()
+        .any(|name| module.source().contains(name.as_str()))
+        .then_some(module)
+}
+
+/// Returns the range of the identifier at `offset` that may refer to a symbol.
+pub(super) fn identifier_at(module: &ParsedModule, offset: TextSize) -> Option<TextRange> {
+    candidates(module)
+        .into_iter()
+        .map(|candidate| candidate.range)
+        .find(|range| range.contains_inclusive(offset))
+}
+
+/// An identifier that may refer to a symbol.
+struct Candidate {
+    range: TextRange,
+    /// The alias the identifier is imported as, e.g. `baz` in `from foo import bar as baz`.
+    alias: Option<TextRange>,
+    /// Whether the identifier is a name expression, which resolves like the other uses of the
+    /// binding it refers to.
+    is_name: bool,
+}
+
+/// Returns every identifier of `module` that may refer to a symbol: names, attributes, the
+/// segments of imported module names and the names bound by definitions.
+fn candidates(module: &ParsedModule) -> Vec<Candidate> {
+    let mut collector = CandidateCollector::default();
+    collector.visit_body(&module.syntax().body);
+
+    let mut seen = FxHashSet::default();
+    let mut candidates = collector.candidates;
+    candidates.extend(module.model().bindings().map(|(_, binding)| Candidate {
+        range: binding.range,
+        alias: None,
+        is_name: false,
+    }));
+    candidates.retain(|candidate| seen.insert(candidate.range));
+    candidates.sort_by_key(|candidate| candidate.range.start());
+    candidates
+}
+
+#[derive(Default)]
+struct CandidateCollector {
+    candidates: Vec<Candidate>,
+}
+
+impl CandidateCollector {
+    fn push(&mut self, range: TextRange) {
+        self.candidates.push(Candidate {
+            range,
+            alias: None,
+            is_name: false,
+        });
+    }
+
+    /// Pushes each segment of a dotted module name, the last one is imported as `alias`.
+    fn push_dotted(&mut self, name: &ast::Identifier, alias: Option<TextRange>) {
+        let mut start = name.start();
+        let mut segments = name.as_str().split('.').peekable();
+        while let Some(segment) = segments.next() {
+            let range = TextRange::at(start, TextSize::of(segment));
+            let is_last = segments.peek().is_none();
+            self.candidates.push(Candidate {
+                range,
+                alias: if is_last { alias } else { None },
+                is_name: false,
+            });
+            start = range.end() + TextSize::of('.');
+        }
+    }
+}
+
+impl<'a> PreorderVisitor<'a> for CandidateCollector {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        match node {
+            AnyNodeRef::NameExpr(name) => self.candidates.push(Candidate {
+                range: name.range,
+                alias: None,
+                is_name: true,
+            }),
+            AnyNodeRef::AttributeExpr(attribute) => self.push(attribute.attr.range),
+            AnyNodeRef::Alias(alias) => {
+                let asname = alias.asname.as_ref().map(Ranged::range);
+                self.push_dotted(&alias.name, asname);
+                if let Some(asname) = asname {
+                    self.push(asname);
+                }
+            }
+            AnyNodeRef::Keyword(ast::Keyword { arg: Some(arg), .. }) => self.push(arg.range),
+            AnyNodeRef::StmtImportFrom(ast::ImportFromStmt {
+                module: Some(module),
+                ..
+            }) => self.push_dotted(module, None),
+            AnyNodeRef::StmtGlobal(ast::GlobalStmt { names, .. })
+            | AnyNodeRef::StmtNonlocal(ast::NonlocalStmt { names, .. }) => {
+                for name in names {
+                    self.push(name.range);
+                }
+            }
+            _ => {}
+        }
+
+        TraversalSignal::Traverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+    use std::sync::Arc;
+
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::TextSize;
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::edit::Document;
+    use crate::resolve::ImportResolver;
+
+    use super::find_references;
+
+    /// Returns the line and the text of each reference to the symbol at `cursor`.
+    fn references(
+        root: &Path,
+        main: &str,
+        cursor: &str,
+        paths: &[PathBuf],
+    ) -> Vec<(String, usize, String)> {
+        let module = Arc::new(ParsedModule::new(
+            root.join("main.py"),
+            main.to_string(),
+            LineIndex::from_source_text(main),
+        ));
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let offset = TextSize::try_from(main.find(cursor).unwrap()).unwrap();
+        find_references(&mut resolver, &module, offset, paths)
+            .into_iter()
+            .map(|reference| {
+                let file = reference
+                    .module
+                    .path()
+                    .file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string();
+                let source = reference.module.source();
+                let line = source[..reference.range.start().to_usize()]
+                    .matches('\n')
+                    .count()
+                    + 1;
+                (file, line, source[reference.range].to_string())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn local_references() {
+        let root = Path::new("/project");
+        let source = r#"x = 1
+print(x)
+x += 2
+del x
+
+def f(x):
+    return x
+"#;
+
+        assert_eq!(
+            references(root, source, "x", &[]),
+            vec![
+                ("main.py".to_string(), 1, "x".to_string()),
+                ("main.py".to_string(), 2, "x".to_string()),
+                ("main.py".to_string(), 3, "x".to_string()),
+                ("main.py".to_string(), 4, "x".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_across_modules() {
+        let root = tempfile::tempdir().unwrap();
+        let helpers = root.path().join("helpers.py");
+        let other = root.path().join("other.py");
+        let unrelated = root.path().join("unrelated.py");
+        std::fs::write(&helpers, "def helper():\n    pass\n").unwrap();
+        std::fs::write(
+            &other,
+            "from helpers import helper as h\nimport helpers\n\nh()\nhelpers.helper()\n",
+        )
+        .unwrap();
+        std::fs::write(&unrelated, "def helper():\n    pass\n\nhelper()\n").unwrap();
+
+        let main = "from other import h\n\nh()\n";
+        let paths = vec![helpers, other, unrelated];
+
+        let mut found = references(root.path(), main, "h()", &paths);
+        found.sort();
+        assert_eq!(
+            found,
+            vec![
+                ("helpers.py".to_string(), 1, "helper".to_string()),
+                ("main.py".to_string(), 1, "h".to_string()),
+                ("main.py".to_string(), 3, "h".to_string()),
+                ("other.py".to_string(), 1, "h".to_string()),
+                ("other.py".to_string(), 1, "helper".to_string()),
+                ("other.py".to_string(), 4, "h".to_string()),
+                ("other.py".to_string(), 5, "helper".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn references_in_open_documents() {
+        let root = tempfile::tempdir().unwrap();
+        let helpers = root.path().join("helpers.py");
+        let other = root.path().join("other.py");
+        std::fs::write(&helpers, "def helper():\n    pass\n").unwrap();
+        std::fs::write(&other, "import helpers\n").unwrap();
+
+        // The unsaved contents of `other.py` are searched instead of the file on disk.
+        let document = Document::new(
+            "import os\nfrom helpers import helper\n\nhelper()\n".to_string(),
+            1,
+        );
+        let main = "from helpers import helper\n";
+        let module = Arc::new(ParsedModule::new(
+            root.path().join("main.py"),
+            main.to_string(),
+            LineIndex::from_source_text(main),
+        ));
+        let import_resolver = ImportResolver::new(root.path().to_path_buf());
+        let mut resolver =
+            DefinitionResolver::new(&import_resolver).with_documents([(other.clone(), &document)]);
+        resolver.insert(module.clone());
+
+        let offset = TextSize::try_from(main.find("helper\n").unwrap()).unwrap();
+        let mut found: Vec<_> = find_references(&mut resolver, &module, offset, &[helpers, other])
+            .into_iter()
+            .filter(|reference| reference.module.path().ends_with("other.py"))
+            .map(|reference| reference.range.start().to_usize())
+            .collect();
+        found.sort_unstable();
+
+        assert_eq!(found, vec![30, 38]);
+    }
+}
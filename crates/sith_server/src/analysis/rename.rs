@@ -0,0 +1,431 @@
+//! Renaming of symbols across the modules of a workspace.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use python_ast::AnyNodeRef;
+use ruff_text_size::{TextRange, TextSize};
+use rustc_hash::FxHashSet;
+use semantic_model::builtins::{BUILTIN_KEYWORDS, BUILTIN_SYMBOLS};
+use semantic_model::{BindingId, BindingKind};
+
+use super::references::identifier_at;
+use super::{find_references, DefinitionKind, DefinitionResolver, ParsedModule};
+
+/// The reason a rename was rejected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RenameError {
+    Builtin(String),
+    Keyword(String),
+    StringLiteral,
+    Module(String),
+    External(String),
+    InvalidName(String),
+}
+
+impl fmt::Display for RenameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenameError::Builtin(name) => write!(f, "Cannot rename the builtin `{name}`"),
+            RenameError::Keyword(name) => write!(f, "Cannot rename the keyword `{name}`"),
+            RenameError::StringLiteral => write!(f, "Cannot rename a string literal"),
+            RenameError::Module(name) => write!(f, "Cannot rename the module `{name}`"),
+            RenameError::External(name) => {
+                write!(
+                    f,
+                    "Cannot rename `{name}`, it's defined outside of the workspace"
+                )
+            }
+            RenameError::InvalidName(name) => write!(f, "`{name}` is not a valid identifier"),
+        }
+    }
+}
+
+/// Returns the range of the identifier at `offset` if the symbol it refers to can be renamed,
+/// `None` if there's no symbol at `offset`.
+pub(crate) fn prepare_rename(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    offset: TextSize,
+) -> Result<Option<TextRange>, RenameError> {
+    let is_string = python_ast_utils::node_at_offset(module.syntax(), offset).is_some_and(|node| {
+        matches!(
+            node,
+            AnyNodeRef::StringLiteralExpr(_)
+                | AnyNodeRef::StringLiteral(_)
+                | AnyNodeRef::BytesLiteralExpr(_)
+                | AnyNodeRef::BytesLiteral(_)
+                | AnyNodeRef::FStringExpr(_)
+                | AnyNodeRef::FString(_)
+                | AnyNodeRef::FStringLiteralElement(_)
+        )
+    });
+    if is_string {
+        return Err(RenameError::StringLiteral);
+    }
+
+    let Some(identifier) = identifier_at(module, offset) else {
+        let word = word_at(module.source(), offset);
+        if BUILTIN_KEYWORDS.contains(&word) {
+            return Err(RenameError::Keyword(word.to_string()));
+        }
+        return Ok(None);
+    };
+
+    let name = &module.source()[identifier];
+    let definitions = resolver.definitions_at(module, identifier.start());
+    if definitions.is_empty() {
+        if BUILTIN_SYMBOLS.contains(&name) {
+            return Err(RenameError::Builtin(name.to_string()));
+        }
+        return Ok(None);
+    }
+
+    let root = resolver.root().to_path_buf();
+    for definition in &definitions {
+        let path = definition.module.path();
+        if path
+            .file_stem()
+            .is_some_and(|stem| stem == "builtins" || stem == "__builtins__")
+        {
+            return Err(RenameError::Builtin(name.to_string()));
+        }
+        if definition.kind == DefinitionKind::Module {
+            return Err(RenameError::Module(name.to_string()));
+        }
+        if !path.starts_with(&root) {
+            return Err(RenameError::External(name.to_string()));
+        }
+    }
+
+    Ok(Some(identifier))
+}
+
+/// Returns the edits renaming the symbol at `offset` to `new_name`, searching `module` and the
+/// modules at `paths` for its occurrences.
+///
+/// Aliases are kept as is: renaming `bar` in `from foo import bar as baz` only renames `bar`,
+/// and the uses of `baz` remain unchanged, even where `baz` is imported back as `bar`. Renaming
+/// `baz` itself renames the alias and its uses in the module instead. The entries of `__all__`
+/// naming a renamed global are renamed as well.
+pub(crate) fn rename(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    offset: TextSize,
+    new_name: &str,
+    paths: &[PathBuf],
+) -> Result<Vec<(Arc<ParsedModule>, TextRange)>, RenameError> {
+    if !python_parser::lexer::is_identifier(new_name) {
+        return Err(RenameError::InvalidName(new_name.to_string()));
+    }
+
+    let Some(identifier) = prepare_rename(resolver, module, offset)? else {
+        return Ok(Vec::new());
+    };
+    let old_name = module.source()[identifier].to_string();
+    let alias = import_alias(module, identifier);
+
+    let mut edits: Vec<(Arc<ParsedModule>, TextRange)> =
+        find_references(resolver, module, identifier.start(), paths)
+            .into_iter()
+            .filter(|reference| {
+                if reference.module.source()[reference.range] != old_name {
+                    return false;
+                }
+                match alias {
+                    Some(alias) => {
+                        reference.module.path() == module.path()
+                            && import_alias(&reference.module, reference.range) == Some(alias)
+                    }
+                    None => {
+                        !resolver.resolves_through_alias(&reference.module, reference.range.start())
+                    }
+                }
+            })
+            .map(|reference| (reference.module, reference.range))
+            .collect();
+
+    // Rename the `__all__` entries of the modules where a renamed global is defined or imported.
+    let mut exporting_modules: FxHashSet<&Path> = FxHashSet::default();
+    let mut all_entries = Vec::new();
+    for (module, range) in &edits {
+        let is_global = module
+            .model()
+            .global_scope()
+            .get_all(&old_name)
+            .iter()
+            .any(|&id| module.model().binding(id).range == *range);
+
+        if is_global && exporting_modules.insert(module.path()) {
+            all_entries.extend(
+                module
+                    .dunder_all()
+                    .into_iter()
+                    .filter(|range| module.source()[*range] == old_name)
+                    .map(|range| (module.clone(), range)),
+            );
+        }
+    }
+    edits.extend(all_entries);
+
+    Ok(edits)
+}
+
+/// Returns the binding of the import alias the identifier at `range` refers to, e.g. `baz` in
+/// `from foo import bar as baz`, `None` if it refers to another kind of binding.
+fn import_alias(module: &ParsedModule, range: TextRange) -> Option<BindingId> {
+    let model = module.model();
+    let name = &module.source()[range];
+    let id = model.lookup(name, model.scope_at(range.start()), range.start())?;
+    let binding = model.binding(id);
+    match &binding.kind {
+        BindingKind::FromImport(import) if binding.name != import.member => Some(id),
+        _ => None,
+    }
+}
+
+/// Returns the word of ASCII letters, digits and underscores around `offset`.
+fn word_at(source: &str, offset: TextSize) -> &str {
+    let offset = offset.to_usize().min(source.len());
+    let is_word = |c: char| c.is_ascii_alphanumeric() || c == '_';
+
+    let start = source[..offset]
+        .rfind(|c: char| !is_word(c))
+        .map_or(0, |index| index + 1);
+    let end = source[offset..]
+        .find(|c: char| !is_word(c))
+        .map_or(source.len(), |index| offset + index);
+
+    &source[start..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::TextSize;
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+
+    use super::{prepare_rename, rename, RenameError};
+
+    fn module(root: &Path, source: &str) -> Arc<ParsedModule> {
+        Arc::new(ParsedModule::new(
+            root.join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        ))
+    }
+
+    fn offset(source: &str, cursor: &str) -> TextSize {
+        TextSize::try_from(source.find(cursor).unwrap()).unwrap()
+    }
+
+    /// Applies the rename of the symbol at `cursor` to `main.py` and returns its new source.
+    fn rename_in_main(source: &str, cursor: &str, new_name: &str) -> Result<String, RenameError> {
+        let root = Path::new("/project");
+        let module = module(root, source);
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let mut edits = rename(
+            &mut resolver,
+            &module,
+            offset(source, cursor),
+            new_name,
+            &[],
+        )?;
+        edits.sort_by_key(|(_, range)| std::cmp::Reverse(range.start()));
+
+        let mut renamed = source.to_string();
+        for (_, range) in edits {
+            renamed.replace_range(std::ops::Range::<usize>::from(range), new_name);
+        }
+        Ok(renamed)
+    }
+
+    #[test]
+    fn rejects_builtins_keywords_and_strings() {
+        let root = Path::new("/project");
+        let source = "print('value')\nif True:\n    pass\n";
+        let module = module(root, source);
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+
+        assert_eq!(
+            prepare_rename(&mut resolver, &module, offset(source, "print")),
+            Err(RenameError::Builtin("print".to_string()))
+        );
+        assert_eq!(
+            prepare_rename(&mut resolver, &module, offset(source, "value")),
+            Err(RenameError::StringLiteral)
+        );
+        assert_eq!(
+            prepare_rename(&mut resolver, &module, offset(source, "if")),
+            Err(RenameError::Keyword("if".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        let source = "x = 1\n";
+
+        assert_eq!(
+            rename_in_main(source, "x", "class"),
+            Err(RenameError::InvalidName("class".to_string()))
+        );
+        assert_eq!(
+            rename_in_main(source, "x", "1x"),
+            Err(RenameError::InvalidName("1x".to_string()))
+        );
+    }
+
+    #[test]
+    fn renames_keyword_arguments_and_dunder_all() {
+        let source = r#"__all__ = ["compute", "other"]
+
+def compute(value, scale=1):
+    return value * scale
+
+def other():
+    return compute(1, scale=2)
+"#;
+
+        assert_eq!(
+            rename_in_main(source, "compute(value", "calculate").unwrap(),
+            r#"__all__ = ["calculate", "other"]
+
+def calculate(value, scale=1):
+    return value * scale
+
+def other():
+    return calculate(1, scale=2)
+"#
+        );
+        assert_eq!(
+            rename_in_main(source, "scale=1", "factor").unwrap(),
+            r#"__all__ = ["compute", "other"]
+
+def compute(value, factor=1):
+    return value * factor
+
+def other():
+    return compute(1, factor=2)
+"#
+        );
+    }
+
+    #[test]
+    fn keeps_aliases() {
+        let root = tempfile::tempdir().unwrap();
+        let helpers = root.path().join("helpers.py");
+        std::fs::write(&helpers, "def helper():\n    pass\n").unwrap();
+
+        let source = "from helpers import helper as h\n\nh()\n";
+        let module = module(root.path(), source);
+        let import_resolver = ImportResolver::new(root.path().to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let edits = rename(
+            &mut resolver,
+            &module,
+            offset(source, "helper as"),
+            "assist",
+            std::slice::from_ref(&helpers),
+        )
+        .unwrap();
+        let mut edits: Vec<_> = edits
+            .iter()
+            .map(|(module, range)| {
+                (
+                    module.path().to_path_buf(),
+                    module.source()[*range].to_string(),
+                )
+            })
+            .collect();
+        edits.sort();
+
+        assert_eq!(
+            edits,
+            vec![
+                (helpers, "helper".to_string()),
+                (root.path().join("main.py"), "helper".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn renames_aliases() {
+        let root = tempfile::tempdir().unwrap();
+        let helpers = root.path().join("helpers.py");
+        std::fs::write(&helpers, "def helper():\n    pass\n").unwrap();
+
+        let source = "from helpers import helper as h\n\nh()\nprint(h)\n";
+        let module = module(root.path(), source);
+        let import_resolver = ImportResolver::new(root.path().to_path_buf());
+
+        for cursor in ["h\n", "h()"] {
+            let mut resolver = DefinitionResolver::new(&import_resolver);
+            resolver.insert(module.clone());
+            let mut edits = rename(
+                &mut resolver,
+                &module,
+                offset(source, cursor),
+                "assist",
+                std::slice::from_ref(&helpers),
+            )
+            .unwrap();
+            edits.sort_by_key(|(_, range)| std::cmp::Reverse(range.start()));
+
+            let mut renamed = source.to_string();
+            for (module, range) in edits {
+                assert_eq!(module.path(), root.path().join("main.py"));
+                renamed.replace_range(std::ops::Range::<usize>::from(range), "assist");
+            }
+            assert_eq!(
+                renamed,
+                "from helpers import helper as assist\n\nassist()\nprint(assist)\n"
+            );
+        }
+    }
+
+    #[test]
+    fn keeps_aliases_imported_back_under_the_old_name() {
+        let root = tempfile::tempdir().unwrap();
+        let a = root.path().join("a.py");
+        let b = root.path().join("b.py");
+        let c = root.path().join("c.py");
+        std::fs::write(&a, "def f():\n    pass\n").unwrap();
+        std::fs::write(&b, "from a import f as g\n").unwrap();
+        std::fs::write(&c, "from b import g as f\n\nf()\n").unwrap();
+
+        let source = "from a import f\n\nf()\n";
+        let module = module(root.path(), source);
+        let import_resolver = ImportResolver::new(root.path().to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let edits = rename(
+            &mut resolver,
+            &module,
+            offset(source, "f()"),
+            "h",
+            &[a.clone(), b.clone(), c],
+        )
+        .unwrap();
+        let mut edits: Vec<_> = edits
+            .iter()
+            .map(|(module, range)| (module.path().to_path_buf(), range.start().to_usize()))
+            .collect();
+        edits.sort();
+
+        let main = root.path().join("main.py");
+        assert_eq!(edits, vec![(a, 4), (b, 14), (main.clone(), 14), (main, 17)]);
+    }
+}
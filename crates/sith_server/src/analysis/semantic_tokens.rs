@@ -0,0 +1,622 @@
+//! Semantic highlighting of the names of a module, classified with the semantic model.
+
+use std::sync::Arc;
+
+use lsp_types::{SemanticTokenModifier, SemanticTokenType};
+use python_ast::AnyNodeRef;
+use python_parser::lexer::lex;
+use python_parser::{Mode, TokenKind};
+use ruff_text_size::TextRange;
+use rustc_hash::FxHashMap;
+use semantic_model::builtins::{BUILTIN_MODULES, BUILTIN_SYMBOLS};
+use semantic_model::{Binding, BindingId, BindingKind, ScopeKind};
+
+use super::definition::defining_node;
+use super::symbols::is_constant;
+use super::{Definition, DefinitionKind, DefinitionResolver, ParsedModule};
+
+/// The token types of the legend, in the order of [`TokenType`].
+pub(crate) const TOKEN_TYPES: [SemanticTokenType; 10] = [
+    SemanticTokenType::NAMESPACE,
+    SemanticTokenType::CLASS,
+    SemanticTokenType::FUNCTION,
+    SemanticTokenType::METHOD,
+    SemanticTokenType::PARAMETER,
+    SemanticTokenType::new("selfParameter"),
+    SemanticTokenType::VARIABLE,
+    SemanticTokenType::PROPERTY,
+    SemanticTokenType::TYPE_PARAMETER,
+    SemanticTokenType::TYPE,
+];
+
+/// The token modifiers of the legend, the bit of each one is its index.
+pub(crate) const TOKEN_MODIFIERS: [SemanticTokenModifier; 4] = [
+    SemanticTokenModifier::DECLARATION,
+    SemanticTokenModifier::READONLY,
+    SemanticTokenModifier::DEFAULT_LIBRARY,
+    SemanticTokenModifier::ASYNC,
+];
+
+pub(crate) const DECLARATION: u32 = 1 << 0;
+pub(crate) const READONLY: u32 = 1 << 1;
+pub(crate) const DEFAULT_LIBRARY: u32 = 1 << 2;
+pub(crate) const ASYNC: u32 = 1 << 3;
+
+/// The type of a semantic token, its discriminant is the index in [`TOKEN_TYPES`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenType {
+    Namespace,
+    Class,
+    Function,
+    Method,
+    Parameter,
+    /// The `self` or `cls` parameter of a method.
+    SelfParameter,
+    Variable,
+    Property,
+    TypeParameter,
+    /// A type alias.
+    Type,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct SemanticToken {
+    pub(crate) range: TextRange,
+    pub(crate) token_type: TokenType,
+    /// The bitset of the token modifiers, e.g. [`DECLARATION`].
+    pub(crate) modifiers: u32,
+}
+
+/// Builtins that are classes rather than functions, excluding the exceptions and warnings
+/// which are recognized by their capitalized names.
+const BUILTIN_CLASSES: &[&str] = &[
+    "bool",
+    "bytearray",
+    "bytes",
+    "classmethod",
+    "complex",
+    "dict",
+    "enumerate",
+    "filter",
+    "float",
+    "frozenset",
+    "int",
+    "list",
+    "map",
+    "memoryview",
+    "object",
+    "property",
+    "range",
+    "reversed",
+    "set",
+    "slice",
+    "staticmethod",
+    "str",
+    "super",
+    "tuple",
+    "type",
+    "zip",
+];
+
+/// Returns the semantic tokens of the names in `module` overlapping `range`, in source order.
+///
+/// Keywords, literals, operators and comments are left to the client's syntax highlighting,
+/// as are the names that can't be resolved.
+pub(crate) fn semantic_tokens(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    range: TextRange,
+) -> Vec<SemanticToken> {
+    let tokens = significant_tokens(module.source());
+    let mut classifier = Classifier::new(resolver, module);
+    let mut semantic_tokens: Vec<SemanticToken> = Vec::new();
+
+    let mut statement = Statement::Other;
+    // Whether the dotted module name being imported is part of the standard library.
+    let mut is_stdlib = false;
+    let mut depth = 0u32;
+
+    for (index, &(kind, token_range)) in tokens.iter().enumerate() {
+        let previous = index.checked_sub(1).map(|index| tokens[index].0);
+        let next = tokens.get(index + 1).map(|&(kind, _)| kind);
+
+        match kind {
+            TokenKind::Newline | TokenKind::Semi => statement = Statement::Other,
+            TokenKind::Import if statement == Statement::From => statement = Statement::FromImport,
+            TokenKind::Import if is_statement_start(previous) => statement = Statement::Import,
+            TokenKind::From if is_statement_start(previous) => {
+                statement = Statement::From;
+                is_stdlib = false;
+            }
+            TokenKind::Lpar | TokenKind::Lsqb | TokenKind::Lbrace => depth += 1,
+            TokenKind::Rpar | TokenKind::Rsqb | TokenKind::Rbrace => {
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+
+        if kind != TokenKind::Name
+            && !(kind.is_soft_keyword() && classifier.is_soft_keyword_name(token_range))
+        {
+            continue;
+        }
+        if token_range.end() <= range.start() || token_range.start() >= range.end() {
+            continue;
+        }
+
+        let classified = match statement {
+            Statement::Import | Statement::From => {
+                if previous != Some(TokenKind::Dot) {
+                    is_stdlib = statement == Statement::Import || previous == Some(TokenKind::From);
+                    is_stdlib &= is_stdlib_module(&module.source()[token_range]);
+                }
+                classifier.declaration(token_range).or_else(|| {
+                    let modifiers = if is_stdlib { DEFAULT_LIBRARY } else { 0 };
+                    Some((TokenType::Namespace, modifiers))
+                })
+            }
+            Statement::FromImport => classifier
+                .declaration(token_range)
+                .or_else(|| classifier.imported_member(token_range)),
+            Statement::Other if previous == Some(TokenKind::Dot) => {
+                let receiver = semantic_tokens
+                    .last()
+                    .filter(|token| index >= 2 && token.range == tokens[index - 2].1);
+                let is_stdlib = receiver.is_some_and(|token| {
+                    token.token_type == TokenType::Namespace
+                        && token.modifiers & DEFAULT_LIBRARY != 0
+                });
+                classifier
+                    .attribute(token_range, next)
+                    .map(|(token_type, modifiers)| {
+                        let stdlib = if is_stdlib { DEFAULT_LIBRARY } else { 0 };
+                        (token_type, modifiers | stdlib)
+                    })
+            }
+            Statement::Other => classifier.declaration(token_range).or_else(|| {
+                let is_keyword_argument = depth > 0
+                    && next == Some(TokenKind::Equal)
+                    && matches!(previous, Some(TokenKind::Lpar | TokenKind::Comma));
+                if is_keyword_argument {
+                    Some((TokenType::Parameter, 0))
+                } else {
+                    classifier.reference(token_range)
+                }
+            }),
+        };
+
+        if let Some((token_type, modifiers)) = classified {
+            semantic_tokens.push(SemanticToken {
+                range: token_range,
+                token_type,
+                modifiers,
+            });
+        }
+    }
+
+    semantic_tokens
+}
+
+/// The kind of statement the current token is part of, import statements name modules rather
+/// than variables.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Statement {
+    /// The modules of `import foo.bar as baz`.
+    Import,
+    /// The module of `from foo.bar import baz`.
+    From,
+    /// The members of `from foo.bar import baz`.
+    FromImport,
+    Other,
+}
+
+/// Returns the tokens of `source` without the comments and the non-logical newlines.
+fn significant_tokens(source: &str) -> Vec<(TokenKind, TextRange)> {
+    let mut lexer = lex(source, Mode::Module);
+    let mut tokens = Vec::new();
+    loop {
+        let kind = lexer.next_token();
+        if kind.is_eof() {
+            break;
+        }
+        if !kind.is_trivia() {
+            tokens.push((kind, lexer.current_range()));
+        }
+    }
+    tokens
+}
+
+/// Returns `true` if a token following `previous` starts a simple statement.
+fn is_statement_start(previous: Option<TokenKind>) -> bool {
+    matches!(
+        previous,
+        None | Some(
+            TokenKind::Newline
+                | TokenKind::Indent
+                | TokenKind::Dedent
+                | TokenKind::Semi
+                | TokenKind::Colon
+        )
+    )
+}
+
+fn is_stdlib_module(name: &str) -> bool {
+    BUILTIN_MODULES.contains(&name)
+}
+
+/// Classifies the names of a module, caching the classification of each binding since
+/// following imports may read other modules.
+struct Classifier<'a, 'r> {
+    resolver: &'a mut DefinitionResolver<'r>,
+    module: &'a Arc<ParsedModule>,
+    /// The bindings by the range of the name they bind.
+    declarations: FxHashMap<TextRange, BindingId>,
+    bindings: FxHashMap<BindingId, Option<(TokenType, u32)>>,
+}
+
+impl<'a, 'r> Classifier<'a, 'r> {
+    fn new(resolver: &'a mut DefinitionResolver<'r>, module: &'a Arc<ParsedModule>) -> Self {
+        let mut declarations = FxHashMap::default();
+        for (id, binding) in module.model().bindings() {
+            declarations.entry(binding.range).or_insert(id);
+        }
+
+        Self {
+            resolver,
+            module,
+            declarations,
+            bindings: FxHashMap::default(),
+        }
+    }
+
+    /// Returns `true` if the soft keyword at `range`, e.g. `match` or `type`, is used as a name.
+    fn is_soft_keyword_name(&self, range: TextRange) -> bool {
+        if self.declarations.contains_key(&range) {
+            return true;
+        }
+        let nodes = python_ast_utils::nodes_at_offset(self.module.syntax(), range.start());
+        match nodes.last() {
+            Some(AnyNodeRef::NameExpr(name)) => name.range == range,
+            Some(AnyNodeRef::AttributeExpr(attribute)) => attribute.attr.range == range,
+            _ => false,
+        }
+    }
+
+    /// Classifies the name at `range` if it's the name bound by a binding.
+    fn declaration(&mut self, range: TextRange) -> Option<(TokenType, u32)> {
+        let id = *self.declarations.get(&range)?;
+        let (token_type, modifiers) = self.binding(id)?;
+        Some((token_type, modifiers | DECLARATION))
+    }
+
+    /// Classifies the name at `range` referring to a binding or a builtin.
+    fn reference(&mut self, range: TextRange) -> Option<(TokenType, u32)> {
+        let model = self.module.model();
+        let name = &self.module.source()[range];
+        match model.lookup(name, model.scope_at(range.start()), range.start()) {
+            Some(id) => self.binding(id),
+            None => builtin(name),
+        }
+    }
+
+    /// Classifies the imported name of an aliased member, e.g. `bar` in
+    /// `from foo import bar as baz`, like the alias it's bound to.
+    fn imported_member(&mut self, range: TextRange) -> Option<(TokenType, u32)> {
+        let (id, _) = self.module.model().bindings().find(|(_, binding)| {
+            binding.kind.is_from_import() && binding.definition_range.contains_range(range)
+        })?;
+        self.binding(id)
+    }
+
+    /// Classifies the attribute name at `range`, falling back to its syntax if the attribute
+    /// can't be resolved.
+    fn attribute(&mut self, range: TextRange, next: Option<TokenKind>) -> Option<(TokenType, u32)> {
+        let definition = self
+            .resolver
+            .definitions_at(self.module, range.start())
+            .into_iter()
+            .next();
+
+        definition
+            .and_then(|definition| self.definition(&definition))
+            .or_else(|| {
+                if next == Some(TokenKind::Lpar) {
+                    Some((TokenType::Method, 0))
+                } else {
+                    Some((TokenType::Property, 0))
+                }
+            })
+    }
+
+    fn binding(&mut self, id: BindingId) -> Option<(TokenType, u32)> {
+        if let Some(&classified) = self.bindings.get(&id) {
+            return classified;
+        }
+
+        let binding = self.module.model().binding(id);
+        let classified = match &binding.kind {
+            BindingKind::Import(_) | BindingKind::FromImport(_) | BindingKind::Parameter => {
+                let stdlib = stdlib_modifier(binding);
+                self.resolver
+                    .definitions_for_binding(self.module, id, 0)
+                    .first()
+                    .and_then(|definition| self.definition(definition))
+                    .map(|(token_type, modifiers)| (token_type, modifiers | stdlib))
+            }
+            _ => classify(self.module, id),
+        };
+
+        self.bindings.insert(id, classified);
+        classified
+    }
+
+    /// Classifies the symbol at `definition`, imports are classified like what they import.
+    fn definition(&self, definition: &Definition) -> Option<(TokenType, u32)> {
+        let DefinitionKind::Binding(id) = definition.kind else {
+            return Some((TokenType::Namespace, 0));
+        };
+
+        let binding = definition.module.model().binding(id);
+        let (token_type, modifiers) = match &binding.kind {
+            BindingKind::Import(_) => (TokenType::Namespace, stdlib_modifier(binding)),
+            BindingKind::FromImport(_) => (
+                variable_type(binding, &definition.module),
+                stdlib_modifier(binding),
+            ),
+            BindingKind::Parameter => {
+                if super::definition::class_scope(definition).is_some() {
+                    (TokenType::SelfParameter, 0)
+                } else {
+                    (TokenType::Parameter, 0)
+                }
+            }
+            _ => classify(&definition.module, id)?,
+        };
+
+        let readonly = if token_type == TokenType::Variable && is_constant(&binding.name) {
+            READONLY
+        } else {
+            0
+        };
+        Some((token_type, modifiers | readonly))
+    }
+}
+
+/// Classifies the symbol bound by the binding `id` of `module`, without following imports.
+fn classify(module: &ParsedModule, id: BindingId) -> Option<(TokenType, u32)> {
+    let binding = module.model().binding(id);
+    let classified = match &binding.kind {
+        BindingKind::FunctionDefinition(_) => {
+            let token_type = if module.model().scope(binding.scope).kind == ScopeKind::Class {
+                TokenType::Method
+            } else {
+                TokenType::Function
+            };
+            let is_async = matches!(
+                defining_node(module, binding),
+                Some(AnyNodeRef::StmtFunctionDef(function)) if function.is_async
+            );
+            (token_type, if is_async { ASYNC } else { 0 })
+        }
+        BindingKind::ClassDefinition(_) => (TokenType::Class, 0),
+        BindingKind::Parameter => (TokenType::Parameter, 0),
+        BindingKind::TypeParam => (TokenType::TypeParameter, 0),
+        BindingKind::TypeAlias => (TokenType::Type, 0),
+        BindingKind::Import(_) => (TokenType::Namespace, 0),
+        _ => {
+            let token_type = variable_type(binding, module);
+            let readonly = if token_type == TokenType::Variable && is_constant(&binding.name) {
+                READONLY
+            } else {
+                0
+            };
+            (token_type, readonly)
+        }
+    };
+    Some(classified)
+}
+
+/// Returns the token type of a variable, a property if it's a class variable.
+fn variable_type(binding: &Binding, module: &ParsedModule) -> TokenType {
+    if module.model().scope(binding.scope).kind == ScopeKind::Class {
+        TokenType::Property
+    } else {
+        TokenType::Variable
+    }
+}
+
+/// Returns [`DEFAULT_LIBRARY`] if `binding` imports from the standard library.
+fn stdlib_modifier(binding: &Binding) -> u32 {
+    let module = match &binding.kind {
+        BindingKind::Import(import) => Some(import.qualified_name.as_str()),
+        BindingKind::FromImport(import) if import.level == 0 => import.module.as_deref(),
+        _ => None,
+    };
+    let is_stdlib = module
+        .and_then(|module| module.split('.').next())
+        .is_some_and(is_stdlib_module);
+    if is_stdlib {
+        DEFAULT_LIBRARY
+    } else {
+        0
+    }
+}
+
+/// Classifies the builtin `name`, returns `None` if it's not a builtin.
+fn builtin(name: &str) -> Option<(TokenType, u32)> {
+    if !BUILTIN_SYMBOLS.contains(&name) {
+        return None;
+    }
+
+    let token_type = if name.starts_with("__") {
+        TokenType::Variable
+    } else if matches!(name, "Ellipsis" | "NotImplemented") {
+        return Some((TokenType::Variable, READONLY | DEFAULT_LIBRARY));
+    } else if BUILTIN_CLASSES.contains(&name) || name.starts_with(char::is_uppercase) {
+        TokenType::Class
+    } else {
+        TokenType::Function
+    };
+    Some((token_type, DEFAULT_LIBRARY))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::{TextRange, TextSize};
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+
+    use super::{semantic_tokens, TokenType, ASYNC, DECLARATION, DEFAULT_LIBRARY, READONLY};
+
+    /// Renders the semantic tokens of `source` with one token per line, e.g.
+    /// `function helper [declaration]`.
+    fn render(root: &Path, source: &str) -> String {
+        let module = Arc::new(ParsedModule::new(
+            root.join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        ));
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let range = TextRange::up_to(TextSize::of(source));
+        let mut output = String::new();
+        for token in semantic_tokens(&mut resolver, &module, range) {
+            let token_type = match token.token_type {
+                TokenType::Namespace => "namespace",
+                TokenType::Class => "class",
+                TokenType::Function => "function",
+                TokenType::Method => "method",
+                TokenType::Parameter => "parameter",
+                TokenType::SelfParameter => "selfParameter",
+                TokenType::Variable => "variable",
+                TokenType::Property => "property",
+                TokenType::TypeParameter => "typeParameter",
+                TokenType::Type => "type",
+            };
+            let modifiers: Vec<_> = [
+                (DECLARATION, "declaration"),
+                (READONLY, "readonly"),
+                (DEFAULT_LIBRARY, "defaultLibrary"),
+                (ASYNC, "async"),
+            ]
+            .into_iter()
+            .filter(|(bit, _)| token.modifiers & bit != 0)
+            .map(|(_, name)| name)
+            .collect();
+
+            output.push_str(&format!("{token_type} {}", &source[token.range]));
+            if !modifiers.is_empty() {
+                output.push_str(&format!(" [{}]", modifiers.join(", ")));
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    #[test]
+    fn definitions_and_references() {
+        let root = tempfile::tempdir().unwrap();
+        let source = r#"MAX_SIZE = 10
+
+class Shape[T]:
+    sides = 0
+
+    def __init__(self, name):
+        self.name = name
+
+    async def area(self):
+        return len(self.name) + MAX_SIZE
+
+type Pair = tuple[int, int]
+shape = Shape(name="square")
+print(shape.area(), Shape.sides)
+"#;
+
+        assert_eq!(
+            render(root.path(), source),
+            "\
+variable MAX_SIZE [declaration, readonly]
+class Shape [declaration]
+typeParameter T [declaration]
+property sides [declaration]
+method __init__ [declaration]
+selfParameter self [declaration]
+parameter name [declaration]
+selfParameter self
+property name
+parameter name
+method area [declaration, async]
+selfParameter self [declaration]
+function len [defaultLibrary]
+selfParameter self
+property name
+variable MAX_SIZE [readonly]
+type Pair [declaration]
+class tuple [defaultLibrary]
+class int [defaultLibrary]
+class int [defaultLibrary]
+variable shape [declaration]
+class Shape
+parameter name
+function print [defaultLibrary]
+variable shape
+method area
+class Shape
+property sides
+"
+        );
+    }
+
+    #[test]
+    fn imports() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(
+            root.path().join("helpers.py"),
+            "def helper(): ...\nclass Helper: ...\n",
+        )
+        .unwrap();
+        let source = r#"import os.path
+import helpers as h
+from helpers import helper, Helper as Alias
+from collections import OrderedDict
+
+os.path.join(h.helper(), Alias, OrderedDict)
+match = 1
+match match:
+    case _:
+        pass
+"#;
+
+        assert_eq!(
+            render(root.path(), source),
+            "\
+namespace os [declaration, defaultLibrary]
+namespace path [defaultLibrary]
+namespace helpers
+namespace h [declaration]
+namespace helpers
+function helper [declaration]
+class Helper
+class Alias [declaration]
+namespace collections [defaultLibrary]
+variable OrderedDict [declaration, defaultLibrary]
+namespace os [defaultLibrary]
+property path [defaultLibrary]
+method join
+namespace h
+function helper
+class Alias
+variable OrderedDict [defaultLibrary]
+variable match [declaration]
+variable match
+"
+        );
+    }
+}
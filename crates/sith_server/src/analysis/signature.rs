@@ -0,0 +1,291 @@
+//! Rendering of the signatures of functions and classes from their definitions.
+
+use python_ast::{self as ast};
+use ruff_text_size::Ranged;
+
+/// Returns the signature of `function`, e.g. `def foo[T](a: T, /, *, b: int = 1) -> T`.
+pub(crate) fn function_signature(function: &ast::FunctionDefStmt, source: &str) -> String {
+    let mut signature = String::new();
+    if function.is_async {
+        signature.push_str("async ");
+    }
+    signature.push_str("def ");
+    signature.push_str(&function.name);
+    if let Some(type_params) = &function.type_params {
+        signature.push_str(&format_type_params(type_params, source));
+    }
+    let parameters = signature_parameters(&function.parameters, source);
+    let labels: Vec<_> = parameter_labels(&parameters)
+        .into_iter()
+        .map(|(_, label)| label)
+        .collect();
+    signature.push('(');
+    signature.push_str(&labels.join(", "));
+    signature.push(')');
+    if let Some(returns) = &function.returns {
+        signature.push_str(" -> ");
+        signature.push_str(&source_text(returns.as_ref(), source));
+    }
+    signature
+}
+
+/// Returns the signature of `class`, e.g. `class Foo[T](Base, metaclass=Meta)`.
+pub(crate) fn class_signature(class: &ast::ClassDefStmt, source: &str) -> String {
+    let mut signature = format!("class {}", class.name.as_str());
+    if let Some(type_params) = &class.type_params {
+        signature.push_str(&format_type_params(type_params, source));
+    }
+    if let Some(arguments) = class.arguments.as_deref() {
+        let arguments: Vec<_> = arguments
+            .args
+            .iter()
+            .map(|arg| source_text(arg, source))
+            .chain(
+                arguments
+                    .keywords
+                    .iter()
+                    .map(|keyword| source_text(keyword, source)),
+            )
+            .collect();
+        if !arguments.is_empty() {
+            signature.push('(');
+            signature.push_str(&arguments.join(", "));
+            signature.push(')');
+        }
+    }
+    signature
+}
+
+/// How an argument is bound to a parameter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ParameterKind {
+    /// A parameter before the `/` separator.
+    PositionalOnly,
+    PositionalOrKeyword,
+    /// The `*args` parameter.
+    VarPositional,
+    /// A parameter after the `*` separator or `*args`.
+    KeywordOnly,
+    /// The `**kwargs` parameter.
+    VarKeyword,
+}
+
+/// A parameter of a function, as written in its signature.
+pub(crate) struct SignatureParameter<'a> {
+    pub(crate) name: &'a str,
+    /// The parameter as written in the signature, e.g. `*args: int` or `x: int = 1`.
+    pub(crate) label: String,
+    pub(crate) kind: ParameterKind,
+}
+
+/// Returns the parameters of a function, in order.
+pub(crate) fn signature_parameters<'a>(
+    parameters: &'a ast::Parameters,
+    source: &str,
+) -> Vec<SignatureParameter<'a>> {
+    let with_default = |parameter: &'a ast::ParameterWithDefault, kind| SignatureParameter {
+        name: parameter.parameter.name.as_str(),
+        label: parameter_with_default_label(parameter, source),
+        kind,
+    };
+    let variadic = |parameter: &'a ast::Parameter, prefix: &str, kind| SignatureParameter {
+        name: parameter.name.as_str(),
+        label: format!("{prefix}{}", parameter_label(parameter, source)),
+        kind,
+    };
+
+    let mut signature_parameters = Vec::new();
+    signature_parameters.extend(
+        parameters
+            .posonlyargs
+            .iter()
+            .map(|parameter| with_default(parameter, ParameterKind::PositionalOnly)),
+    );
+    signature_parameters.extend(
+        parameters
+            .args
+            .iter()
+            .map(|parameter| with_default(parameter, ParameterKind::PositionalOrKeyword)),
+    );
+    signature_parameters.extend(
+        parameters
+            .vararg
+            .as_deref()
+            .map(|parameter| variadic(parameter, "*", ParameterKind::VarPositional)),
+    );
+    signature_parameters.extend(
+        parameters
+            .kwonlyargs
+            .iter()
+            .map(|parameter| with_default(parameter, ParameterKind::KeywordOnly)),
+    );
+    signature_parameters.extend(
+        parameters
+            .kwarg
+            .as_deref()
+            .map(|parameter| variadic(parameter, "**", ParameterKind::VarKeyword)),
+    );
+    signature_parameters
+}
+
+/// Returns the label of each parameter, in order, with the `/` and `*` separators of the
+/// positional-only and keyword-only parameters as labels of their own. Labels that aren't
+/// separators are paired with the index of their parameter in `parameters`.
+pub(crate) fn parameter_labels<'a>(
+    parameters: &'a [SignatureParameter],
+) -> Vec<(Option<usize>, &'a str)> {
+    let mut labels = Vec::new();
+    let mut previous = None;
+
+    for (index, parameter) in parameters.iter().enumerate() {
+        if previous == Some(ParameterKind::PositionalOnly)
+            && parameter.kind != ParameterKind::PositionalOnly
+        {
+            labels.push((None, "/"));
+        }
+        if parameter.kind == ParameterKind::KeywordOnly
+            && !matches!(
+                previous,
+                Some(ParameterKind::VarPositional | ParameterKind::KeywordOnly)
+            )
+        {
+            labels.push((None, "*"));
+        }
+        labels.push((Some(index), parameter.label.as_str()));
+        previous = Some(parameter.kind);
+    }
+    if previous == Some(ParameterKind::PositionalOnly) {
+        labels.push((None, "/"));
+    }
+
+    labels
+}
+
+/// Returns the docstring of a module, class or function with the given `body`.
+pub(crate) fn docstring(body: &[ast::Stmt]) -> Option<String> {
+    let ast::Stmt::Expr(ast::ExprStmt { value, .. }) = body.first()? else {
+        return None;
+    };
+    let ast::Expr::StringLiteral(ast::StringLiteralExpr { value, .. }) = value.as_ref() else {
+        return None;
+    };
+    Some(clean_docstring(value.to_str()))
+}
+
+/// Removes the indentation shared by the lines of a docstring after the first one, like
+/// `inspect.cleandoc`.
+fn clean_docstring(docstring: &str) -> String {
+    let mut lines = docstring.lines();
+    let first = lines.next().unwrap_or_default().trim();
+    let rest: Vec<_> = lines.collect();
+    let indentation = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    let mut cleaned = vec![first];
+    cleaned.extend(rest.iter().map(|line| {
+        line.get(indentation..)
+            .unwrap_or_else(|| line.trim_start())
+            .trim_end()
+    }));
+
+    cleaned.join("\n").trim().to_string()
+}
+
+fn parameter_with_default_label(parameter: &ast::ParameterWithDefault, source: &str) -> String {
+    let mut label = parameter_label(&parameter.parameter, source);
+    if let Some(default) = &parameter.default {
+        // PEP 8 puts spaces around the `=` of annotated parameters only.
+        if parameter.parameter.annotation.is_some() {
+            label.push_str(" = ");
+        } else {
+            label.push('=');
+        }
+        label.push_str(&source_text(default.as_ref(), source));
+    }
+    label
+}
+
+fn parameter_label(parameter: &ast::Parameter, source: &str) -> String {
+    match &parameter.annotation {
+        Some(annotation) => format!(
+            "{}: {}",
+            parameter.name.as_str(),
+            source_text(annotation.as_ref(), source)
+        ),
+        None => parameter.name.to_string(),
+    }
+}
+
+fn format_type_params(type_params: &ast::TypeParams, source: &str) -> String {
+    let type_params: Vec<_> = type_params
+        .iter()
+        .map(|type_param| source_text(type_param, source))
+        .collect();
+    format!("[{}]", type_params.join(", "))
+}
+
+/// Returns the source text of `node` on a single line.
+pub(crate) fn source_text(node: impl Ranged, source: &str) -> String {
+    let mut text = String::new();
+    for word in source[node.range()].split_whitespace() {
+        let is_closing = word.starts_with([')', ']', '}']);
+        if !(text.is_empty() || text.ends_with(['(', '[', '{']) || is_closing) {
+            text.push(' ');
+        }
+        text.push_str(word);
+    }
+    text.replace(",)", ")").replace(",]", "]")
+}
+
+#[cfg(test)]
+mod tests {
+    use python_ast::Stmt;
+    use python_parser::parse_module;
+
+    use super::{class_signature, docstring, function_signature};
+
+    fn signature(source: &str) -> String {
+        let parsed = parse_module(source);
+        match &parsed.syntax().body[0] {
+            Stmt::FunctionDef(function) => function_signature(function, source),
+            Stmt::ClassDef(class) => class_signature(class, source),
+            _ => panic!("expected a definition"),
+        }
+    }
+
+    #[test]
+    fn function_signatures() {
+        assert_eq!(signature("def foo(): pass"), "def foo()");
+        assert_eq!(
+            signature("async def foo[T](a: T, b=1, /, c: int = 2, *args, d, **kwargs) -> T: pass"),
+            "async def foo[T](a: T, b=1, /, c: int = 2, *args, d, **kwargs) -> T"
+        );
+        assert_eq!(
+            signature("def foo(\n    a: dict[\n        str,\n        int,\n    ],\n    *,\n    b,\n): pass"),
+            "def foo(a: dict[str, int], *, b)"
+        );
+    }
+
+    #[test]
+    fn class_signatures() {
+        assert_eq!(signature("class Foo: pass"), "class Foo");
+        assert_eq!(
+            signature("class Foo[T](Base[T], metaclass=Meta): pass"),
+            "class Foo[T](Base[T], metaclass=Meta)"
+        );
+    }
+
+    #[test]
+    fn docstrings() {
+        let parsed = parse_module("def foo():\n    \"\"\"Docs.\"\"\"\n    return 1\n");
+        let Stmt::FunctionDef(function) = &parsed.syntax().body[0] else {
+            panic!("expected a function");
+        };
+        assert_eq!(docstring(&function.body).as_deref(), Some("Docs."));
+        assert_eq!(docstring(&parsed.syntax().body), None);
+    }
+}
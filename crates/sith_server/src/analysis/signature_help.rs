@@ -0,0 +1,408 @@
+//! Signature of the function being called at the cursor and the parameter the argument under
+//! the cursor is bound to.
+
+use std::sync::Arc;
+
+use python_ast::visitor::preorder::{PreorderVisitor, TraversalSignal};
+use python_ast::{self as ast, AnyNodeRef, ArgOrKeyword, Expr};
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use semantic_model::{BindingKind, ScopeKind};
+
+use super::definition::defining_node;
+use super::signature::{
+    docstring, parameter_labels, signature_parameters, source_text, ParameterKind,
+    SignatureParameter,
+};
+use super::{Definition, DefinitionResolver, ParsedModule};
+
+pub(crate) struct SignatureHelp {
+    /// The signature of the called function, e.g. `foo(a: int, /, *, b: str) -> None`.
+    pub(crate) label: String,
+    /// The range of each parameter in `label`.
+    pub(crate) parameters: Vec<TextRange>,
+    pub(crate) documentation: Option<String>,
+    /// The index in `parameters` of the parameter the argument at the cursor is bound to.
+    pub(crate) active_parameter: Option<usize>,
+}
+
+/// Returns the signature help of the call whose arguments contain `offset` in `module`.
+pub(crate) fn signature_help(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    offset: TextSize,
+) -> Option<SignatureHelp> {
+    let call = enclosing_call(module, offset)?;
+
+    let callee = resolver
+        .resolve_expression(module, &call.func, 0)
+        .into_iter()
+        .next()?;
+    let (name, function, skip_first) = called_function(resolver, module, &call.func, &callee)?;
+    let source = callee.module.source();
+
+    let mut parameters = signature_parameters(&function.parameters, source);
+    if skip_first
+        && parameters
+            .first()
+            .is_some_and(|parameter| parameter.kind != ParameterKind::VarPositional)
+    {
+        parameters.remove(0);
+    }
+
+    let mut label = format!("{name}(");
+    let mut ranges = vec![TextRange::default(); parameters.len()];
+    for (position, (index, parameter_label)) in
+        parameter_labels(&parameters).into_iter().enumerate()
+    {
+        if position > 0 {
+            label.push_str(", ");
+        }
+        let start = TextSize::of(label.as_str());
+        label.push_str(parameter_label);
+        if let Some(index) = index {
+            ranges[index] = TextRange::at(start, TextSize::of(parameter_label));
+        }
+    }
+    label.push(')');
+    if let Some(returns) = &function.returns {
+        label.push_str(" -> ");
+        label.push_str(&source_text(returns.as_ref(), source));
+    }
+
+    Some(SignatureHelp {
+        label,
+        parameters: ranges,
+        documentation: docstring(&function.body),
+        active_parameter: active_parameter(module.source(), call, offset, &parameters),
+    })
+}
+
+/// Returns the name and the definition of the function called through `func`, which resolves
+/// to `callee`, and whether its first parameter is bound rather than passed by the caller.
+///
+/// Calling a class calls its `__init__` method, and calling a method through an instance
+/// binds its first parameter.
+pub(super) fn called_function<'a>(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    func: &Expr,
+    callee: &'a Definition,
+) -> Option<(&'a str, &'a ast::FunctionDefStmt, bool)> {
+    let binding = callee.as_binding()?;
+    let callee_module = &callee.module;
+    let source = callee_module.source();
+    let model = callee_module.model();
+
+    match (binding.kind.clone(), defining_node(callee_module, binding)?) {
+        (BindingKind::FunctionDefinition(_), AnyNodeRef::StmtFunctionDef(function)) => {
+            let is_method = model.scope(binding.scope).kind == ScopeKind::Class;
+            let skip_first = is_method
+                && !has_decorator(function, "staticmethod", source)
+                && (has_decorator(function, "classmethod", source)
+                    || is_called_on_instance(resolver, module, func));
+            Some((function.name.as_str(), function, skip_first))
+        }
+        (BindingKind::ClassDefinition(scope), AnyNodeRef::StmtClassDef(class)) => {
+            let init = model.binding(model.scope(scope).get("__init__")?);
+            let Some(AnyNodeRef::StmtFunctionDef(function)) = defining_node(callee_module, init)
+            else {
+                return None;
+            };
+            Some((class.name.as_str(), function, true))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the innermost call whose parentheses contain `offset`.
+///
+/// The call may be unterminated while the arguments are being typed, e.g. `foo(a, `, in
+/// which case the parser recovers with a call spanning the arguments typed so far.
+fn enclosing_call(module: &ParsedModule, offset: TextSize) -> Option<&ast::CallExpr> {
+    let mut finder = EnclosingCall {
+        source: module.source(),
+        offset,
+        call: None,
+    };
+    finder.visit_body(&module.syntax().body);
+    finder.call
+}
+
+struct EnclosingCall<'a> {
+    source: &'a str,
+    offset: TextSize,
+    call: Option<&'a ast::CallExpr>,
+}
+
+impl<'a> PreorderVisitor<'a> for EnclosingCall<'a> {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        if let AnyNodeRef::CallExpr(call) = node {
+            let arguments = call.arguments.range();
+            let is_closed = self.source[arguments].ends_with(')');
+            let contains_offset = arguments.start() < self.offset
+                && (self.offset < arguments.end()
+                    || (!is_closed
+                        && self.source[TextRange::new(arguments.end(), self.offset)]
+                            .trim()
+                            .is_empty()));
+            if contains_offset {
+                // Calls are visited before the calls nested in their arguments.
+                self.call = Some(call);
+            }
+        }
+
+        TraversalSignal::Traverse
+    }
+}
+
+/// Returns the index of the parameter the argument at `offset` is bound to, if any.
+fn active_parameter(
+    source: &str,
+    call: &ast::CallExpr,
+    offset: TextSize,
+    parameters: &[SignatureParameter],
+) -> Option<usize> {
+    let arguments: Vec<_> = call.arguments.arguments_source_order().collect();
+
+    // The arguments preceding the cursor are the ones followed by a comma before it. The
+    // cursor is either in the next argument or where a new one is being typed.
+    let index = arguments
+        .iter()
+        .position(|argument| {
+            argument.end() > offset || !source[TextRange::new(argument.end(), offset)].contains(',')
+        })
+        .unwrap_or(arguments.len());
+    let preceding = &arguments[..index];
+    let active = arguments
+        .get(index)
+        .filter(|argument| argument.range().contains_inclusive(offset));
+
+    match active {
+        Some(ArgOrKeyword::Keyword(ast::Keyword { arg: Some(arg), .. })) => {
+            keyword_parameter(parameters, arg.as_str())
+        }
+        Some(ArgOrKeyword::Keyword(ast::Keyword { arg: None, .. })) => parameters
+            .iter()
+            .position(|parameter| parameter.kind == ParameterKind::VarKeyword),
+        Some(ArgOrKeyword::Arg(Expr::Starred(_))) => parameters
+            .iter()
+            .position(|parameter| parameter.kind == ParameterKind::VarPositional),
+        _ => {
+            let has_keywords = preceding
+                .iter()
+                .any(|argument| matches!(argument, ArgOrKeyword::Keyword(_)));
+            if has_keywords {
+                // Only keyword arguments can follow a keyword argument, suggest the first
+                // parameter that isn't supplied yet.
+                next_keyword_parameter(parameters, preceding)
+            } else {
+                positional_parameter(parameters, preceding.len())
+            }
+        }
+    }
+}
+
+/// Returns the parameter the `index`-th positional argument is bound to.
+fn positional_parameter(parameters: &[SignatureParameter], index: usize) -> Option<usize> {
+    let positional = parameters
+        .iter()
+        .enumerate()
+        .filter(|(_, parameter)| {
+            matches!(
+                parameter.kind,
+                ParameterKind::PositionalOnly | ParameterKind::PositionalOrKeyword
+            )
+        })
+        .map(|(index, _)| index)
+        .nth(index);
+
+    positional.or_else(|| {
+        parameters
+            .iter()
+            .position(|parameter| parameter.kind == ParameterKind::VarPositional)
+    })
+}
+
+/// Returns the parameter the keyword argument `name` is bound to.
+fn keyword_parameter(parameters: &[SignatureParameter], name: &str) -> Option<usize> {
+    parameters
+        .iter()
+        .position(|parameter| {
+            parameter.name == name
+                && matches!(
+                    parameter.kind,
+                    ParameterKind::PositionalOrKeyword | ParameterKind::KeywordOnly
+                )
+        })
+        .or_else(|| {
+            parameters
+                .iter()
+                .position(|parameter| parameter.kind == ParameterKind::VarKeyword)
+        })
+}
+
+/// Returns the first parameter that can be passed by keyword and isn't bound to one of
+/// `arguments` already.
+fn next_keyword_parameter(
+    parameters: &[SignatureParameter],
+    arguments: &[ArgOrKeyword],
+) -> Option<usize> {
+    let positional = arguments
+        .iter()
+        .filter(|argument| matches!(argument, ArgOrKeyword::Arg(_)))
+        .count();
+    let supplied: Vec<_> = arguments
+        .iter()
+        .filter_map(|argument| match argument {
+            ArgOrKeyword::Keyword(ast::Keyword { arg: Some(arg), .. }) => Some(arg.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    parameters
+        .iter()
+        .enumerate()
+        .skip(positional)
+        .find(|(_, parameter)| {
+            matches!(
+                parameter.kind,
+                ParameterKind::PositionalOrKeyword | ParameterKind::KeywordOnly
+            ) && !supplied.contains(&parameter.name)
+        })
+        .map(|(index, _)| index)
+        .or_else(|| {
+            parameters
+                .iter()
+                .position(|parameter| parameter.kind == ParameterKind::VarKeyword)
+        })
+}
+
+fn has_decorator(function: &ast::FunctionDefStmt, name: &str, source: &str) -> bool {
+    function
+        .decorator_list
+        .iter()
+        .any(|decorator| source_text(&decorator.expression, source) == name)
+}
+
+/// Returns `true` if `func` is a method accessed on an instance, e.g. `self.method` or
+/// `Foo().method`, rather than on its class, e.g. `Foo.method`.
+fn is_called_on_instance(
+    resolver: &mut DefinitionResolver,
+    module: &Arc<ParsedModule>,
+    func: &Expr,
+) -> bool {
+    let Expr::Attribute(attribute) = func else {
+        return false;
+    };
+    let is_class = resolver
+        .resolve_expression(module, &attribute.value, 0)
+        .first()
+        .and_then(|definition| definition.as_binding())
+        .is_some_and(|binding| matches!(binding.kind, BindingKind::ClassDefinition(_)));
+    !is_class
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use std::sync::Arc;
+
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::TextSize;
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+
+    use super::signature_help;
+
+    const DEFINITIONS: &str = r#"def f(a, b, /, c, *args, d, e=1, **kwargs) -> int:
+    """Does things."""
+
+def g(a, *, b, c): ...
+
+class Point:
+    def __init__(self, x: int, y: int = 0): ...
+
+    def move(self, dx, dy): ...
+
+"#;
+
+    /// Returns the label of the signature and the label of the active parameter, the cursor
+    /// is at the `|` in `call` or at its end.
+    fn active(call: &str) -> Option<(String, Option<String>)> {
+        let source = format!("{DEFINITIONS}{call}");
+        let offset = source.find('|').unwrap_or(source.len());
+        let source = source.replace('|', "");
+        let module = Arc::new(ParsedModule::new(
+            Path::new("/project/main.py").to_path_buf(),
+            source.clone(),
+            LineIndex::from_source_text(&source),
+        ));
+        let import_resolver = ImportResolver::new(Path::new("/project").to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        resolver.insert(module.clone());
+
+        let offset = TextSize::try_from(offset).unwrap();
+        let help = signature_help(&mut resolver, &module, offset)?;
+        let parameter = help
+            .active_parameter
+            .map(|index| help.label[help.parameters[index]].to_string());
+        Some((help.label, parameter))
+    }
+
+    fn active_parameter(call: &str) -> Option<String> {
+        active(call).and_then(|(_, parameter)| parameter)
+    }
+
+    #[test]
+    fn labels() {
+        assert_eq!(
+            active("f(").unwrap().0,
+            "f(a, b, /, c, *args, d, e=1, **kwargs) -> int"
+        );
+        assert_eq!(active("g(").unwrap().0, "g(a, *, b, c)");
+        assert_eq!(active("Point(").unwrap().0, "Point(x: int, y: int = 0)");
+        assert_eq!(active("Point.move(").unwrap().0, "move(self, dx, dy)");
+        assert_eq!(
+            active("    def run(self):\n        self.move(").unwrap().0,
+            "move(dx, dy)"
+        );
+        assert_eq!(active("f"), None);
+    }
+
+    #[test]
+    fn positional_arguments() {
+        assert_eq!(active_parameter("f(").as_deref(), Some("a"));
+        assert_eq!(active_parameter("f(1, ").as_deref(), Some("b"));
+        assert_eq!(active_parameter("f(1, 2, 3").as_deref(), Some("c"));
+        assert_eq!(
+            active_parameter("f(1, 2, 3, 4, 5, ").as_deref(),
+            Some("*args")
+        );
+        assert_eq!(active_parameter("f(*xs").as_deref(), Some("*args"));
+        assert_eq!(active_parameter("g(1, ").as_deref(), None);
+        assert_eq!(active_parameter("Point(1, ").as_deref(), Some("y: int = 0"));
+    }
+
+    #[test]
+    fn keyword_arguments() {
+        assert_eq!(active_parameter("f(1, 2, d=").as_deref(), Some("d"));
+        assert_eq!(active_parameter("f(1, 2, a=").as_deref(), Some("**kwargs"));
+        assert_eq!(active_parameter("f(1, 2, d=1, ").as_deref(), Some("c"));
+        assert_eq!(
+            active_parameter("f(1, 2, c=1, d=1, ").as_deref(),
+            Some("e=1")
+        );
+        assert_eq!(active_parameter("g(1, c=1, ").as_deref(), Some("b"));
+        assert_eq!(active_parameter("f(**options").as_deref(), Some("**kwargs"));
+    }
+
+    #[test]
+    fn nested_and_closed_calls() {
+        assert_eq!(active_parameter("f(1, g(2, b=").as_deref(), Some("b"));
+        assert_eq!(active_parameter("f(1, g(2, b=3), ").as_deref(), Some("c"));
+        assert_eq!(active("f(1)\n"), None);
+        assert_eq!(active_parameter("f(1, |\nx = 1\n").as_deref(), Some("b"));
+        assert_eq!(active_parameter("f(1, |, d=2)").as_deref(), Some("b"));
+    }
+}
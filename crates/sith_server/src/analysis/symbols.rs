@@ -0,0 +1,312 @@
+//! The outline of the symbols defined by a module.
+
+use lsp_types::SymbolKind;
+use python_ast::statement_visitor::{walk_match_case, walk_stmt, StatementVisitor};
+use python_ast::{self as ast, Expr, ModModule, Pattern, Stmt};
+use ruff_text_size::{Ranged, TextRange};
+
+/// A symbol of the outline of a module.
+#[derive(Debug)]
+pub(crate) struct Symbol {
+    pub(crate) name: String,
+    pub(crate) kind: SymbolKind,
+    /// The range of the whole definition, e.g. a function including its body.
+    pub(crate) range: TextRange,
+    /// The range of the defined name.
+    pub(crate) selection_range: TextRange,
+    pub(crate) children: Vec<Symbol>,
+}
+
+/// Returns the symbols defined by `module`: its classes and functions, with their nested
+/// definitions as children, and the names assigned in the module and in the classes.
+///
+/// A module with syntax errors still has an outline, built from the statements the parser
+/// recovered.
+pub(crate) fn document_symbols(module: &ModModule) -> Vec<Symbol> {
+    let mut collector = SymbolCollector::new(Container::Module);
+    collector.visit_body(&module.body);
+    collector.symbols
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Container {
+    Module,
+    Class,
+    Function,
+}
+
+struct SymbolCollector {
+    container: Container,
+    symbols: Vec<Symbol>,
+}
+
+impl SymbolCollector {
+    fn new(container: Container) -> Self {
+        Self {
+            container,
+            symbols: Vec::new(),
+        }
+    }
+
+    /// Returns the symbols defined in `body`, a class or function of this container.
+    fn children(body: &[Stmt], container: Container) -> Vec<Symbol> {
+        let mut collector = Self::new(container);
+        collector.visit_body(body);
+        collector.symbols
+    }
+
+    fn push(&mut self, name: &str, kind: SymbolKind, range: TextRange, selection_range: TextRange) {
+        self.push_with_children(name, kind, range, selection_range, Vec::new());
+    }
+
+    fn push_with_children(
+        &mut self,
+        name: &str,
+        kind: SymbolKind,
+        range: TextRange,
+        selection_range: TextRange,
+        children: Vec<Symbol>,
+    ) {
+        // The parser recovers from incomplete definitions with empty names.
+        if name.is_empty() {
+            return;
+        }
+        self.symbols.push(Symbol {
+            name: name.to_string(),
+            kind,
+            range,
+            selection_range,
+            children,
+        });
+    }
+
+    /// Pushes the variable assigned by `name`, only its first assignment is part of the outline.
+    fn push_variable(&mut self, name: &str, name_range: TextRange, range: TextRange) {
+        if self.container == Container::Function
+            || self.symbols.iter().any(|symbol| symbol.name == name)
+        {
+            return;
+        }
+
+        let kind = match self.container {
+            Container::Class => SymbolKind::FIELD,
+            _ if is_constant(name) => SymbolKind::CONSTANT,
+            _ => SymbolKind::VARIABLE,
+        };
+        self.push(name, kind, range, name_range);
+    }
+
+    /// Pushes the names assigned by `target`, which may unpack a tuple or a list.
+    fn push_target(&mut self, target: &Expr, range: TextRange) {
+        match target {
+            Expr::Name(name) => self.push_variable(&name.id, name.range, range),
+            Expr::Tuple(ast::TupleExpr { elts, .. }) | Expr::List(ast::ListExpr { elts, .. }) => {
+                for element in elts {
+                    self.push_target(element, range);
+                }
+            }
+            Expr::Starred(ast::StarredExpr { value, .. }) => self.push_target(value, range),
+            _ => {}
+        }
+    }
+
+    /// Pushes the names captured by `pattern`.
+    fn push_pattern(&mut self, pattern: &Pattern, range: TextRange) {
+        match pattern {
+            Pattern::MatchAs(ast::PatternMatchAs { pattern, name, .. }) => {
+                if let Some(pattern) = pattern {
+                    self.push_pattern(pattern, range);
+                }
+                if let Some(name) = name {
+                    self.push_variable(name, name.range, range);
+                }
+            }
+            Pattern::MatchStar(ast::PatternMatchStar {
+                name: Some(name), ..
+            }) => self.push_variable(name, name.range, range),
+            Pattern::MatchSequence(ast::PatternMatchSequence { patterns, .. })
+            | Pattern::MatchOr(ast::PatternMatchOr { patterns, .. }) => {
+                for pattern in patterns {
+                    self.push_pattern(pattern, range);
+                }
+            }
+            Pattern::MatchMapping(ast::PatternMatchMapping { patterns, rest, .. }) => {
+                for pattern in patterns {
+                    self.push_pattern(pattern, range);
+                }
+                if let Some(rest) = rest {
+                    self.push_variable(rest, rest.range, range);
+                }
+            }
+            Pattern::MatchClass(ast::PatternMatchClass { arguments, .. }) => {
+                for pattern in &arguments.patterns {
+                    self.push_pattern(pattern, range);
+                }
+                for keyword in &arguments.keywords {
+                    self.push_pattern(&keyword.pattern, range);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl<'a> StatementVisitor<'a> for SymbolCollector {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::FunctionDef(function) => {
+                let kind = match self.container {
+                    Container::Class if function.name.as_str() == "__init__" => {
+                        SymbolKind::CONSTRUCTOR
+                    }
+                    Container::Class => SymbolKind::METHOD,
+                    _ => SymbolKind::FUNCTION,
+                };
+                let children = Self::children(&function.body, Container::Function);
+                self.push_with_children(
+                    &function.name,
+                    kind,
+                    function.range,
+                    function.name.range,
+                    children,
+                );
+            }
+            Stmt::ClassDef(class) => {
+                let children = Self::children(&class.body, Container::Class);
+                self.push_with_children(
+                    &class.name,
+                    SymbolKind::CLASS,
+                    class.range,
+                    class.name.range,
+                    children,
+                );
+            }
+            Stmt::TypeAlias(ast::TypeAliasStmt { name, range, .. }) => {
+                if let Expr::Name(name) = name.as_ref() {
+                    // LSP has no kind for type aliases, they name a type like a class does.
+                    self.push(&name.id, SymbolKind::CLASS, *range, name.range);
+                }
+            }
+            Stmt::Assign(ast::AssignStmt { targets, range, .. }) => {
+                for target in targets {
+                    self.push_target(target, *range);
+                }
+            }
+            Stmt::AnnAssign(ast::AnnAssignStmt { target, range, .. }) => {
+                self.push_target(target, *range);
+            }
+            _ => walk_stmt(self, stmt),
+        }
+    }
+
+    fn visit_match_case(&mut self, match_case: &'a ast::MatchCase) {
+        self.push_pattern(&match_case.pattern, match_case.pattern.range());
+        walk_match_case(self, match_case);
+    }
+}
+
+/// Returns `true` if `name` follows the naming convention of constants, e.g. `MAX_SIZE`.
+pub(super) fn is_constant(name: &str) -> bool {
+    name.chars().any(|c| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::SymbolKind;
+    use python_ast::PySourceType;
+
+    use super::{document_symbols, Symbol};
+
+    /// Renders the outline of `source` with one symbol per line, indented by depth.
+    fn outline(source: &str) -> String {
+        fn render(symbols: &[Symbol], source: &str, depth: usize, output: &mut String) {
+            for symbol in symbols {
+                let kind = match symbol.kind {
+                    SymbolKind::CLASS => "class",
+                    SymbolKind::CONSTRUCTOR => "constructor",
+                    SymbolKind::METHOD => "method",
+                    SymbolKind::FUNCTION => "function",
+                    SymbolKind::FIELD => "field",
+                    SymbolKind::CONSTANT => "constant",
+                    SymbolKind::VARIABLE => "variable",
+                    kind => panic!("unexpected kind {kind:?}"),
+                };
+                assert_eq!(&source[symbol.selection_range], symbol.name);
+                assert!(symbol.range.contains_range(symbol.selection_range));
+                output.push_str(&format!("{}{kind} {}\n", "  ".repeat(depth), symbol.name));
+                render(&symbol.children, source, depth + 1, output);
+            }
+        }
+
+        let parsed = python_parser::parse_unchecked_source(source, PySourceType::Python);
+        let mut output = String::new();
+        render(&document_symbols(parsed.syntax()), source, 0, &mut output);
+        output
+    }
+
+    #[test]
+    fn outline_of_definitions() {
+        let source = r#"MAX_SIZE = 10
+first, *rest = [1, 2, 3]
+first = 4
+type Pair = tuple[int, int]
+
+class Shape:
+    sides: int = 0
+
+    def __init__(self, name):
+        self.name = name
+
+    def area(self):
+        def helper():
+            pass
+        total = 0
+        return total
+
+if True:
+    enabled = True
+
+match command:
+    case [action, *arguments]:
+        pass
+    case {"key": value, **others}:
+        pass
+    case Point(x=horizontal) | other:
+        pass
+"#;
+
+        assert_eq!(
+            outline(source),
+            "\
+constant MAX_SIZE
+variable first
+variable rest
+class Pair
+class Shape
+  field sides
+  constructor __init__
+  method area
+    function helper
+variable enabled
+variable action
+variable arguments
+variable value
+variable others
+variable horizontal
+variable other
+"
+        );
+    }
+
+    #[test]
+    fn partial_outline_with_syntax_errors() {
+        let source = "class Broken:\n    def method(self\n\ndef valid():\n    pass\n";
+
+        let outline = outline(source);
+        assert!(outline.starts_with("class Broken\n  method method\n"));
+        assert!(outline.contains("function valid"));
+    }
+}
@@ -0,0 +1,164 @@
+//! The names a module uses without defining them.
+
+use python_ast::visitor::preorder::{PreorderVisitor, TraversalSignal};
+use python_ast::{self as ast, AnyNodeRef, ContextExpr};
+use ruff_text_size::TextRange;
+use rustc_hash::{FxHashMap, FxHashSet};
+use semantic_model::builtins::BUILTIN_SYMBOLS;
+
+use super::{DefinitionResolver, ParsedModule};
+
+/// The names every module, class or method has without binding them, besides the ones
+/// inherited from `builtins`, and the special forms of type checkers.
+const IMPLICIT_NAMES: &[&str] = &[
+    "__annotations__",
+    "__builtins__",
+    "__cached__",
+    "__class__",
+    "__dict__",
+    "__file__",
+    "__module__",
+    "__path__",
+    "__qualname__",
+    "reveal_locals",
+    "reveal_type",
+];
+
+/// Returns the names loaded in `module` that aren't bound in scope, builtins or imported by a
+/// wildcard import.
+///
+/// A name in a scope affected by a wildcard import of a module that can't be resolved is
+/// never reported, as it may be defined by it.
+pub(crate) fn undefined_names(
+    module: &ParsedModule,
+    resolver: &mut DefinitionResolver,
+) -> Vec<(String, TextRange)> {
+    let mut collector = LoadedNames::default();
+    collector.visit_body(&module.syntax().body);
+
+    let model = module.model();
+    // The names bound by each wildcard import, `None` if its module can't be resolved.
+    let mut star_imports: FxHashMap<TextRange, Option<FxHashSet<String>>> = FxHashMap::default();
+
+    collector
+        .names
+        .into_iter()
+        .filter(|(name, range)| {
+            if BUILTIN_SYMBOLS.contains(&name.as_str()) || IMPLICIT_NAMES.contains(&name.as_str()) {
+                return false;
+            }
+            let scope = model.scope_at(range.start());
+            if model.lookup(name, scope, range.start()).is_some() {
+                return false;
+            }
+
+            let mut imports = model
+                .ancestors(scope)
+                .flat_map(|id| model.scope(id).star_imports());
+            !imports.any(|star_import| {
+                star_imports
+                    .entry(star_import.range)
+                    .or_insert_with(|| {
+                        resolver.star_import_names(
+                            module.path(),
+                            star_import.level,
+                            star_import.module.as_deref().unwrap_or(""),
+                        )
+                    })
+                    .as_ref()
+                    .is_none_or(|names| names.contains(name))
+            })
+        })
+        .collect()
+}
+
+/// Collects the names loaded by a module, e.g. `x` in `print(x)`.
+#[derive(Default)]
+struct LoadedNames {
+    names: Vec<(String, TextRange)>,
+}
+
+impl<'a> PreorderVisitor<'a> for LoadedNames {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        if let AnyNodeRef::NameExpr(ast::NameExpr {
+            id,
+            range,
+            ctx: ContextExpr::Load,
+        }) = node
+        {
+            self.names.push((id.to_string(), *range));
+        }
+
+        TraversalSignal::Traverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use ruff_source_file::LineIndex;
+
+    use crate::analysis::{DefinitionResolver, ParsedModule};
+    use crate::resolve::ImportResolver;
+
+    use super::undefined_names;
+
+    fn undefined(root: &Path, source: &str) -> Vec<String> {
+        let module = ParsedModule::new(
+            root.join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        );
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        let mut resolver = DefinitionResolver::new(&import_resolver);
+        undefined_names(&module, &mut resolver)
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    #[test]
+    fn undefined_names_are_found() {
+        let source = r#"
+import os
+
+def f(a):
+    b = a + len(os.sep)
+    return b + c + __file__
+
+class A:
+    x = 1
+    squares = [x * x for x in range(3)]
+    copies = [y for y in squares]
+    hidden = [x for _ in range(3)]
+
+    def g(self):
+        return x
+
+print(undefined, [y for y in range(3)], __name__)
+"#;
+        assert_eq!(
+            undefined(Path::new("/project"), source),
+            ["c", "x", "x", "undefined"]
+        );
+    }
+
+    #[test]
+    fn star_imports_define_names() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::write(root.join("exported.py"), "__all__ = ['a']\na = b = 1\n").unwrap();
+        std::fs::write(
+            root.join("public.py"),
+            "from exported import *\nc = _d = 1\n",
+        )
+        .unwrap();
+
+        let source = "from public import *\nprint(a, b, c, _d)\n";
+        assert_eq!(undefined(root, source), ["b", "_d"]);
+
+        let source = "from missing import *\nprint(a)\n";
+        assert!(undefined(root, source).is_empty());
+    }
+}
@@ -114,14 +114,16 @@ impl ToLocation for TextRange {
     }
 }
 
-pub fn position_to_offset(contents: &str, position: &types::Position, index: &LineIndex) -> u32 {
-    index
-        .line_start(
-            OneIndexed::from_zero_indexed(u32_index_to_usize(position.line)),
-            contents,
-        )
-        .to_u32()
-        + position.character
+/// Converts an LSP position to a byte offset in `text`.
+pub(crate) fn position_to_offset(
+    position: types::Position,
+    text: &str,
+    index: &LineIndex,
+    encoding: PositionEncoding,
+) -> TextSize {
+    types::Range::new(position, position)
+        .to_text_range(text, index, encoding)
+        .start()
 }
 
 /// Converts a UTF-16 code unit offset for a given line into a UTF-8 column number.
@@ -1,3 +1,6 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::Arc;
+
 use lsp_types::TextDocumentContentChangeEvent;
 use ruff_source_file::LineIndex;
 
@@ -20,6 +23,9 @@ pub struct Document {
     /// The latest version of the document, set by the LSP client. The server will panic in
     /// debug mode if we attempt to update the document with an 'older' version.
     version: DocumentVersion,
+    /// The version of the most recent edit made to this document. Clones of a document
+    /// share this value, which lets an older snapshot know that it has been superseded.
+    latest_version: Arc<AtomicI32>,
 }
 
 impl Document {
@@ -29,6 +35,7 @@ impl Document {
             contents,
             index,
             version,
+            latest_version: Arc::new(AtomicI32::new(version)),
         }
     }
 
@@ -44,6 +51,12 @@ impl Document {
         self.version
     }
 
+    /// Returns `true` if a newer version of this document has been created since this
+    /// snapshot of it was taken.
+    pub fn is_outdated(&self) -> bool {
+        self.latest_version.load(Ordering::Acquire) != self.version
+    }
+
     pub fn apply_changes(
         &mut self,
         changes: Vec<lsp_types::TextDocumentContentChangeEvent>,
@@ -62,38 +75,26 @@ impl Document {
             return;
         }
 
-        let old_contents = self.contents().to_string();
-        let mut new_contents = self.contents().to_string();
-        let mut active_index = self.index().clone();
-
-        // TODO: Is this code executed when using `TextDocumentSyncKind::FULL`?
-        for TextDocumentContentChangeEvent {
-            range,
-            text: change,
-            ..
-        } in changes
-        {
-            if let Some(range) = range {
-                let range = range.to_text_range(&new_contents, &active_index, encoding);
-
-                new_contents.replace_range(
-                    usize::from(range.start())..usize::from(range.end()),
-                    &change,
-                );
-            } else {
-                new_contents = change;
-            }
-
-            if new_contents != old_contents {
-                active_index = LineIndex::from_source_text(&new_contents);
-            }
-        }
-
         self.modify_with_manual_index(|contents, version, index| {
-            if contents != &new_contents {
-                *index = active_index;
+            for TextDocumentContentChangeEvent {
+                range,
+                text: change,
+                ..
+            } in changes
+            {
+                if let Some(range) = range {
+                    let range = range.to_text_range(contents, index, encoding);
+
+                    contents.replace_range(
+                        usize::from(range.start())..usize::from(range.end()),
+                        &change,
+                    );
+                    index.apply_edit(range, &change, contents);
+                } else {
+                    *contents = change;
+                    *index = LineIndex::from_source_text(contents);
+                }
             }
-            *contents = new_contents;
             *version = new_version;
         });
     }
@@ -120,5 +121,6 @@ impl Document {
         let old_version = self.version;
         func(&mut self.contents, &mut self.version, &mut self.index);
         debug_assert!(self.version >= old_version);
+        self.latest_version.store(self.version, Ordering::Release);
     }
 }
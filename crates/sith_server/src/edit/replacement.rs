@@ -18,11 +18,11 @@ impl Replacement {
         let mut replaced_start = TextSize::default();
         let mut source_end = source.text_len();
         let mut replaced_end = modified.text_len();
-        let mut line_iter = source_line_starts
+        for (source_line_start, modified_line_start) in source_line_starts
             .iter()
             .copied()
-            .zip(modified_line_starts.iter().copied());
-        for (source_line_start, modified_line_start) in line_iter.by_ref() {
+            .zip(modified_line_starts.iter().copied())
+        {
             if source_line_start != modified_line_start
                 || source[TextRange::new(source_start, source_line_start)]
                     != modified[TextRange::new(replaced_start, modified_line_start)]
@@ -33,11 +33,15 @@ impl Replacement {
             replaced_start = modified_line_start;
         }
 
-        let mut line_iter = line_iter.rev();
-
-        for (old_line_start, new_line_start) in line_iter.by_ref() {
-            if old_line_start <= source_start
-                || new_line_start <= replaced_start
+        // Walk both line starts backwards from the end, the sources may differ in length.
+        for (old_line_start, new_line_start) in source_line_starts
+            .iter()
+            .copied()
+            .rev()
+            .zip(modified_line_starts.iter().copied().rev())
+        {
+            if old_line_start < source_start
+                || new_line_start < replaced_start
                 || source[TextRange::new(old_line_start, source_end)]
                     != modified[TextRange::new(new_line_start, replaced_end)]
             {
@@ -219,4 +223,42 @@ cccc
         );
         assert_eq!(modified, &expected);
     }
+
+    #[test]
+    fn replace_line_of_different_length() {
+        let source = "aaaa
+bb
+cccc
+";
+        let modified = "aaaa
+bbbbbb
+cccc
+";
+        let (replacement, expected) = compute_replacement(source, modified);
+        assert_eq!(replacement.source_range, TextRange::new(5.into(), 8.into()));
+        assert_eq!(
+            replacement.modified_range,
+            TextRange::new(5.into(), 12.into())
+        );
+        assert_eq!(modified, &expected);
+    }
+
+    #[test]
+    fn delete_repeated_line() {
+        // The unchanged lines before and after the deleted line overlap.
+        let source = "aaaa
+aaaa
+aaaa
+";
+        let modified = "aaaa
+aaaa
+";
+        let (replacement, expected) = compute_replacement(source, modified);
+        assert_eq!(
+            replacement.source_range,
+            TextRange::new(10.into(), 15.into())
+        );
+        assert_eq!(replacement.modified_range, TextRange::empty(10.into()));
+        assert_eq!(modified, &expected);
+    }
 }
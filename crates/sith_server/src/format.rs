@@ -0,0 +1,349 @@
+//! Formatting of Python files with an external formatter, either Ruff or Black.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use ruff_source_file::LineIndex;
+use ruff_text_size::TextRange;
+
+use crate::session::RuffSettings;
+
+/// The formatter run when none of the configured paths exist, looked up in `PATH`.
+const DEFAULT_FORMATTER: &str = "ruff";
+
+/// How long the formatter may run before it's killed.
+const FORMAT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Formatter {
+    Ruff,
+    Black,
+}
+
+impl Formatter {
+    fn from_executable(executable: &Path) -> Self {
+        let is_black = executable
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .is_some_and(|stem| stem.starts_with("black"));
+        if is_black {
+            Self::Black
+        } else {
+            Self::Ruff
+        }
+    }
+}
+
+/// Pipes `source`, the contents of the file at `path`, through the formatter configured by
+/// `settings` and returns the formatted source. Only the lines overlapping `range` are
+/// formatted if it's given.
+///
+/// The formatter runs in `root`, so that it finds the project's configuration.
+pub(crate) fn format(
+    source: &str,
+    index: &LineIndex,
+    path: Option<&Path>,
+    range: Option<TextRange>,
+    root: &Path,
+    settings: &RuffSettings,
+) -> crate::Result<String> {
+    let executable = executable(&settings.path);
+    let arguments = arguments(
+        Formatter::from_executable(&executable),
+        source,
+        index,
+        path,
+        range,
+        &settings.format.args,
+    );
+
+    let mut child = Command::new(&executable)
+        .args(&arguments)
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("Failed to run the formatter `{}`", executable.display()))?;
+
+    // The source is written and the output read on their own threads, so that a formatter
+    // that stops reading its input or fills its output pipes can't block the request.
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("Failed to open the formatter's stdin")?;
+    let input = source.as_bytes().to_vec();
+    // Dropping stdin once it's written closes it, letting the formatter know it's complete.
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    let status =
+        wait_timeout(&mut child, FORMAT_TIMEOUT).context("Failed to wait for the formatter")?;
+    let Some(status) = status else {
+        // Killing the formatter closes the pipes, which ends the threads.
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(anyhow!(
+            "`{}` didn't format the file within {} seconds",
+            executable.display(),
+            FORMAT_TIMEOUT.as_secs()
+        ));
+    };
+
+    let stdout = join_output(stdout).context("Failed to read the formatter's output")?;
+    let stderr = join_output(stderr).context("Failed to read the formatter's output")?;
+    let written = writer
+        .join()
+        .map_err(|_| anyhow!("The thread writing to the formatter panicked"))?;
+
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(anyhow!(
+            "`{}` failed to format the file ({}): {}",
+            executable.display(),
+            status,
+            stderr.trim()
+        ));
+    }
+    // A formatter exiting early may have closed stdin, which is only an error if it succeeded.
+    written.context("Failed to send the file to the formatter")?;
+
+    String::from_utf8(stdout).context("The formatter's output isn't valid UTF-8")
+}
+
+/// Waits for `child` to exit and returns its status, or `None` if it's still running after
+/// `timeout`.
+fn wait_timeout(child: &mut Child, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+    let deadline = Instant::now() + timeout;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if Instant::now() >= deadline {
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// Reads the whole of `pipe` on another thread.
+fn read_in_background(
+    pipe: Option<impl Read + Send + 'static>,
+) -> JoinHandle<std::io::Result<Vec<u8>>> {
+    std::thread::spawn(move || {
+        let mut output = Vec::new();
+        if let Some(mut pipe) = pipe {
+            pipe.read_to_end(&mut output)?;
+        }
+        Ok(output)
+    })
+}
+
+fn join_output(reader: JoinHandle<std::io::Result<Vec<u8>>>) -> crate::Result<Vec<u8>> {
+    let output = reader
+        .join()
+        .map_err(|_| anyhow!("The thread reading from the formatter panicked"))??;
+    Ok(output)
+}
+
+/// Returns the first of the configured `paths` that exists, or the default formatter. Bare
+/// command names, e.g. `black`, are looked up in `PATH`.
+fn executable(paths: &[PathBuf]) -> PathBuf {
+    paths
+        .iter()
+        .find(|path| path.is_file() || is_command_name(path))
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_FORMATTER))
+}
+
+fn is_command_name(path: &Path) -> bool {
+    path.is_relative() && path.components().count() == 1
+}
+
+/// Returns the command-line arguments formatting the source read from stdin.
+fn arguments(
+    formatter: Formatter,
+    source: &str,
+    index: &LineIndex,
+    path: Option<&Path>,
+    range: Option<TextRange>,
+    extra_arguments: &[String],
+) -> Vec<String> {
+    let mut arguments = match formatter {
+        // Respect the files excluded in the configuration, Ruff outputs them unchanged.
+        Formatter::Ruff => vec![
+            "format".to_string(),
+            "--force-exclude".to_string(),
+            "--quiet".to_string(),
+        ],
+        Formatter::Black => vec!["--quiet".to_string()],
+    };
+
+    if let Some(path) = path {
+        arguments.push("--stdin-filename".to_string());
+        arguments.push(path.display().to_string());
+    }
+
+    if let Some(range) = range {
+        let start = index.source_location(range.start(), source);
+        let end = index.source_location(range.end(), source);
+        match formatter {
+            // Ruff's range is made of one-based lines and columns, e.g. `1:1-2:5`.
+            Formatter::Ruff => arguments.push(format!(
+                "--range={}:{}-{}:{}",
+                start.row, start.column, end.row, end.column
+            )),
+            // Black formats whole lines, a range ending at the start of a line excludes it.
+            Formatter::Black => {
+                let end_row = if end.column.get() == 1 && end.row > start.row {
+                    end.row.get() - 1
+                } else {
+                    end.row.get()
+                };
+                arguments.push(format!("--line-ranges={}-{end_row}", start.row));
+            }
+        }
+    }
+
+    arguments.extend(extra_arguments.iter().cloned());
+    arguments.push("-".to_string());
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use ruff_source_file::LineIndex;
+    use ruff_text_size::{TextRange, TextSize};
+
+    use crate::session::RuffSettings;
+
+    use super::{arguments, executable, format, wait_timeout, Formatter};
+
+    #[test]
+    fn formatter_arguments() {
+        let source = "x = 1\ny  =  2\nz = 3\n";
+        let index = LineIndex::from_source_text(source);
+        let path = Path::new("/project/main.py");
+        // From `y` up to the start of the line of `z`.
+        let range = TextRange::new(TextSize::new(6), TextSize::new(14));
+        let extra = vec!["--line-length=100".to_string()];
+
+        assert_eq!(
+            arguments(Formatter::Ruff, source, &index, Some(path), None, &extra),
+            [
+                "format",
+                "--force-exclude",
+                "--quiet",
+                "--stdin-filename",
+                "/project/main.py",
+                "--line-length=100",
+                "-"
+            ]
+        );
+        assert_eq!(
+            arguments(Formatter::Ruff, source, &index, None, Some(range), &[]),
+            [
+                "format",
+                "--force-exclude",
+                "--quiet",
+                "--range=2:1-3:1",
+                "-"
+            ]
+        );
+        assert_eq!(
+            arguments(
+                Formatter::Black,
+                source,
+                &index,
+                Some(path),
+                Some(range),
+                &[]
+            ),
+            [
+                "--quiet",
+                "--stdin-filename",
+                "/project/main.py",
+                "--line-ranges=2-2",
+                "-"
+            ]
+        );
+    }
+
+    #[test]
+    fn formatter_executable() {
+        let root = tempfile::tempdir().unwrap();
+        let black = root.path().join("black");
+        std::fs::write(&black, "").unwrap();
+
+        let paths = vec![root.path().join("missing/ruff"), black.clone()];
+        assert_eq!(executable(&paths), black);
+        assert_eq!(Formatter::from_executable(&black), Formatter::Black);
+        assert_eq!(executable(&[]), PathBuf::from("ruff"));
+        assert_eq!(
+            executable(&[PathBuf::from("black")]),
+            PathBuf::from("black")
+        );
+        assert_eq!(
+            Formatter::from_executable(Path::new("ruff")),
+            Formatter::Ruff
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn formatter_failure() {
+        let root = tempfile::tempdir().unwrap();
+        let settings = RuffSettings {
+            path: vec![PathBuf::from("/usr/bin/false")],
+            ..RuffSettings::default()
+        };
+
+        let source = "x = 1\n";
+        let index = LineIndex::from_source_text(source);
+        let error = format(source, &index, None, None, root.path(), &settings).unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("`/usr/bin/false` failed to format the file"));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn formatter_large_input() {
+        use std::os::unix::fs::PermissionsExt;
+
+        // A formatter echoing its input, which is larger than the pipe buffers.
+        let root = tempfile::tempdir().unwrap();
+        let formatter = root.path().join("formatter");
+        std::fs::write(&formatter, "#!/bin/sh\nexec cat\n").unwrap();
+        std::fs::set_permissions(&formatter, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let settings = RuffSettings {
+            path: vec![formatter],
+            ..RuffSettings::default()
+        };
+
+        let source = "x = 1\n".repeat(100_000);
+        let index = LineIndex::from_source_text(&source);
+        let formatted = format(&source, &index, None, None, root.path(), &settings).unwrap();
+        assert_eq!(formatted, source);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn formatter_timeout() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        let status = wait_timeout(&mut child, std::time::Duration::from_millis(50)).unwrap();
+        assert_eq!(status, None);
+        child.kill().unwrap();
+        child.wait().unwrap();
+    }
+}
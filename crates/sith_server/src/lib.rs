@@ -1,12 +1,19 @@
 pub use edit::{Document, PositionEncoding};
 pub use server::Server;
 
+mod analysis;
 mod edit;
+mod format;
+mod lint;
+mod resolve;
 mod server;
 mod session;
 
 pub(crate) const SERVER_NAME: &str = "Sith LSP";
 pub(crate) const DIAGNOSTIC_NAME: &str = "Sith";
+/// The kind of the code action applying every safe fix of a document.
+pub(crate) const SOURCE_FIX_ALL_SITH: lsp_types::CodeActionKind =
+    lsp_types::CodeActionKind::new("source.fixAll.sith");
 
 /// A common result type used in most cases where a
 /// result type is needed.
@@ -0,0 +1,319 @@
+//! Access to the diagnostics produced by Sith for a document.
+
+mod fix;
+mod imports;
+mod suppression;
+mod undefined;
+mod unused;
+
+use lsp_types::{Diagnostic, DiagnosticSeverity, DiagnosticTag, NumberOrString};
+use python_parser::ParseError;
+use ruff_text_size::TextRange;
+use rustc_hash::FxHashSet;
+
+use crate::analysis::{Edit, ParsedModule};
+use crate::edit::ToRangeExt;
+use crate::resolve::ImportResolver;
+use crate::PositionEncoding;
+
+pub(crate) use self::fix::Fix;
+use self::suppression::Suppressions;
+
+/// The kinds of problems reported in addition to syntax errors, each with a stable code that
+/// suppression comments can refer to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rule {
+    /// An imported module that can't be resolved.
+    UnresolvedImport,
+    /// An imported submodule that can't be resolved, although its package was found.
+    PartlyResolvedImport,
+    /// An imported module with a stub but without its source.
+    MissingModuleSource,
+    /// A name that isn't defined in scope.
+    UndefinedVariable,
+    /// An imported name that's never used.
+    UnusedImport,
+    /// A variable assigned in a function that's never used.
+    UnusedVariable,
+}
+
+impl Rule {
+    pub(crate) const fn code(self) -> &'static str {
+        match self {
+            Rule::UnresolvedImport => "reportMissingImports",
+            Rule::PartlyResolvedImport => "reportMissingSubmodule",
+            Rule::MissingModuleSource => "reportMissingModuleSource",
+            Rule::UndefinedVariable => "reportUndefinedVariable",
+            Rule::UnusedImport => "reportUnusedImport",
+            Rule::UnusedVariable => "reportUnusedVariable",
+        }
+    }
+
+    const fn severity(self) -> DiagnosticSeverity {
+        match self {
+            Rule::UnresolvedImport | Rule::UndefinedVariable => DiagnosticSeverity::ERROR,
+            Rule::PartlyResolvedImport => DiagnosticSeverity::WARNING,
+            Rule::MissingModuleSource => DiagnosticSeverity::INFORMATION,
+            Rule::UnusedImport | Rule::UnusedVariable => DiagnosticSeverity::WARNING,
+        }
+    }
+
+    /// Returns the tags of the rule's diagnostics, e.g. to fade out unused code.
+    fn tags(self) -> Option<Vec<DiagnosticTag>> {
+        match self {
+            Rule::UnusedImport | Rule::UnusedVariable => Some(vec![DiagnosticTag::UNNECESSARY]),
+            _ => None,
+        }
+    }
+}
+
+/// A problem found in a module by one of the [`Rule`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LintDiagnostic {
+    pub(crate) rule: Rule,
+    pub(crate) range: TextRange,
+    pub(crate) message: String,
+    pub(crate) fix: Option<Fix>,
+}
+
+/// Checks the `module` and returns every problem found as an LSP [`Diagnostic`]. The problems
+/// suppressed by a comment on their line, e.g. `# noqa`, are left out.
+pub(crate) fn check(
+    module: &ParsedModule,
+    import_resolver: &ImportResolver,
+    encoding: PositionEncoding,
+) -> Vec<Diagnostic> {
+    // Lexical errors are reported by the parser as `ParseErrorType::Lexical`, so both
+    // kinds of syntax error end up in the same list.
+    let mut diagnostics: Vec<_> = module
+        .parse_errors()
+        .iter()
+        .map(|parse_error| syntax_error_to_lsp_diagnostic(parse_error, module, encoding))
+        .collect();
+
+    let suppressions = Suppressions::from_source(module.source(), module.index());
+    diagnostics.extend(
+        imports::check_imports(module, import_resolver)
+            .into_iter()
+            .chain(undefined::check_undefined(module, import_resolver))
+            .chain(unused::check_unused(module))
+            .filter(|diagnostic| !suppressions.is_suppressed(diagnostic, module.index()))
+            .map(|diagnostic| lint_diagnostic_to_lsp_diagnostic(diagnostic, module, encoding)),
+    );
+
+    diagnostics
+}
+
+/// Returns the problems of `module` that can be fixed, along with the LSP [`Diagnostic`]
+/// reporting each of them.
+pub(crate) fn fixable(module: &ParsedModule, encoding: PositionEncoding) -> Vec<(Diagnostic, Fix)> {
+    let suppressions = Suppressions::from_source(module.source(), module.index());
+    unused::check_unused(module)
+        .into_iter()
+        .filter(|diagnostic| !suppressions.is_suppressed(diagnostic, module.index()))
+        .filter_map(|mut diagnostic| {
+            let fix = diagnostic.fix.take()?;
+            Some((
+                lint_diagnostic_to_lsp_diagnostic(diagnostic, module, encoding),
+                fix,
+            ))
+        })
+        .collect()
+}
+
+/// Returns the edits removing every unused import of `module` at once.
+pub(crate) fn fix_all(module: &ParsedModule) -> Vec<Edit> {
+    let suppressions = Suppressions::from_source(module.source(), module.index());
+    let unused_imports: FxHashSet<_> = unused::check_unused(module)
+        .into_iter()
+        .filter(|diagnostic| {
+            diagnostic.rule == Rule::UnusedImport
+                && !suppressions.is_suppressed(diagnostic, module.index())
+        })
+        .map(|diagnostic| diagnostic.range)
+        .collect();
+
+    fix::remove_imports(module, &unused_imports)
+}
+
+fn syntax_error_to_lsp_diagnostic(
+    parse_error: &ParseError,
+    module: &ParsedModule,
+    encoding: PositionEncoding,
+) -> Diagnostic {
+    Diagnostic {
+        range: parse_error
+            .location
+            .to_range(module.source(), module.index(), encoding),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: None,
+        code_description: None,
+        source: Some(crate::DIAGNOSTIC_NAME.into()),
+        message: format!("SyntaxError: {}", parse_error.error),
+        related_information: None,
+        tags: None,
+        data: None,
+    }
+}
+
+fn lint_diagnostic_to_lsp_diagnostic(
+    diagnostic: LintDiagnostic,
+    module: &ParsedModule,
+    encoding: PositionEncoding,
+) -> Diagnostic {
+    Diagnostic {
+        range: diagnostic
+            .range
+            .to_range(module.source(), module.index(), encoding),
+        severity: Some(diagnostic.rule.severity()),
+        code: Some(NumberOrString::String(diagnostic.rule.code().to_string())),
+        code_description: None,
+        source: Some(crate::DIAGNOSTIC_NAME.into()),
+        message: diagnostic.message,
+        related_information: None,
+        tags: diagnostic.rule.tags(),
+        data: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use lsp_types::{Diagnostic, NumberOrString, Position, Range};
+    use ruff_source_file::LineIndex;
+
+    use crate::analysis::ParsedModule;
+    use crate::resolve::ImportResolver;
+    use crate::PositionEncoding;
+
+    use super::check;
+
+    fn diagnostics(root: &Path, source: &str) -> Vec<Diagnostic> {
+        let module = ParsedModule::new(
+            root.join("main.py"),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        );
+        let import_resolver = ImportResolver::new(root.to_path_buf());
+        check(&module, &import_resolver, PositionEncoding::UTF16)
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<(u32, String)> {
+        diagnostics
+            .iter()
+            .filter_map(|diagnostic| match &diagnostic.code {
+                Some(NumberOrString::String(code)) => {
+                    Some((diagnostic.range.start.line, code.clone()))
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid_source_has_no_diagnostics() {
+        let diagnostics = diagnostics(Path::new("/project"), "def foo():\n    return 42\n");
+        assert!(diagnostics.is_empty());
+    }
+
+    #[test]
+    fn syntax_errors_are_reported() {
+        let diagnostics = diagnostics(Path::new("/project"), "x = 1\ndef foo(:\n    pass\n");
+        assert!(!diagnostics.is_empty());
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(1, 8), Position::new(1, 9))
+        );
+        assert!(diagnostics[0].message.starts_with("SyntaxError: "));
+    }
+
+    #[test]
+    fn lexical_errors_are_reported() {
+        let diagnostics = diagnostics(Path::new("/project"), "x = 'unterminated\n");
+        assert!(diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.message.contains("missing closing quote")));
+    }
+
+    #[test]
+    fn unresolved_imports_are_reported() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("pkg")).unwrap();
+        std::fs::write(root.join("pkg/__init__.py"), "").unwrap();
+        std::fs::write(root.join("pkg/util.py"), "").unwrap();
+        std::fs::write(root.join("stubbed.pyi"), "").unwrap();
+
+        let source = "\
+import pkg.util
+import missing
+from pkg import util
+from pkg.missing import name
+import stubbed
+
+def f():
+    import other.missing
+    return other
+
+print(pkg, missing, util, name, stubbed)
+";
+        let diagnostics = diagnostics(root, source);
+        assert_eq!(
+            codes(&diagnostics),
+            vec![
+                (1, "reportMissingImports".to_string()),
+                (3, "reportMissingSubmodule".to_string()),
+                (4, "reportMissingModuleSource".to_string()),
+                (7, "reportMissingImports".to_string()),
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(1, 7), Position::new(1, 14))
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "Import \"missing\" could not be resolved"
+        );
+        assert_eq!(
+            diagnostics[1].message,
+            "Import \"pkg.missing\" could not be resolved, \"pkg\" was found but not its submodule \"missing\""
+        );
+    }
+
+    #[test]
+    fn undefined_names_are_reported() {
+        let diagnostics = diagnostics(Path::new("/project"), "print(value)\nlen(value)  # noqa\n");
+        assert_eq!(
+            codes(&diagnostics),
+            vec![(0, "reportUndefinedVariable".to_string())]
+        );
+        assert_eq!(diagnostics[0].message, "\"value\" is not defined");
+        assert_eq!(
+            diagnostics[0].range,
+            Range::new(Position::new(0, 6), Position::new(0, 11))
+        );
+    }
+
+    #[test]
+    fn suppressed_diagnostics() {
+        let source = "\
+import a  # type: ignore
+import b  # noqa
+import c  # noqa: reportMissingImports
+import d  # noqa: reportMissingModuleSource
+import e  # pyright: ignore[reportMissingImports]
+x = '# noqa'; import f
+print(a, b, c, d, e, f)
+";
+        let diagnostics = diagnostics(&PathBuf::from("/project"), source);
+        assert_eq!(
+            codes(&diagnostics),
+            vec![
+                (3, "reportMissingImports".to_string()),
+                (5, "reportMissingImports".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Edits removing statements and the names imported by a statement.
+
+use python_ast::statement_visitor::{walk_body, StatementVisitor};
+use python_ast::{self as ast, Stmt};
+use ruff_source_file::Locator;
+use ruff_text_size::{Ranged, TextRange, TextSize};
+use rustc_hash::FxHashSet;
+
+use crate::analysis::{Edit, ParsedModule};
+
+/// A change fixing a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Fix {
+    pub(crate) title: String,
+    pub(crate) edits: Vec<Edit>,
+}
+
+/// Returns every block of statements of `module`, starting with the module's body.
+pub(super) fn bodies(module: &ParsedModule) -> Vec<&[Stmt]> {
+    let mut collector = Bodies::default();
+    collector.visit_body(&module.syntax().body);
+    collector.bodies
+}
+
+#[derive(Default)]
+struct Bodies<'a> {
+    bodies: Vec<&'a [Stmt]>,
+}
+
+impl<'a> StatementVisitor<'a> for Bodies<'a> {
+    fn visit_body(&mut self, body: &'a [Stmt]) {
+        self.bodies.push(body);
+        walk_body(self, body);
+    }
+}
+
+/// Returns the edits removing the imported names, i.e. the aliases, at `aliases`.
+///
+/// Statements left without any name are deleted, and a block left without any statement is
+/// replaced by `pass`.
+pub(super) fn remove_imports(module: &ParsedModule, aliases: &FxHashSet<TextRange>) -> Vec<Edit> {
+    let locator = Locator::new(module.source());
+    let mut edits = Vec::new();
+
+    for (index, body) in bodies(module).into_iter().enumerate() {
+        // The ranges of the deleted statements, the statements following each other on the
+        // same line are deleted together, e.g. `import a; import b`.
+        let mut deleted: Vec<TextRange> = Vec::new();
+        let mut deleted_count = 0;
+        let mut previous = None;
+
+        for (position, stmt) in body.iter().enumerate() {
+            let names = match stmt {
+                Stmt::Import(ast::ImportStmt { names, .. })
+                | Stmt::ImportFrom(ast::ImportFromStmt { names, .. }) => names,
+                _ => continue,
+            };
+            let removed: Vec<_> = names
+                .iter()
+                .map(|alias| aliases.contains(&alias.range))
+                .collect();
+
+            if removed.iter().all(|&removed| removed) {
+                match deleted.last_mut() {
+                    Some(last)
+                        if previous == Some(position - 1)
+                            && !locator
+                                .contains_line_break(TextRange::new(last.end(), stmt.start())) =>
+                    {
+                        *last = TextRange::new(last.start(), stmt.end());
+                    }
+                    _ => deleted.push(stmt.range()),
+                }
+                deleted_count += 1;
+                previous = Some(position);
+            } else if removed.iter().any(|&removed| removed) {
+                edits.extend(remove_aliases(names, &removed));
+            }
+        }
+
+        // The module's body is the first one, it can be empty.
+        let is_emptied = index > 0 && deleted_count == body.len();
+        for (position, range) in deleted.into_iter().enumerate() {
+            if is_emptied && position == 0 {
+                edits.push(Edit::replacement("pass".to_string(), range));
+            } else {
+                edits.push(delete_stmt(&locator, range));
+            }
+        }
+    }
+
+    edits
+}
+
+/// Returns the edits removing the `aliases` of an import statement marked as `removed`, at
+/// least one of them is kept. Each alias is removed along with the comma separating it from
+/// the previous alias, or the next alias if no previous alias is kept.
+fn remove_aliases(aliases: &[ast::Alias], removed: &[bool]) -> Vec<Edit> {
+    let mut edits = Vec::new();
+    let mut has_kept = false;
+
+    for (index, alias) in aliases.iter().enumerate() {
+        if !removed[index] {
+            has_kept = true;
+            continue;
+        }
+
+        let range = if has_kept {
+            TextRange::new(aliases[index - 1].end(), alias.end())
+        } else {
+            TextRange::new(alias.start(), aliases[index + 1].start())
+        };
+        edits.push(Edit::deletion(range));
+    }
+
+    edits
+}
+
+/// Returns the edit deleting the statement at `range`, including its line if the statement
+/// is the only one on it, or the semicolon separating it from the statements on the same
+/// line.
+pub(super) fn delete_stmt(locator: &Locator, range: TextRange) -> Edit {
+    let is_blank = |c: char| c == ' ' || c == '\t';
+
+    // `import os; x = 1`
+    let after = locator.after(range.end());
+    let trailing = after.trim_start_matches(is_blank);
+    if let Some(rest) = trailing.strip_prefix(';') {
+        let end = locator.len() - rest.trim_start_matches(is_blank).len();
+        return Edit::deletion(TextRange::new(
+            range.start(),
+            TextSize::try_from(end).unwrap(),
+        ));
+    }
+
+    let line_start = locator.line_start(range.start());
+    if locator
+        .slice(TextRange::new(line_start, range.start()))
+        .chars()
+        .all(is_blank)
+    {
+        return Edit::deletion(TextRange::new(
+            line_start,
+            locator.full_line_end(range.end()),
+        ));
+    }
+
+    // `x = 1; import os`
+    let before = locator.up_to(range.start()).trim_end_matches(is_blank);
+    let start = match before.strip_suffix(';') {
+        Some(before) => TextSize::try_from(before.len()).unwrap(),
+        None => range.start(),
+    };
+    Edit::deletion(TextRange::new(start, range.end()))
+}
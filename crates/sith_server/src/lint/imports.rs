@@ -0,0 +1,135 @@
+//! Diagnostics of the imports that can't be resolved.
+
+use python_ast::statement_visitor::{walk_stmt, StatementVisitor};
+use python_ast::{self as ast, Stmt};
+use ruff_python_resolver::import_result::{ImportResult, ImportType};
+use ruff_text_size::{Ranged, TextRange};
+
+use crate::analysis::ParsedModule;
+use crate::resolve::ImportResolver;
+
+use super::{LintDiagnostic, Rule};
+
+/// Resolves every module imported by `module`, including the imports nested in functions and
+/// classes, and reports the ones that can't be resolved.
+pub(super) fn check_imports(
+    module: &ParsedModule,
+    import_resolver: &ImportResolver,
+) -> Vec<LintDiagnostic> {
+    let mut collector = ImportCollector::default();
+    collector.visit_body(&module.syntax().body);
+
+    collector
+        .imports
+        .into_iter()
+        .filter_map(|import| {
+            let result = import_resolver.resolve(module.path(), import.level, import.name);
+            let issue = ImportIssue::of(&result)?;
+            Some(LintDiagnostic {
+                rule: issue.rule(),
+                range: import.range,
+                message: issue.message(&import, &result),
+                fix: None,
+            })
+        })
+        .collect()
+}
+
+/// A module imported with `level` leading dots.
+struct Import<'a> {
+    name: &'a str,
+    level: u32,
+    range: TextRange,
+}
+
+#[derive(Default)]
+struct ImportCollector<'a> {
+    imports: Vec<Import<'a>>,
+}
+
+impl<'a> StatementVisitor<'a> for ImportCollector<'a> {
+    fn visit_stmt(&mut self, stmt: &'a Stmt) {
+        match stmt {
+            Stmt::Import(ast::ImportStmt { names, .. }) => {
+                self.imports.extend(names.iter().map(|alias| Import {
+                    name: alias.name.as_str(),
+                    level: 0,
+                    range: alias.name.range(),
+                }));
+            }
+            // The names imported from a module are attributes of the module, only the module is
+            // resolved. `from . import name` has no module to report.
+            Stmt::ImportFrom(ast::ImportFromStmt {
+                module: Some(module),
+                level,
+                ..
+            }) => self.imports.push(Import {
+                name: module.as_str(),
+                level: *level,
+                range: module.range(),
+            }),
+            _ => walk_stmt(self, stmt),
+        }
+    }
+}
+
+/// Why an import is reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ImportIssue {
+    Unresolved,
+    PartlyResolved,
+    MissingSource,
+}
+
+impl ImportIssue {
+    /// Returns the issue of an import resolved to `result`, if any.
+    fn of(result: &ImportResult) -> Option<Self> {
+        if !result.is_import_found {
+            return Some(if result.is_partly_resolved {
+                Self::PartlyResolved
+            } else {
+                Self::Unresolved
+            });
+        }
+
+        // The standard library stubs of typeshed never have a source, it's built into the
+        // interpreter.
+        let is_missing_source = result.is_stub_file
+            && !result.is_stdlib_typeshed_file
+            && result.import_type != ImportType::BuiltIn
+            && !result
+                .non_stub_import_result
+                .as_ref()
+                .is_some_and(|non_stub| non_stub.is_import_found);
+        is_missing_source.then_some(Self::MissingSource)
+    }
+
+    const fn rule(self) -> Rule {
+        match self {
+            Self::Unresolved => Rule::UnresolvedImport,
+            Self::PartlyResolved => Rule::PartlyResolvedImport,
+            Self::MissingSource => Rule::MissingModuleSource,
+        }
+    }
+
+    fn message(self, import: &Import, result: &ImportResult) -> String {
+        let name = format!("{}{}", ".".repeat(import.level as usize), import.name);
+        match self {
+            Self::Unresolved => format!("Import \"{name}\" could not be resolved"),
+            Self::PartlyResolved => {
+                // A part of the module was resolved for each file found.
+                let found = result.resolved_paths.len();
+                let parts: Vec<_> = import.name.split('.').collect();
+                let package = parts[..found.min(parts.len())].join(".");
+                let submodule = parts[found.min(parts.len())..].join(".");
+                format!(
+                    "Import \"{name}\" could not be resolved, \"{package}\" was found but not \
+                     its submodule \"{submodule}\""
+                )
+            }
+            Self::MissingSource => format!(
+                "Import \"{name}\" could not be resolved from source, only its stub was found"
+            ),
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Comments suppressing the diagnostics of their line, e.g. `# type: ignore` or
+//! `# noqa: reportMissingImports`.
+
+use python_parser::lexer::lex;
+use python_parser::{Mode, TokenKind};
+use ruff_source_file::{LineIndex, OneIndexed};
+use rustc_hash::FxHashMap;
+
+use super::LintDiagnostic;
+
+/// The diagnostics suppressed by the comment of a line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Suppression {
+    /// Every diagnostic of the line, e.g. `# type: ignore` or `# noqa`.
+    All,
+    /// The diagnostics with one of the codes, e.g. `# noqa: reportMissingImports`.
+    Codes(Vec<String>),
+}
+
+impl Suppression {
+    fn suppresses(&self, code: &str) -> bool {
+        match self {
+            Suppression::All => true,
+            Suppression::Codes(codes) => codes.iter().any(|suppressed| suppressed == code),
+        }
+    }
+}
+
+/// The suppression comments of a module, by line.
+#[derive(Debug, Default)]
+pub(super) struct Suppressions {
+    lines: FxHashMap<OneIndexed, Vec<Suppression>>,
+}
+
+impl Suppressions {
+    pub(super) fn from_source(source: &str, index: &LineIndex) -> Self {
+        let mut lines: FxHashMap<OneIndexed, Vec<Suppression>> = FxHashMap::default();
+
+        let mut lexer = lex(source, Mode::Module);
+        loop {
+            match lexer.next_token() {
+                TokenKind::EndOfFile => break,
+                TokenKind::Comment => {
+                    let range = lexer.current_range();
+                    let suppressions = parse_comment(&source[range]);
+                    if !suppressions.is_empty() {
+                        lines
+                            .entry(index.line_index(range.start()))
+                            .or_default()
+                            .extend(suppressions);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self { lines }
+    }
+
+    /// Returns `true` if a comment on the line where `diagnostic` starts suppresses it.
+    pub(super) fn is_suppressed(&self, diagnostic: &LintDiagnostic, index: &LineIndex) -> bool {
+        self.lines
+            .get(&index.line_index(diagnostic.range.start()))
+            .is_some_and(|suppressions| {
+                suppressions
+                    .iter()
+                    .any(|suppression| suppression.suppresses(diagnostic.rule.code()))
+            })
+    }
+}
+
+/// Returns the suppressions of a comment, which can hold several directives, e.g.
+/// `# noqa  # type: ignore`.
+fn parse_comment(comment: &str) -> Vec<Suppression> {
+    comment
+        .split('#')
+        .map(str::trim)
+        .filter_map(|directive| {
+            if let Some(rest) = strip_directive(directive, "type:", "ignore") {
+                // The codes of `# type: ignore[...]` are mypy's, it's always a blanket ignore.
+                return (rest.is_empty() || rest.starts_with('[')).then_some(Suppression::All);
+            }
+            if let Some(rest) = strip_directive(directive, "pyright:", "ignore") {
+                return match rest.strip_prefix('[') {
+                    Some(codes) => Some(Suppression::Codes(parse_codes(
+                        codes.split(']').next().unwrap_or_default(),
+                    ))),
+                    None => rest.is_empty().then_some(Suppression::All),
+                };
+            }
+            let rest = directive.strip_prefix("noqa")?.trim_start();
+            if rest.is_empty() {
+                return Some(Suppression::All);
+            }
+            rest.strip_prefix(':')
+                .map(|codes| Suppression::Codes(parse_codes(codes)))
+        })
+        .collect()
+}
+
+/// Strips a `prefix: value` directive from `comment`, allowing any spacing after the prefix,
+/// and returns the rest of the comment.
+fn strip_directive<'a>(comment: &'a str, prefix: &str, value: &str) -> Option<&'a str> {
+    comment
+        .strip_prefix(prefix)?
+        .trim_start()
+        .strip_prefix(value)
+        .map(str::trim)
+}
+
+fn parse_codes(codes: &str) -> Vec<String> {
+    codes
+        .split([',', ' '])
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_comment, Suppression};
+
+    #[test]
+    fn suppression_comments() {
+        assert_eq!(parse_comment("# type: ignore"), vec![Suppression::All]);
+        assert_eq!(
+            parse_comment("# type: ignore[import-untyped]"),
+            vec![Suppression::All]
+        );
+        assert_eq!(parse_comment("#noqa"), vec![Suppression::All]);
+        assert_eq!(
+            parse_comment("# noqa: reportMissingImports, reportMissingModuleSource"),
+            vec![Suppression::Codes(vec![
+                "reportMissingImports".to_string(),
+                "reportMissingModuleSource".to_string()
+            ])]
+        );
+        assert_eq!(
+            parse_comment("# fmt: skip  # pyright: ignore[reportMissingImports]"),
+            vec![Suppression::Codes(vec!["reportMissingImports".to_string()])]
+        );
+        assert_eq!(parse_comment("# type: ignored"), vec![]);
+        assert_eq!(parse_comment("# noqa is mentioned here"), vec![]);
+    }
+}
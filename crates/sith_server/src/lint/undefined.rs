@@ -0,0 +1,24 @@
+//! Diagnostics of the names used without being defined.
+
+use crate::analysis::{undefined_names, DefinitionResolver, ParsedModule};
+use crate::resolve::ImportResolver;
+
+use super::{LintDiagnostic, Rule};
+
+/// Reports the names loaded by `module` that aren't defined in scope, builtins or imported by
+/// a wildcard import.
+pub(super) fn check_undefined(
+    module: &ParsedModule,
+    import_resolver: &ImportResolver,
+) -> Vec<LintDiagnostic> {
+    let mut resolver = DefinitionResolver::new(import_resolver);
+    undefined_names(module, &mut resolver)
+        .into_iter()
+        .map(|(name, range)| LintDiagnostic {
+            rule: Rule::UndefinedVariable,
+            range,
+            message: format!("\"{name}\" is not defined"),
+            fix: None,
+        })
+        .collect()
+}
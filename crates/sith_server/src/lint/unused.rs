@@ -0,0 +1,432 @@
+//! Diagnostics of the imports and local variables that are never used.
+
+use python_ast::visitor::preorder::{PreorderVisitor, TraversalSignal};
+use python_ast::{self as ast, AnyNodeRef, ContextExpr, Expr, Stmt};
+use ruff_source_file::Locator;
+use ruff_text_size::{Ranged, TextRange};
+use rustc_hash::{FxHashMap, FxHashSet};
+use semantic_model::{BindingKind, ScopeId, ScopeKind};
+
+use crate::analysis::{Edit, ParsedModule};
+
+use super::fix::{bodies, delete_stmt, remove_imports, Fix};
+use super::{LintDiagnostic, Rule};
+
+/// Reports the imports whose names are never read, and the variables assigned in a function
+/// that are never read.
+pub(super) fn check_unused(module: &ParsedModule) -> Vec<LintDiagnostic> {
+    let model = module.model();
+    let used = used_names(module);
+    let is_used = |scope: ScopeId, name: &str| used.contains(&(scope, name));
+
+    let file_name = module
+        .path()
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let is_stub = file_name.ends_with(".pyi");
+    let is_package = matches!(file_name, "__init__.py" | "__init__.pyi");
+
+    let bodies = bodies(module);
+    let mut diagnostics = Vec::new();
+
+    // Imports are bound to the range of their alias.
+    let imports: FxHashMap<_, _> = model
+        .bindings()
+        .filter(|(_, binding)| binding.is_import())
+        .map(|(_, binding)| (binding.definition_range, binding))
+        .collect();
+    for stmt in bodies.iter().flat_map(|body| body.iter()) {
+        let (names, is_future) = match stmt {
+            Stmt::Import(ast::ImportStmt { names, .. }) => (names, false),
+            Stmt::ImportFrom(ast::ImportFromStmt { module, names, .. }) => (
+                names,
+                module.as_ref().is_some_and(|module| module == "__future__"),
+            ),
+            _ => continue,
+        };
+        if is_future {
+            continue;
+        }
+
+        for alias in names {
+            let Some(binding) = imports.get(&alias.range) else {
+                continue;
+            };
+            let scope = model.scope(binding.scope);
+            if !matches!(scope.kind, ScopeKind::Module | ScopeKind::Function)
+                || is_used(binding.scope, &binding.name)
+            {
+                continue;
+            }
+            // The names imported by a package's `__init__.py` are usually re-exported, and so
+            // are the names imported with a redundant alias in a stub, e.g. `import x as x`.
+            let is_reexport = (is_package && binding.scope == ScopeId::global())
+                || (is_stub
+                    && alias
+                        .asname
+                        .as_ref()
+                        .is_some_and(|asname| asname.as_str() == alias.name.as_str()));
+            if is_reexport {
+                continue;
+            }
+
+            diagnostics.push(LintDiagnostic {
+                rule: Rule::UnusedImport,
+                range: alias.range,
+                message: format!("Import \"{}\" is not accessed", binding.name),
+                fix: Some(Fix {
+                    title: format!("Remove unused import `{}`", binding.name),
+                    edits: remove_imports(module, &FxHashSet::from_iter([alias.range])),
+                }),
+            });
+        }
+    }
+
+    let locator = Locator::new(module.source());
+    for (_, binding) in model.bindings() {
+        let is_local = model.scope(binding.scope).kind == ScopeKind::Function;
+        let is_assignment = matches!(
+            binding.kind,
+            BindingKind::Assignment
+                | BindingKind::AnnotatedAssignment
+                | BindingKind::NamedExprAssignment
+        );
+        if !is_local
+            || !is_assignment
+            || binding.name.starts_with('_')
+            || is_used(binding.scope, &binding.name)
+        {
+            continue;
+        }
+
+        let stmt = bodies.iter().find_map(|body| {
+            let stmt = body
+                .iter()
+                .find(|stmt| stmt.range() == binding.definition_range)?;
+            Some((stmt, *body))
+        });
+
+        diagnostics.push(LintDiagnostic {
+            rule: Rule::UnusedVariable,
+            range: binding.range,
+            message: format!("Variable \"{}\" is not accessed", binding.name),
+            fix: stmt.and_then(|(stmt, body)| {
+                let edit = remove_assignment(&locator, stmt, body)?;
+                Some(Fix {
+                    title: format!("Remove assignment to unused variable `{}`", binding.name),
+                    edits: vec![edit],
+                })
+            }),
+        });
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.range.start());
+    diagnostics
+}
+
+/// Returns the edit removing the assignment of a single variable in `stmt`, keeping its value
+/// if evaluating it may have side effects, e.g. `x = f()` becomes `f()`.
+fn remove_assignment(locator: &Locator, stmt: &Stmt, body: &[Stmt]) -> Option<Edit> {
+    let (target, value) = match stmt {
+        Stmt::Assign(ast::AssignStmt { targets, value, .. }) if targets.len() == 1 => {
+            (&targets[0], value)
+        }
+        Stmt::AnnAssign(ast::AnnAssignStmt {
+            target,
+            value: Some(value),
+            ..
+        }) => (target.as_ref(), value),
+        _ => return None,
+    };
+    if !target.is_name_expr() {
+        return None;
+    }
+
+    if has_side_effects(value) {
+        Some(Edit::deletion(TextRange::new(
+            target.start(),
+            value.start(),
+        )))
+    } else if body.len() == 1 {
+        Some(Edit::replacement("pass".to_string(), stmt.range()))
+    } else {
+        Some(delete_stmt(locator, stmt.range()))
+    }
+}
+
+/// Returns `true` if `expr` calls a function or suspends, which may have side effects.
+fn has_side_effects(expr: &Expr) -> bool {
+    struct SideEffects(bool);
+
+    impl<'a> PreorderVisitor<'a> for SideEffects {
+        fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+            if matches!(
+                node,
+                AnyNodeRef::CallExpr(_)
+                    | AnyNodeRef::AwaitExpr(_)
+                    | AnyNodeRef::YieldExpr(_)
+                    | AnyNodeRef::YieldFromExpr(_)
+                    | AnyNodeRef::NamedExpr(_)
+            ) {
+                self.0 = true;
+            }
+            if self.0 {
+                TraversalSignal::Skip
+            } else {
+                TraversalSignal::Traverse
+            }
+        }
+    }
+
+    let mut visitor = SideEffects(false);
+    visitor.visit_expr(expr);
+    visitor.0
+}
+
+/// Returns the names read in `module`, along with the scope of the binding they resolve to.
+fn used_names(module: &ParsedModule) -> FxHashSet<(ScopeId, &str)> {
+    let mut collector = Uses::default();
+    collector.visit_body(&module.syntax().body);
+
+    let model = module.model();
+    let mut used: FxHashSet<_> = collector
+        .names
+        .iter()
+        .filter_map(|(name, range)| {
+            let binding = model.lookup(name, model.scope_at(range.start()), range.start())?;
+            let binding = model.binding(binding);
+            Some((binding.scope, binding.name.as_str()))
+        })
+        .collect();
+
+    // The names exported by `__all__` are used by the modules importing them.
+    used.extend(module.dunder_all().into_iter().filter_map(|range| {
+        let name = &module.source()[range];
+        let binding = model.global_scope().get(name)?;
+        Some((ScopeId::global(), model.binding(binding).name.as_str()))
+    }));
+
+    used
+}
+
+/// Collects the names read by a module, including the names deleted or updated by an
+/// augmented assignment, and the names in string annotations.
+#[derive(Default)]
+struct Uses {
+    names: Vec<(String, TextRange)>,
+}
+
+impl Uses {
+    /// Collects the names of the forward references in `annotation`, e.g. `Foo` in
+    /// `x: "list[Foo]"`. They are all located at the string.
+    fn visit_annotation(&mut self, annotation: &Expr) {
+        #[derive(Default)]
+        struct Strings<'a>(Vec<&'a ast::StringLiteralExpr>);
+
+        impl<'a> PreorderVisitor<'a> for Strings<'a> {
+            fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+                if let AnyNodeRef::StringLiteralExpr(string) = node {
+                    self.0.push(string);
+                }
+                TraversalSignal::Traverse
+            }
+        }
+
+        let mut strings = Strings::default();
+        strings.visit_expr(annotation);
+        for string in strings.0 {
+            let Ok(parsed) = python_parser::parse_expression(string.value.to_str()) else {
+                continue;
+            };
+            let mut names = Uses::default();
+            names.visit_expr(parsed.expr());
+            self.names.extend(
+                names
+                    .names
+                    .into_iter()
+                    .map(|(name, _)| (name, string.range)),
+            );
+        }
+    }
+}
+
+impl<'a> PreorderVisitor<'a> for Uses {
+    fn enter_node(&mut self, node: AnyNodeRef<'a>) -> TraversalSignal {
+        match node {
+            AnyNodeRef::NameExpr(ast::NameExpr {
+                id,
+                range,
+                ctx: ContextExpr::Load | ContextExpr::Del,
+            }) => self.names.push((id.to_string(), *range)),
+            AnyNodeRef::StmtAugAssign(ast::AugAssignStmt { target, .. }) => {
+                if let Expr::Name(ast::NameExpr { id, range, .. }) = target.as_ref() {
+                    self.names.push((id.to_string(), *range));
+                }
+            }
+            AnyNodeRef::StmtAnnAssign(ast::AnnAssignStmt { annotation, .. }) => {
+                self.visit_annotation(annotation);
+            }
+            AnyNodeRef::Parameter(ast::Parameter {
+                annotation: Some(annotation),
+                ..
+            }) => self.visit_annotation(annotation),
+            AnyNodeRef::StmtFunctionDef(ast::FunctionDefStmt {
+                returns: Some(returns),
+                ..
+            }) => self.visit_annotation(returns),
+            _ => {}
+        }
+
+        TraversalSignal::Traverse
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ruff_source_file::LineIndex;
+
+    use crate::analysis::{Edit, ParsedModule};
+    use crate::lint::{fix_all, Rule};
+
+    use super::check_unused;
+
+    fn parse(path: &str, source: &str) -> ParsedModule {
+        ParsedModule::new(
+            PathBuf::from(path),
+            source.to_string(),
+            LineIndex::from_source_text(source),
+        )
+    }
+
+    fn unused(path: &str, source: &str) -> Vec<(Rule, String)> {
+        let module = parse(path, source);
+        check_unused(&module)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.rule, source[diagnostic.range].to_string()))
+            .collect()
+    }
+
+    fn apply(source: &str, mut edits: Vec<Edit>) -> String {
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start()));
+        let mut result = source.to_string();
+        for edit in edits {
+            result.replace_range(std::ops::Range::<usize>::from(edit.range), &edit.content);
+        }
+        result
+    }
+
+    #[test]
+    fn unused_names_are_reported() {
+        let source = r#"
+from __future__ import annotations
+import os, sys
+import os.path
+from typing import TYPE_CHECKING, Any, cast as typed
+from collections import OrderedDict
+
+__all__ = ["OrderedDict"]
+
+if TYPE_CHECKING:
+    from pathlib import Path
+
+def f(x: "Path") -> "list[Any]":
+    import json
+    count = 0
+    total = 0
+    for item in x:
+        count += 1
+    _ignored = 1
+    first, second = x
+    value = len(x)
+    del second
+
+    def inner():
+        return total
+
+    return inner, os.sep
+"#;
+        assert_eq!(
+            unused("main.py", source),
+            vec![
+                (Rule::UnusedImport, "sys".to_string()),
+                (Rule::UnusedImport, "cast as typed".to_string()),
+                (Rule::UnusedImport, "json".to_string()),
+                (Rule::UnusedVariable, "first".to_string()),
+                (Rule::UnusedVariable, "value".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reexports_are_not_reported() {
+        let source = "import os\nimport sys as sys\nfrom typing import Any as Any\n";
+        assert!(unused("pkg/__init__.py", source).is_empty());
+        assert_eq!(
+            unused("main.pyi", source),
+            vec![(Rule::UnusedImport, "os".to_string())]
+        );
+    }
+
+    #[test]
+    fn unused_names_are_fixed() {
+        let source = "\
+import os
+from typing import Any, List, Dict
+import sys; import re
+
+def f():
+    if True:
+        import json
+    x = 1
+    y = g()
+    return List
+
+def g():
+    z: int = 1
+";
+        let module = parse("main.py", source);
+        let fixed: Vec<_> = check_unused(&module)
+            .into_iter()
+            .map(|diagnostic| {
+                let fix = diagnostic.fix.unwrap();
+                (fix.title, apply(source, fix.edits))
+            })
+            .collect();
+
+        assert_eq!(fixed[0].0, "Remove unused import `os`");
+        assert!(fixed[0]
+            .1
+            .starts_with("from typing import Any, List, Dict\n"));
+        assert!(fixed[1].1.contains("from typing import List, Dict\n"));
+        assert!(fixed[2].1.contains("from typing import Any, List\n"));
+        assert!(fixed[3].1.contains("\nimport re\n"));
+        assert!(fixed[4].1.contains("import sys\n"));
+        assert!(fixed[5].1.contains("    if True:\n        pass\n"));
+        assert_eq!(fixed[6].0, "Remove assignment to unused variable `x`");
+        assert!(fixed[6]
+            .1
+            .contains("    if True:\n        import json\n    y = g()\n"));
+        assert!(fixed[7].1.contains("    x = 1\n    g()\n"));
+        assert!(fixed[8].1.ends_with("def g():\n    pass\n"));
+
+        assert_eq!(
+            apply(source, fix_all(&module)),
+            "\
+from typing import List
+
+def f():
+    if True:
+        pass
+    x = 1
+    y = g()
+    return List
+
+def g():
+    z: int = 1
+"
+        );
+    }
+}
@@ -0,0 +1,302 @@
+//! Resolution of Python imports to the files defining them.
+
+mod project;
+
+use std::path::{Path, PathBuf};
+
+use python_utils::{current_platform, PythonHost};
+use ruff_python_resolver::config::Config;
+use ruff_python_resolver::execution_environment::ExecutionEnvironment;
+use ruff_python_resolver::host::Host;
+use ruff_python_resolver::implicit_imports::{ImplicitImport, ImplicitImports};
+use ruff_python_resolver::import_result::ImportResult;
+use ruff_python_resolver::module_descriptor::ImportModuleDescriptor;
+use ruff_python_resolver::resolver::resolve_import;
+use ruff_python_resolver::search::{python_search_paths, stdlib_typeshed_path};
+
+use self::project::{parse_python_platform, parse_python_version};
+pub(crate) use self::project::{ProjectOptions, CONFIGURATION_FILES};
+
+/// The directory of custom stubs of a project that doesn't configure one, as in Pyright.
+const DEFAULT_STUB_PATH: &str = "typings";
+
+/// Resolves the imports of the files in a workspace.
+#[derive(Debug)]
+pub(crate) struct ImportResolver {
+    root: PathBuf,
+    /// The environment of the files outside of the configured execution environments.
+    default_environment: ExecutionEnvironment,
+    /// The environments of subdirectories of the workspace, the first one containing a file
+    /// applies to it.
+    execution_environments: Vec<ExecutionEnvironment>,
+    config: Config,
+    host: PythonHost,
+}
+
+impl ImportResolver {
+    /// Creates a resolver for the workspace at `root`, without a Python environment to
+    /// resolve third-party imports from.
+    pub(crate) fn new(root: PathBuf) -> Self {
+        let host = PythonHost::default();
+        Self {
+            default_environment: ExecutionEnvironment {
+                root: root.clone(),
+                python_version: host.python_version(),
+                python_platform: current_platform(),
+                extra_paths: Vec::new(),
+            },
+            execution_environments: Vec::new(),
+            root,
+            config: Config {
+                typeshed_path: None,
+                stub_path: None,
+                venv_path: None,
+                venv: None,
+            },
+            host,
+        }
+    }
+
+    /// Resolves third-party imports from the Python environment of `host`.
+    pub(crate) fn with_host(mut self, host: PythonHost) -> Self {
+        self.default_environment.python_version = host.python_version();
+        self.host = host;
+        self
+    }
+
+    /// Applies the options of the project's configuration file. The options take precedence
+    /// over the Python version of the host.
+    pub(crate) fn with_project(mut self, options: &ProjectOptions) -> Self {
+        let root = &self.root;
+        self.config = Config {
+            typeshed_path: options.typeshed_path.as_ref().map(|path| root.join(path)),
+            stub_path: Some(
+                root.join(
+                    options
+                        .stub_path
+                        .as_deref()
+                        .unwrap_or(Path::new(DEFAULT_STUB_PATH)),
+                ),
+            ),
+            venv_path: options.venv_path.as_ref().map(|path| root.join(path)),
+            venv: options.venv.clone(),
+        };
+
+        let default = &mut self.default_environment;
+        default.extra_paths = options
+            .extra_paths
+            .iter()
+            .map(|path| root.join(path))
+            .collect();
+        if let Some(version) = options
+            .python_version
+            .as_deref()
+            .and_then(parse_python_version)
+        {
+            default.python_version = version;
+        }
+        if let Some(platform) = options
+            .python_platform
+            .as_deref()
+            .and_then(parse_python_platform)
+        {
+            default.python_platform = platform;
+        }
+
+        self.execution_environments = options
+            .execution_environments
+            .iter()
+            .map(|environment| ExecutionEnvironment {
+                root: root.join(&environment.root),
+                python_version: environment
+                    .python_version
+                    .as_deref()
+                    .and_then(parse_python_version)
+                    .unwrap_or(default.python_version),
+                python_platform: environment
+                    .python_platform
+                    .as_deref()
+                    .and_then(parse_python_platform)
+                    .unwrap_or(default.python_platform),
+                extra_paths: environment.extra_paths.as_ref().map_or_else(
+                    || default.extra_paths.clone(),
+                    |paths| paths.iter().map(|path| root.join(path)).collect(),
+                ),
+            })
+            .collect();
+        self
+    }
+
+    /// Adds directories to resolve top-level modules from, searched after the extra paths of
+    /// the project.
+    pub(crate) fn with_extra_paths(mut self, extra_paths: Vec<PathBuf>) -> Self {
+        for environment in
+            std::iter::once(&mut self.default_environment).chain(&mut self.execution_environments)
+        {
+            environment.extra_paths.extend(extra_paths.iter().cloned());
+        }
+        self
+    }
+
+    /// The root of the workspace the imports are resolved from.
+    pub(crate) fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Returns the execution environment of the file at `path`.
+    fn execution_environment(&self, path: &Path) -> &ExecutionEnvironment {
+        self.execution_environments
+            .iter()
+            .find(|environment| path.starts_with(&environment.root))
+            .unwrap_or(&self.default_environment)
+    }
+
+    /// Returns the directories top-level modules are imported from by the file at
+    /// `source_file`, in the order they're searched.
+    pub(crate) fn search_roots(&self, source_file: &Path) -> Vec<PathBuf> {
+        let environment = self.execution_environment(source_file);
+        let mut roots = vec![environment.root.clone()];
+        roots.extend(environment.extra_paths.iter().cloned());
+        roots.extend(stdlib_typeshed_path(&self.config, &self.host));
+        roots.extend(python_search_paths(&self.config, &self.host));
+        roots
+    }
+
+    /// Returns the directories third-party packages are installed in, e.g. `site-packages`.
+    pub(crate) fn third_party_roots(&self) -> Vec<PathBuf> {
+        python_search_paths(&self.config, &self.host)
+    }
+
+    /// Returns the directories of the package `module` imported with `level` leading dots from
+    /// `source_file`. A namespace package can span multiple directories.
+    pub(crate) fn package_directories(
+        &self,
+        source_file: &Path,
+        level: u32,
+        module: &str,
+    ) -> Vec<PathBuf> {
+        let bases = if level > 0 {
+            let mut base = source_file.parent();
+            for _ in 1..level {
+                base = base.and_then(Path::parent);
+            }
+            base.map(Path::to_path_buf).into_iter().collect()
+        } else {
+            self.search_roots(source_file)
+        };
+
+        bases
+            .into_iter()
+            .map(|base| {
+                module
+                    .split('.')
+                    .filter(|part| !part.is_empty())
+                    .fold(base, |path, part| path.join(part))
+            })
+            .filter(|path| path.is_dir())
+            .collect()
+    }
+
+    /// Resolves the module `module` imported with `level` leading dots from `source_file`.
+    pub(crate) fn resolve(&self, source_file: &Path, level: u32, module: &str) -> ImportResult {
+        let descriptor = ImportModuleDescriptor {
+            leading_dots: level as usize,
+            name_parts: module
+                .split('.')
+                .filter(|part| !part.is_empty())
+                .map(ToString::to_string)
+                .collect(),
+            imported_symbols: Vec::new(),
+        };
+
+        resolve_import(
+            source_file,
+            self.execution_environment(source_file),
+            &descriptor,
+            &self.config,
+            &self.host,
+        )
+    }
+}
+
+/// Returns the modules and packages in `directory`, e.g. the submodules of a package.
+pub(crate) fn modules_in(directory: &Path) -> Vec<(String, ImplicitImport)> {
+    let Ok(implicit_imports) = ImplicitImports::find(directory, &[]) else {
+        return Vec::new();
+    };
+
+    implicit_imports
+        .into_iter()
+        .filter(|(name, _)| name != "__init__" && python_parser::lexer::is_identifier(name))
+        .collect()
+}
+
+/// Returns the files defining a resolved module, in order of preference.
+///
+/// The source file is preferred over its stub when both are known. A stub is still returned
+/// as a fallback, because the definition may only exist in the stub.
+pub(crate) fn module_files(import_result: &ImportResult) -> Vec<PathBuf> {
+    if !import_result.is_import_found {
+        return Vec::new();
+    }
+
+    let mut files = Vec::new();
+
+    if import_result.is_stub_file {
+        if let Some(non_stub) = import_result
+            .non_stub_import_result
+            .as_deref()
+            .filter(|non_stub| non_stub.is_import_found)
+        {
+            files.extend(resolved_file(non_stub));
+        }
+    }
+    files.extend(resolved_file(import_result));
+
+    files
+}
+
+/// Returns the file defining the last segment of a resolved module. Namespace packages are
+/// represented by empty paths and don't have such a file.
+pub(crate) fn resolved_file(import_result: &ImportResult) -> Option<PathBuf> {
+    import_result
+        .resolved_paths
+        .last()
+        .filter(|path| !path.as_os_str().is_empty())
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{resolved_file, ImportResolver, ProjectOptions};
+
+    #[test]
+    fn stub_path() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join("typings")).unwrap();
+        std::fs::create_dir_all(root.join("stubs")).unwrap();
+        std::fs::write(root.join("typings/lib.pyi"), "").unwrap();
+        std::fs::write(root.join("stubs/lib.pyi"), "").unwrap();
+        let main = root.join("main.py");
+
+        let resolver =
+            ImportResolver::new(root.to_path_buf()).with_project(&ProjectOptions::default());
+        assert_eq!(
+            resolved_file(&resolver.resolve(&main, 0, "lib")),
+            Some(root.join("typings/lib.pyi"))
+        );
+
+        let options = ProjectOptions {
+            stub_path: Some(PathBuf::from("stubs")),
+            ..ProjectOptions::default()
+        };
+        let resolver = ImportResolver::new(root.to_path_buf()).with_project(&options);
+        assert_eq!(
+            resolved_file(&resolver.resolve(&main, 0, "lib")),
+            Some(root.join("stubs/lib.pyi"))
+        );
+    }
+}
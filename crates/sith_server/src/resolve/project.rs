@@ -0,0 +1,167 @@
+//! The resolver options of a project, read from its `pyproject.toml` or `pyrightconfig.json`.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use ruff_python_resolver::python_platform::PythonPlatform;
+use ruff_python_resolver::python_version::PythonVersion;
+use serde::Deserialize;
+
+const PYPROJECT_TOML: &str = "pyproject.toml";
+const PYRIGHTCONFIG_JSON: &str = "pyrightconfig.json";
+
+/// The files the project options are read from.
+pub(crate) const CONFIGURATION_FILES: [&str; 2] = [PYPROJECT_TOML, PYRIGHTCONFIG_JSON];
+
+/// The `[tool.sith]` section of a `pyproject.toml` file, which shares the names of Pyright's
+/// options. Paths are relative to the project root.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct ProjectOptions {
+    /// Additional directories to resolve imports from.
+    pub(super) extra_paths: Vec<PathBuf>,
+    /// The directory of custom stubs, `typings` by default.
+    pub(super) stub_path: Option<PathBuf>,
+    pub(super) typeshed_path: Option<PathBuf>,
+    /// The directory containing the virtual environment `venv`.
+    pub(super) venv_path: Option<PathBuf>,
+    pub(super) venv: Option<PathBuf>,
+    pub(super) python_version: Option<String>,
+    pub(super) python_platform: Option<String>,
+    /// The environments of subdirectories of the project, the first one containing a file
+    /// applies to it.
+    pub(super) execution_environments: Vec<ExecutionEnvironmentOptions>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct ExecutionEnvironmentOptions {
+    pub(super) root: PathBuf,
+    /// Replaces the project's extra paths if set.
+    pub(super) extra_paths: Option<Vec<PathBuf>>,
+    pub(super) python_version: Option<String>,
+    pub(super) python_platform: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct PyProject {
+    tool: Option<Tools>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Tools {
+    sith: Option<ProjectOptions>,
+    pyright: Option<ProjectOptions>,
+}
+
+impl ProjectOptions {
+    /// Reads the options of the project at `root`. The `[tool.sith]` section of its
+    /// `pyproject.toml` takes precedence over `pyrightconfig.json`, followed by the
+    /// `[tool.pyright]` section.
+    pub(crate) fn load(root: &Path) -> crate::Result<Option<Self>> {
+        let tools = read_pyproject(&root.join(PYPROJECT_TOML))?.unwrap_or_default();
+        if let Some(options) = tools.sith {
+            return Ok(Some(options));
+        }
+
+        let pyrightconfig = root.join(PYRIGHTCONFIG_JSON);
+        if pyrightconfig.is_file() {
+            let contents = std::fs::read_to_string(&pyrightconfig)
+                .with_context(|| format!("Failed to read `{}`", pyrightconfig.display()))?;
+            let options = serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse `{}`", pyrightconfig.display()))?;
+            return Ok(Some(options));
+        }
+
+        Ok(tools.pyright)
+    }
+}
+
+fn read_pyproject(path: &Path) -> crate::Result<Option<Tools>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read `{}`", path.display()))?;
+    let pyproject: PyProject = toml::from_str(&contents)
+        .with_context(|| format!("Failed to parse `{}`", path.display()))?;
+    Ok(pyproject.tool)
+}
+
+/// Parses a `pythonVersion` option, e.g. `3.11`, logging unsupported versions.
+pub(super) fn parse_python_version(version: &str) -> Option<PythonVersion> {
+    let parsed = PythonVersion::from_version_str(version);
+    if parsed.is_none() {
+        tracing::warn!("Unsupported `pythonVersion` `{version}`");
+    }
+    parsed
+}
+
+/// Parses a `pythonPlatform` option, one of `Linux`, `Darwin` or `Windows`.
+pub(super) fn parse_python_platform(platform: &str) -> Option<PythonPlatform> {
+    match platform {
+        "Linux" => Some(PythonPlatform::Linux),
+        "Darwin" => Some(PythonPlatform::Darwin),
+        "Windows" => Some(PythonPlatform::Windows),
+        _ => {
+            tracing::warn!("Unsupported `pythonPlatform` `{platform}`");
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{ExecutionEnvironmentOptions, ProjectOptions};
+
+    #[test]
+    fn project_options_precedence() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        assert_eq!(ProjectOptions::load(root).unwrap(), None);
+
+        std::fs::write(
+            root.join("pyproject.toml"),
+            "[tool.pyright]\nextraPaths = [\"lib\"]\n",
+        )
+        .unwrap();
+        assert_eq!(
+            ProjectOptions::load(root).unwrap().unwrap().extra_paths,
+            vec![PathBuf::from("lib")]
+        );
+
+        std::fs::write(
+            root.join("pyrightconfig.json"),
+            r#"{
+                "venvPath": ".",
+                "venv": "env",
+                "executionEnvironments": [{"root": "src", "pythonVersion": "3.9"}]
+            }"#,
+        )
+        .unwrap();
+        let options = ProjectOptions::load(root).unwrap().unwrap();
+        assert_eq!(options.venv, Some(PathBuf::from("env")));
+        assert_eq!(
+            options.execution_environments,
+            vec![ExecutionEnvironmentOptions {
+                root: PathBuf::from("src"),
+                python_version: Some("3.9".to_string()),
+                ..ExecutionEnvironmentOptions::default()
+            }]
+        );
+
+        std::fs::write(
+            root.join("pyproject.toml"),
+            "[tool.sith]\npythonVersion = \"3.10\"\nstubPath = \"stubs\"\n",
+        )
+        .unwrap();
+        let options = ProjectOptions::load(root).unwrap().unwrap();
+        assert_eq!(options.python_version.as_deref(), Some("3.10"));
+        assert_eq!(options.stub_path, Some(PathBuf::from("stubs")));
+
+        std::fs::write(root.join("pyproject.toml"), "[tool.sith\n").unwrap();
+        assert!(ProjectOptions::load(root).is_err());
+    }
+}
@@ -2,15 +2,28 @@
 
 use std::num::NonZeroUsize;
 
+use crossbeam::channel;
 use lsp::Connection;
 use lsp_server as lsp;
 use lsp_types as types;
 use lsp_types::CompletionOptions;
+use types::CallHierarchyServerCapability;
 use types::ClientCapabilities;
+use types::CodeActionKind;
+use types::CodeActionOptions;
+use types::CodeActionProviderCapability;
 use types::DiagnosticOptions;
 use types::DidChangeWatchedFilesRegistrationOptions;
 use types::FileSystemWatcher;
+use types::HoverProviderCapability;
+use types::InlayHintOptions;
+use types::InlayHintServerCapabilities;
 use types::OneOf;
+use types::RenameOptions;
+use types::SemanticTokensFullOptions;
+use types::SemanticTokensLegend;
+use types::SemanticTokensOptions;
+use types::SignatureHelpOptions;
 use types::TextDocumentSyncCapability;
 use types::TextDocumentSyncKind;
 use types::TextDocumentSyncOptions;
@@ -20,7 +33,8 @@ use types::WorkspaceFoldersServerCapabilities;
 use self::schedule::event_loop_thread;
 use self::schedule::Scheduler;
 use self::schedule::Task;
-use crate::session::Session;
+use crate::analysis::{TOKEN_MODIFIERS, TOKEN_TYPES};
+use crate::session::{ClientSettings, Session};
 use crate::PositionEncoding;
 
 mod api;
@@ -46,6 +60,10 @@ impl Server {
         let init_params: types::InitializeParams = serde_json::from_value(params)?;
 
         let client_capabilities = init_params.capabilities;
+        let global_settings = init_params
+            .initialization_options
+            .map(ClientSettings::from_value)
+            .unwrap_or_default();
         let server_capabilities = Self::server_capabilities(&client_capabilities);
 
         let workspaces = init_params
@@ -73,7 +91,12 @@ impl Server {
             conn,
             threads,
             worker_threads,
-            session: Session::new(&client_capabilities, &server_capabilities, &workspaces)?,
+            session: Session::new(
+                &client_capabilities,
+                &server_capabilities,
+                &workspaces,
+                global_settings,
+            )?,
             client_capabilities,
         })
     }
@@ -102,7 +125,49 @@ impl Server {
             schedule::Scheduler::new(&mut session, worker_threads, &connection.sender);
 
         Self::try_register_capabilities(client_capabilities, &mut scheduler);
-        for msg in &connection.receiver {
+        // The settings sent during initialization apply to every workspace folder until their
+        // own settings are pulled.
+        scheduler.dispatch(Task::local(|session, _, requester, _| {
+            if !session
+                .resolved_client_capabilities()
+                .workspace_configuration
+            {
+                return;
+            }
+            if let Err(err) = api::request_workspace_settings(session.workspace_urls(), requester) {
+                tracing::error!("Failed to request the workspace settings: {err}");
+            }
+        }));
+        // The symbol indexes built in the background, by workspace root.
+        let (index_sender, index_receiver) = channel::unbounded();
+        loop {
+            for url in scheduler.take_due_diagnostics() {
+                scheduler.dispatch(api::publish_diagnostics(url));
+            }
+            for root in scheduler.take_unindexed_workspaces() {
+                scheduler.dispatch(api::build_symbol_index(root, index_sender.clone()));
+            }
+
+            // Wake up when the next pending diagnostics are due, unless a message comes first.
+            let deadline = scheduler
+                .diagnostics_deadline()
+                .map_or_else(channel::never, channel::at);
+            let msg = channel::select! {
+                recv(connection.receiver) -> msg => match msg {
+                    Ok(msg) => msg,
+                    Err(_) => break,
+                },
+                recv(index_receiver) -> index => {
+                    if let Ok((root, index)) = index {
+                        scheduler.dispatch(Task::local(move |session, _, _, _| {
+                            session.finish_symbol_index(&root, index);
+                        }));
+                    }
+                    continue;
+                },
+                recv(deadline) -> _ => continue,
+            };
+
             let task = match msg {
                 lsp::Message::Request(req) => {
                     if connection.handle_shutdown(&req)? {
@@ -142,7 +207,7 @@ impl Server {
                             watchers: vec![
                                 FileSystemWatcher {
                                     glob_pattern: types::GlobPattern::String(
-                                        "**/.?ruff.toml".into(),
+                                        "**/pyrightconfig.json".into(),
                                     ),
                                     kind: None,
                                 },
@@ -152,6 +217,13 @@ impl Server {
                                     ),
                                     kind: None,
                                 },
+                                // Keeps the workspace symbol index up to date.
+                                FileSystemWatcher {
+                                    glob_pattern: types::GlobPattern::String(
+                                        "**/*.{py,pyi}".into(),
+                                    ),
+                                    kind: None,
+                                },
                             ],
                         })
                         .unwrap(),
@@ -198,12 +270,22 @@ impl Server {
             text_document_sync: Some(TextDocumentSyncCapability::Options(
                 TextDocumentSyncOptions {
                     open_close: Some(true),
-                    change: Some(TextDocumentSyncKind::FULL),
+                    change: Some(TextDocumentSyncKind::INCREMENTAL),
                     will_save: Some(false),
                     will_save_wait_until: Some(false),
                     ..Default::default()
                 },
             )),
+            call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                code_action_kinds: Some(vec![
+                    CodeActionKind::QUICKFIX,
+                    CodeActionKind::SOURCE_ORGANIZE_IMPORTS,
+                    crate::SOURCE_FIX_ALL_SITH,
+                ]),
+                work_done_progress_options: WorkDoneProgressOptions::default(),
+                resolve_provider: Some(false),
+            })),
             completion_provider: Some(CompletionOptions {
                 resolve_provider: Some(false),
                 trigger_characters: Some(vec![".".to_string()]),
@@ -214,8 +296,8 @@ impl Server {
             diagnostic_provider: Some(types::DiagnosticServerCapabilities::Options(
                 DiagnosticOptions {
                     identifier: Some(crate::DIAGNOSTIC_NAME.into()),
-                    // multi-file analysis could change this
-                    inter_file_dependencies: false,
+                    // The imports are checked against the other files of the workspace.
+                    inter_file_dependencies: true,
                     workspace_diagnostics: false,
                     work_done_progress_options: WorkDoneProgressOptions {
                         work_done_progress: Some(true),
@@ -223,9 +305,39 @@ impl Server {
                 },
             )),
             document_formatting_provider: Some(OneOf::Left(true)),
+            document_range_formatting_provider: Some(OneOf::Left(true)),
             definition_provider: Some(OneOf::Left(true)),
+            document_symbol_provider: Some(OneOf::Left(true)),
+            workspace_symbol_provider: Some(OneOf::Left(true)),
+            hover_provider: Some(HoverProviderCapability::Simple(true)),
+            inlay_hint_provider: Some(OneOf::Right(InlayHintServerCapabilities::Options(
+                InlayHintOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                },
+            ))),
             references_provider: Some(OneOf::Left(true)),
-            rename_provider: Some(OneOf::Left(true)),
+            rename_provider: Some(OneOf::Right(RenameOptions {
+                prepare_provider: Some(true),
+                work_done_progress_options: WorkDoneProgressOptions::default(),
+            })),
+            signature_help_provider: Some(SignatureHelpOptions {
+                trigger_characters: Some(vec!["(".to_string(), ",".to_string()]),
+                retrigger_characters: None,
+                work_done_progress_options: WorkDoneProgressOptions::default(),
+            }),
+            semantic_tokens_provider: Some(
+                SemanticTokensOptions {
+                    legend: SemanticTokensLegend {
+                        token_types: TOKEN_TYPES.to_vec(),
+                        token_modifiers: TOKEN_MODIFIERS.to_vec(),
+                    },
+                    range: Some(true),
+                    full: Some(SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                }
+                .into(),
+            ),
             ..Default::default()
         }
     }
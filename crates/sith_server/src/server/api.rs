@@ -1,6 +1,14 @@
-use crate::{server::schedule::Task, session::Session};
+use std::path::PathBuf;
+
+use crate::{
+    server::schedule::Task,
+    session::{Session, SymbolIndex},
+};
+use crossbeam::channel::Sender;
 use lsp_server as server;
 
+mod configuration;
+mod diagnostics;
 mod notifications;
 mod requests;
 mod traits;
@@ -8,6 +16,8 @@ mod traits;
 use notifications as notification;
 use requests as request;
 
+pub(super) use self::configuration::request_workspace_settings;
+use self::diagnostics::publish_diagnostics_for_document;
 use self::traits::{NotificationHandler, RequestHandler};
 
 use super::{client::Responder, schedule::BackgroundSchedule, Result};
@@ -28,6 +38,88 @@ pub(super) fn request<'a>(req: server::Request) -> Task<'a> {
     let id = req.id.clone();
 
     match req.method.as_str() {
+        request::CodeActions::METHOD => background_request_task::<request::CodeActions>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::Completion::METHOD => background_request_task::<request::Completion>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::DocumentDiagnostic::METHOD => {
+            background_request_task::<request::DocumentDiagnostic>(
+                req,
+                BackgroundSchedule::LatencySensitive,
+            )
+        }
+        request::DocumentSymbol::METHOD => background_request_task::<request::DocumentSymbol>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::Format::METHOD => {
+            background_request_task::<request::Format>(req, BackgroundSchedule::Fmt)
+        }
+        request::FormatRange::METHOD => {
+            background_request_task::<request::FormatRange>(req, BackgroundSchedule::Fmt)
+        }
+        request::GotoDefinition::METHOD => background_request_task::<request::GotoDefinition>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::Hover::METHOD => {
+            background_request_task::<request::Hover>(req, BackgroundSchedule::LatencySensitive)
+        }
+        // Finding the callers searches the workspace, like the references.
+        request::IncomingCalls::METHOD => {
+            background_request_task::<request::IncomingCalls>(req, BackgroundSchedule::Worker)
+        }
+        request::InlayHints::METHOD => background_request_task::<request::InlayHints>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::InlayHintResolve::METHOD => local_request_task::<request::InlayHintResolve>(req),
+        // Searching the workspace is slow, run it on a regular-priority thread of the pool.
+        request::References::METHOD => {
+            background_request_task::<request::References>(req, BackgroundSchedule::Worker)
+        }
+        request::OutgoingCalls::METHOD => background_request_task::<request::OutgoingCalls>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::PrepareCallHierarchy::METHOD => background_request_task::<
+            request::PrepareCallHierarchy,
+        >(
+            req, BackgroundSchedule::LatencySensitive
+        ),
+        request::PrepareRename::METHOD => background_request_task::<request::PrepareRename>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::Rename::METHOD => {
+            background_request_task::<request::Rename>(req, BackgroundSchedule::Worker)
+        }
+        request::SemanticTokensFull::METHOD => {
+            background_request_task::<request::SemanticTokensFull>(
+                req,
+                BackgroundSchedule::LatencySensitive,
+            )
+        }
+        request::SemanticTokensRange::METHOD => background_request_task::<
+            request::SemanticTokensRange,
+        >(req, BackgroundSchedule::LatencySensitive),
+        request::SemanticTokensDelta::METHOD => background_request_task::<
+            request::SemanticTokensDelta,
+        >(req, BackgroundSchedule::LatencySensitive),
+        request::SignatureHelp::METHOD => background_request_task::<request::SignatureHelp>(
+            req,
+            BackgroundSchedule::LatencySensitive,
+        ),
+        request::WorkspaceSymbol::METHOD => {
+            background_index_request_task::<request::WorkspaceSymbol>(
+                req,
+                BackgroundSchedule::Worker,
+            )
+        }
         method => {
             tracing::warn!("Received request {method} which does not have a handler");
             return Task::nothing();
@@ -42,6 +134,22 @@ pub(super) fn request<'a>(req: server::Request) -> Task<'a> {
 
 pub(super) fn notification<'a>(notif: server::Notification) -> Task<'a> {
     match notif.method.as_str() {
+        notification::DidChange::METHOD => {
+            local_notification_task::<notification::DidChange>(notif)
+        }
+        notification::DidChangeConfiguration::METHOD => {
+            local_notification_task::<notification::DidChangeConfiguration>(notif)
+        }
+        notification::DidChangeWatchedFiles::METHOD => {
+            local_notification_task::<notification::DidChangeWatchedFiles>(notif)
+        }
+        notification::DidChangeWorkspace::METHOD => {
+            local_notification_task::<notification::DidChangeWorkspace>(notif)
+        }
+        notification::DidClose::METHOD => local_notification_task::<notification::DidClose>(notif),
+        notification::DidOpen::METHOD => local_and_background_notification_task::<
+            notification::DidOpen,
+        >(notif, BackgroundSchedule::LatencySensitive),
         method => {
             tracing::warn!("Received notification {method} which does not have a handler.");
             return Task::nothing();
@@ -53,13 +161,43 @@ pub(super) fn notification<'a>(notif: server::Notification) -> Task<'a> {
     })
 }
 
-#[allow(dead_code)]
+/// Creates a background task that publishes the diagnostics of the open document at `url`.
+pub(super) fn publish_diagnostics<'a>(url: lsp_types::Url) -> Task<'a> {
+    Task::background(BackgroundSchedule::Worker, move |session: &Session| {
+        let Some(snapshot) = session.take_snapshot(&url) else {
+            return Box::new(|_, _| {});
+        };
+        Box::new(move |notifier, _| {
+            if let Err(err) = publish_diagnostics_for_document(&snapshot, &notifier) {
+                tracing::error!("Failed to publish the diagnostics of {url}: {err}");
+            }
+        })
+    })
+}
+
+/// Creates a background task that builds the symbol index of the workspace at `root`, and
+/// sends it to `sender` along with the root.
+pub(super) fn build_symbol_index<'a>(
+    root: PathBuf,
+    sender: Sender<(PathBuf, SymbolIndex)>,
+) -> Task<'a> {
+    Task::background(BackgroundSchedule::Worker, move |session: &Session| {
+        let encoding = session.encoding();
+        Box::new(move |_, _| {
+            let index = SymbolIndex::build(&root, encoding);
+            if sender.send((root, index)).is_err() {
+                tracing::debug!("The symbol index was built after the server stopped");
+            }
+        })
+    })
+}
+
 fn local_request_task<'a, R: traits::SyncRequestHandler>(
     req: server::Request,
 ) -> super::Result<Task<'a>> {
     let (id, params) = cast_request::<R>(req)?;
-    Ok(Task::local(|session, notifier, responder| {
-        let result = R::run(session, notifier, params);
+    Ok(Task::local(|session, notifier, requester, responder| {
+        let result = R::run(session, notifier, requester, params);
         respond::<R>(id, result, &responder);
     }))
 }
@@ -81,18 +219,31 @@ fn background_request_task<'a, R: traits::BackgroundDocumentRequestHandler>(
     }))
 }
 
+fn background_index_request_task<'a, R: traits::BackgroundIndexRequestHandler>(
+    req: server::Request,
+    schedule: BackgroundSchedule,
+) -> super::Result<Task<'a>> {
+    let (id, params) = cast_request::<R>(req)?;
+    Ok(Task::background(schedule, move |session: &Session| {
+        let snapshot = session.take_index_snapshot();
+        Box::new(move |notifier, responder| {
+            let result = R::run_with_snapshot(snapshot, notifier, params);
+            respond::<R>(id, result, &responder);
+        })
+    }))
+}
+
 fn local_notification_task<'a, N: traits::SyncNotificationHandler>(
     notif: server::Notification,
 ) -> super::Result<Task<'a>> {
     let (id, params) = cast_notification::<N>(notif)?;
-    Ok(Task::local(move |session, notifier, _| {
-        if let Err(err) = N::run(session, notifier, params) {
+    Ok(Task::local(move |session, notifier, requester, _| {
+        if let Err(err) = N::run(session, notifier, requester, params) {
             tracing::error!("An error occurred while running {id}: {err}");
         }
     }))
 }
 
-#[allow(dead_code)]
 fn background_notification_thread<'a, N: traits::BackgroundDocumentNotificationHandler>(
     req: server::Notification,
     schedule: BackgroundSchedule,
@@ -111,6 +262,20 @@ fn background_notification_thread<'a, N: traits::BackgroundDocumentNotificationH
     }))
 }
 
+/// Creates a local task that runs `N` with mutable access to the session, followed by
+/// a background task that runs `N` again with a snapshot of the updated document.
+fn local_and_background_notification_task<'a, N>(
+    notif: server::Notification,
+    schedule: BackgroundSchedule,
+) -> super::Result<Task<'a>>
+where
+    N: traits::SyncNotificationHandler + traits::BackgroundDocumentNotificationHandler,
+{
+    let local = local_notification_task::<N>(notif.clone())?;
+    let background = background_notification_thread::<N>(notif, schedule)?;
+    Ok(local.then(background))
+}
+
 /// Tries to cast a serialized request from the server into
 /// a parameter type for a specific request handler.
 /// It is *highly* recommended to not override this function in your
@@ -211,6 +376,9 @@ impl std::fmt::Debug for Error {
 
 impl std::fmt::Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}: {}", self.error, self.error.source().unwrap())
+        match self.error.source() {
+            Some(source) => write!(f, "{}: {source}", self.error),
+            None => write!(f, "{}", self.error),
+        }
     }
 }
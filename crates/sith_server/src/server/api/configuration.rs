@@ -0,0 +1,65 @@
+//! Pulling the settings of the workspace folders from the client.
+
+use lsp_types::{self as types, request as req, Url};
+use serde_json::Value;
+
+use crate::server::api::diagnostics::refresh_diagnostics;
+use crate::server::client::Requester;
+use crate::server::schedule::Task;
+use crate::session::{ClientSettings, Session};
+
+/// The section of the client's configuration holding the server's settings.
+pub(super) const SETTINGS_SECTION: &str = "sith";
+
+/// Asks the client for the settings of the workspace folders at `urls`, the settings of each
+/// folder are updated once the client responds.
+pub(in crate::server) fn request_workspace_settings(
+    urls: Vec<Url>,
+    requester: &mut Requester,
+) -> crate::Result<()> {
+    if urls.is_empty() {
+        return Ok(());
+    }
+
+    let items = urls
+        .iter()
+        .map(|url| types::ConfigurationItem {
+            scope_uri: Some(url.clone()),
+            section: Some(SETTINGS_SECTION.to_string()),
+        })
+        .collect();
+
+    requester.request::<req::WorkspaceConfiguration>(
+        types::ConfigurationParams { items },
+        move |settings: Vec<Value>| {
+            let urls = urls.clone();
+            Task::local(move |session, _, requester, _| {
+                // The client responds with the settings in the order of the requested items.
+                let mut changed = false;
+                for (url, settings) in urls.iter().zip(settings) {
+                    changed |= session
+                        .update_workspace_settings(url, &ClientSettings::from_value(settings));
+                }
+                if !changed {
+                    return;
+                }
+                if let Err(err) = refresh_open_documents(session, requester) {
+                    tracing::error!("Failed to refresh the open documents: {err}");
+                }
+            })
+        },
+    )
+}
+
+/// Updates what the client shows for the open documents after the settings changed: the
+/// diagnostics are pulled again or republished, and the inlay hints are requested again.
+pub(super) fn refresh_open_documents(
+    session: &mut Session,
+    requester: &mut Requester,
+) -> crate::Result<()> {
+    refresh_diagnostics(session, requester)?;
+    if session.resolved_client_capabilities().inlay_hint_refresh {
+        requester.request::<req::InlayHintRefreshRequest>((), |()| Task::nothing())?;
+    }
+    Ok(())
+}
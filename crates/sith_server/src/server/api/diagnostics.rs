@@ -0,0 +1,89 @@
+use std::time::Duration;
+
+use lsp_types::request::WorkspaceDiagnosticRefresh;
+use lsp_types::{notification::PublishDiagnostics, Diagnostic, PublishDiagnosticsParams, Url};
+
+use crate::analysis::ParsedModule;
+use crate::server::client::Requester;
+use crate::server::schedule::Task;
+use crate::session::Session;
+use crate::{lint, server::client::Notifier, session::DocumentSnapshot};
+
+use super::LSPResult;
+
+/// How long to wait after an edit before publishing the diagnostics of a document.
+/// Any edit made during this window pushes the pending publication back.
+pub(super) const PUBLISH_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(200);
+
+pub(super) fn generate_diagnostics(snapshot: &DocumentSnapshot) -> Vec<Diagnostic> {
+    if !snapshot.client_settings().diagnostics.enable {
+        return Vec::new();
+    }
+
+    // Untitled documents resolve their imports as if they were in the workspace root.
+    let path = snapshot.url().to_file_path().unwrap_or_else(|()| {
+        snapshot
+            .import_resolver()
+            .root()
+            .join(snapshot.url().path())
+    });
+    let module = ParsedModule::from_document(path, snapshot.document());
+    lint::check(&module, snapshot.import_resolver(), snapshot.encoding())
+}
+
+/// Sends the diagnostics of the document to the client, unless a newer version of the
+/// document has superseded the `snapshot`.
+pub(super) fn publish_diagnostics_for_document(
+    snapshot: &DocumentSnapshot,
+    notifier: &Notifier,
+) -> crate::server::Result<()> {
+    if snapshot.document().is_outdated() {
+        return Ok(());
+    }
+
+    let diagnostics = generate_diagnostics(snapshot);
+
+    // The document could have been edited while we were checking it.
+    if snapshot.document().is_outdated() {
+        return Ok(());
+    }
+
+    notifier
+        .notify::<PublishDiagnostics>(PublishDiagnosticsParams {
+            uri: snapshot.url().clone(),
+            diagnostics,
+            version: Some(snapshot.document().version()),
+        })
+        .with_failure_code(lsp_server::ErrorCode::InternalError)?;
+
+    Ok(())
+}
+
+/// Updates the diagnostics of every open document after a change outside of them: a client
+/// pulling diagnostics is asked to pull them again, they're republished otherwise.
+pub(super) fn refresh_diagnostics(
+    session: &mut Session,
+    requester: &mut Requester,
+) -> crate::Result<()> {
+    session.invalidate_diagnostics();
+    if !session.resolved_client_capabilities().diagnostic_refresh {
+        return Ok(());
+    }
+    requester.request::<WorkspaceDiagnosticRefresh>((), |()| Task::nothing())
+}
+
+/// Removes every diagnostic the client is showing for the document at `url`.
+pub(super) fn clear_diagnostics_for_document(
+    url: &Url,
+    notifier: &Notifier,
+) -> crate::server::Result<()> {
+    notifier
+        .notify::<PublishDiagnostics>(PublishDiagnosticsParams {
+            uri: url.clone(),
+            diagnostics: vec![],
+            version: None,
+        })
+        .with_failure_code(lsp_server::ErrorCode::InternalError)?;
+
+    Ok(())
+}
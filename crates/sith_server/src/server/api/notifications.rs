@@ -0,0 +1,17 @@
+mod did_change;
+mod did_change_configuration;
+mod did_change_watched_files;
+mod did_change_workspace;
+mod did_close;
+mod did_open;
+
+use super::{
+    define_document_url,
+    traits::{BackgroundDocumentNotificationHandler, NotificationHandler, SyncNotificationHandler},
+};
+pub(super) use did_change::DidChange;
+pub(super) use did_change_configuration::DidChangeConfiguration;
+pub(super) use did_change_watched_files::DidChangeWatchedFiles;
+pub(super) use did_change_workspace::DidChangeWorkspace;
+pub(super) use did_close::DidClose;
+pub(super) use did_open::DidOpen;
@@ -0,0 +1,46 @@
+use crate::server::api::configuration::{
+    refresh_open_documents, request_workspace_settings, SETTINGS_SECTION,
+};
+use crate::server::api::LSPResult;
+use crate::server::client::{Notifier, Requester};
+use crate::server::Result;
+use crate::session::{ClientSettings, Session};
+use lsp_types as types;
+use lsp_types::notification as notif;
+
+pub(crate) struct DidChangeConfiguration;
+
+impl super::NotificationHandler for DidChangeConfiguration {
+    type NotificationType = notif::DidChangeConfiguration;
+}
+
+impl super::SyncNotificationHandler for DidChangeConfiguration {
+    fn run(
+        session: &mut Session,
+        _notifier: Notifier,
+        requester: &mut Requester,
+        params: types::DidChangeConfigurationParams,
+    ) -> Result<()> {
+        // Clients pulling the configuration usually send an empty notification, the settings
+        // of each workspace folder are pulled again instead.
+        if session
+            .resolved_client_capabilities()
+            .workspace_configuration
+        {
+            return request_workspace_settings(session.workspace_urls(), requester)
+                .with_failure_code(lsp_server::ErrorCode::InternalError);
+        }
+
+        let settings = match params.settings {
+            serde_json::Value::Object(mut settings) if settings.contains_key(SETTINGS_SECTION) => {
+                settings.remove(SETTINGS_SECTION).unwrap_or_default()
+            }
+            settings => settings,
+        };
+        if session.update_global_settings(ClientSettings::from_value(settings)) {
+            refresh_open_documents(session, requester)
+                .with_failure_code(lsp_server::ErrorCode::InternalError)?;
+        }
+        Ok(())
+    }
+}
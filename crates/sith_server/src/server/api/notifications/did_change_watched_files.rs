@@ -0,0 +1,42 @@
+use crate::server::api::diagnostics::refresh_diagnostics;
+use crate::server::api::LSPResult;
+use crate::server::client::{Notifier, Requester};
+use crate::server::Result;
+use crate::session::{is_python_file, Session};
+use lsp_types as types;
+use lsp_types::notification as notif;
+
+pub(crate) struct DidChangeWatchedFiles;
+
+impl super::NotificationHandler for DidChangeWatchedFiles {
+    type NotificationType = notif::DidChangeWatchedFiles;
+}
+
+impl super::SyncNotificationHandler for DidChangeWatchedFiles {
+    fn run(
+        session: &mut Session,
+        _notifier: Notifier,
+        requester: &mut Requester,
+        params: types::DidChangeWatchedFilesParams,
+    ) -> Result<()> {
+        session.update_indexed_files(&params.changes);
+
+        // The imports of the open documents may resolve differently after a Python file
+        // or the configuration changed on disk.
+        let mut invalidated = params.changes.iter().any(|change| {
+            change
+                .uri
+                .to_file_path()
+                .is_ok_and(|path| is_python_file(&path))
+        });
+        for change in params.changes {
+            invalidated |= session.update_configuration_file(&change.uri);
+        }
+
+        if invalidated {
+            refresh_diagnostics(session, requester)
+                .with_failure_code(lsp_server::ErrorCode::InternalError)?;
+        }
+        Ok(())
+    }
+}
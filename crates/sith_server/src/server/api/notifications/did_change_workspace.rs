@@ -0,0 +1,43 @@
+use crate::server::api::configuration::request_workspace_settings;
+use crate::server::api::LSPResult;
+use crate::server::client::{Notifier, Requester};
+use crate::server::Result;
+use crate::session::Session;
+use lsp_types as types;
+use lsp_types::notification as notif;
+
+pub(crate) struct DidChangeWorkspace;
+
+impl super::NotificationHandler for DidChangeWorkspace {
+    type NotificationType = notif::DidChangeWorkspaceFolders;
+}
+
+impl super::SyncNotificationHandler for DidChangeWorkspace {
+    fn run(
+        session: &mut Session,
+        _notifier: Notifier,
+        requester: &mut Requester,
+        params: types::DidChangeWorkspaceFoldersParams,
+    ) -> Result<()> {
+        let mut added = Vec::with_capacity(params.event.added.len());
+        for types::WorkspaceFolder { uri, .. } in params.event.added {
+            session
+                .open_workspace_folder(&uri)
+                .with_failure_code(lsp_server::ErrorCode::InvalidParams)?;
+            added.push(uri);
+        }
+        for types::WorkspaceFolder { ref uri, .. } in params.event.removed {
+            session
+                .close_workspace_folder(uri)
+                .with_failure_code(lsp_server::ErrorCode::InvalidParams)?;
+        }
+        if session
+            .resolved_client_capabilities()
+            .workspace_configuration
+        {
+            request_workspace_settings(added, requester)
+                .with_failure_code(lsp_server::ErrorCode::InternalError)?;
+        }
+        Ok(())
+    }
+}
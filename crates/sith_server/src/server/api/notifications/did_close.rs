@@ -0,0 +1,34 @@
+use crate::server::api::diagnostics::clear_diagnostics_for_document;
+use crate::server::api::LSPResult;
+use crate::server::client::{Notifier, Requester};
+use crate::server::Result;
+use crate::session::Session;
+use lsp_types as types;
+use lsp_types::notification as notif;
+
+pub(crate) struct DidClose;
+
+impl super::NotificationHandler for DidClose {
+    type NotificationType = notif::DidCloseTextDocument;
+}
+
+impl super::SyncNotificationHandler for DidClose {
+    #[tracing::instrument(skip_all, fields(file=%uri))]
+    fn run(
+        session: &mut Session,
+        notifier: Notifier,
+        _requester: &mut Requester,
+        types::DidCloseTextDocumentParams {
+            text_document: types::TextDocumentIdentifier { uri },
+        }: types::DidCloseTextDocumentParams,
+    ) -> Result<()> {
+        // Publish diagnostics if the client doesn't support pull diagnostics
+        if !session.resolved_client_capabilities().pull_diagnostics {
+            clear_diagnostics_for_document(&uri, &notifier)?;
+        }
+
+        session
+            .close_document(&uri)
+            .with_failure_code(lsp_server::ErrorCode::InternalError)
+    }
+}
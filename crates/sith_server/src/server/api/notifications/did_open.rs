@@ -0,0 +1,50 @@
+use crate::server::api::diagnostics::publish_diagnostics_for_document;
+use crate::server::client::{Notifier, Requester};
+use crate::server::Result;
+use crate::session::{DocumentSnapshot, Session};
+use lsp_types as types;
+use lsp_types::notification as notif;
+
+pub(crate) struct DidOpen;
+
+impl super::NotificationHandler for DidOpen {
+    type NotificationType = notif::DidOpenTextDocument;
+}
+
+impl super::SyncNotificationHandler for DidOpen {
+    #[tracing::instrument(skip_all, fields(file=%url))]
+    fn run(
+        session: &mut Session,
+        _notifier: Notifier,
+        _requester: &mut Requester,
+        types::DidOpenTextDocumentParams {
+            text_document:
+                types::TextDocumentItem {
+                    uri: ref url,
+                    text,
+                    version,
+                    ..
+                },
+        }: types::DidOpenTextDocumentParams,
+    ) -> Result<()> {
+        session.open_document(url, text, version);
+        Ok(())
+    }
+}
+
+impl super::BackgroundDocumentNotificationHandler for DidOpen {
+    super::define_document_url!(params: &types::DidOpenTextDocumentParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        notifier: Notifier,
+        _params: types::DidOpenTextDocumentParams,
+    ) -> Result<()> {
+        // Publish diagnostics if the client doesn't support pull diagnostics
+        if snapshot.resolved_client_capabilities().pull_diagnostics {
+            return Ok(());
+        }
+
+        publish_diagnostics_for_document(&snapshot, &notifier)
+    }
+}
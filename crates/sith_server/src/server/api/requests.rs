@@ -0,0 +1,40 @@
+mod call_hierarchy;
+mod code_action;
+mod completion;
+mod diagnostic;
+mod document_symbol;
+mod format;
+mod format_range;
+mod goto_definition;
+mod hover;
+mod inlay_hint;
+mod prepare_rename;
+mod references;
+mod rename;
+mod semantic_tokens;
+mod signature_help;
+mod workspace_symbol;
+
+use super::{
+    define_document_url,
+    traits::{
+        BackgroundDocumentRequestHandler, BackgroundIndexRequestHandler, RequestHandler,
+        SyncRequestHandler,
+    },
+};
+pub(super) use call_hierarchy::{IncomingCalls, OutgoingCalls, PrepareCallHierarchy};
+pub(super) use code_action::CodeActions;
+pub(super) use completion::Completion;
+pub(super) use diagnostic::DocumentDiagnostic;
+pub(super) use document_symbol::DocumentSymbol;
+pub(super) use format::Format;
+pub(super) use format_range::FormatRange;
+pub(super) use goto_definition::GotoDefinition;
+pub(super) use hover::Hover;
+pub(super) use inlay_hint::{InlayHintResolve, InlayHints};
+pub(super) use prepare_rename::PrepareRename;
+pub(super) use references::References;
+pub(super) use rename::Rename;
+pub(super) use semantic_tokens::{SemanticTokensDelta, SemanticTokensFull, SemanticTokensRange};
+pub(super) use signature_help::SignatureHelp;
+pub(super) use workspace_symbol::WorkspaceSymbol;
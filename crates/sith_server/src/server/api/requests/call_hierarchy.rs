@@ -0,0 +1,205 @@
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::analysis::{
+    self, python_files, CallHierarchyItem, Calls, DefinitionResolver, ParsedModule,
+};
+use crate::edit::{position_to_offset, RangeExt, ToRangeExt};
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use crate::PositionEncoding;
+use lsp_types::{self as types, request as req};
+use ruff_text_size::TextRange;
+
+pub(crate) struct PrepareCallHierarchy;
+
+impl super::RequestHandler for PrepareCallHierarchy {
+    type RequestType = req::CallHierarchyPrepare;
+}
+
+impl super::BackgroundDocumentRequestHandler for PrepareCallHierarchy {
+    fn document_url(params: &types::CallHierarchyPrepareParams) -> Cow<'_, lsp_types::Url> {
+        Cow::Borrowed(&params.text_document_position_params.text_document.uri)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::CallHierarchyPrepareParams,
+    ) -> Result<Option<Vec<types::CallHierarchyItem>>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.text_document_position_params.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver())
+            .with_documents(snapshot.workspace_documents());
+        resolver.insert(module.clone());
+
+        let items: Vec<_> = analysis::prepare_call_hierarchy(&mut resolver, &module, offset)
+            .iter()
+            .filter_map(|item| to_lsp_item(item, snapshot.url(), snapshot.encoding()))
+            .collect();
+
+        if items.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(items))
+    }
+}
+
+pub(crate) struct IncomingCalls;
+
+impl super::RequestHandler for IncomingCalls {
+    type RequestType = req::CallHierarchyIncomingCalls;
+}
+
+impl super::BackgroundDocumentRequestHandler for IncomingCalls {
+    fn document_url(params: &types::CallHierarchyIncomingCallsParams) -> Cow<'_, lsp_types::Url> {
+        origin_url(&params.item)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::CallHierarchyIncomingCallsParams,
+    ) -> Result<Option<Vec<types::CallHierarchyIncomingCall>>> {
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver())
+            .with_documents(snapshot.workspace_documents());
+        let Some((module, selection_range)) = item_module(&snapshot, &mut resolver, &params.item)
+        else {
+            return Ok(None);
+        };
+
+        let paths = python_files(snapshot.import_resolver().root());
+        let calls: Vec<_> =
+            analysis::incoming_calls(&mut resolver, &module, selection_range, &paths)
+                .into_iter()
+                .filter_map(|Calls { item, ranges }| {
+                    Some(types::CallHierarchyIncomingCall {
+                        from: to_lsp_item(&item, snapshot.url(), snapshot.encoding())?,
+                        from_ranges: to_lsp_ranges(&item.module, &ranges, snapshot.encoding()),
+                    })
+                })
+                .collect();
+
+        if calls.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(calls))
+    }
+}
+
+pub(crate) struct OutgoingCalls;
+
+impl super::RequestHandler for OutgoingCalls {
+    type RequestType = req::CallHierarchyOutgoingCalls;
+}
+
+impl super::BackgroundDocumentRequestHandler for OutgoingCalls {
+    fn document_url(params: &types::CallHierarchyOutgoingCallsParams) -> Cow<'_, lsp_types::Url> {
+        origin_url(&params.item)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::CallHierarchyOutgoingCallsParams,
+    ) -> Result<Option<Vec<types::CallHierarchyOutgoingCall>>> {
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver())
+            .with_documents(snapshot.workspace_documents());
+        let Some((module, selection_range)) = item_module(&snapshot, &mut resolver, &params.item)
+        else {
+            return Ok(None);
+        };
+
+        let calls: Vec<_> = analysis::outgoing_calls(&mut resolver, &module, selection_range)
+            .into_iter()
+            .filter_map(|Calls { item, ranges }| {
+                Some(types::CallHierarchyOutgoingCall {
+                    to: to_lsp_item(&item, snapshot.url(), snapshot.encoding())?,
+                    from_ranges: to_lsp_ranges(&module, &ranges, snapshot.encoding()),
+                })
+            })
+            .collect();
+
+        if calls.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(calls))
+    }
+}
+
+/// Returns the URL of the document the call hierarchy was prepared in, which is kept in the
+/// `data` of its items. The items may be in modules that aren't open, whereas the requests are
+/// run with the snapshot of an open document.
+fn origin_url(item: &types::CallHierarchyItem) -> Cow<'_, lsp_types::Url> {
+    item.data
+        .clone()
+        .and_then(|data| serde_json::from_value(data).ok())
+        .map_or(Cow::Borrowed(&item.uri), Cow::Owned)
+}
+
+/// Returns the module of `item` and the range of its name, reading the module from disk
+/// unless it's open.
+fn item_module(
+    snapshot: &DocumentSnapshot,
+    resolver: &mut DefinitionResolver,
+    item: &types::CallHierarchyItem,
+) -> Option<(Arc<ParsedModule>, TextRange)> {
+    let path = snapshot.url().to_file_path().ok()?;
+    resolver.insert(Arc::new(ParsedModule::from_document(
+        path,
+        snapshot.document(),
+    )));
+
+    let module = resolver.module(&item.uri.to_file_path().ok()?)?;
+    let selection_range =
+        item.selection_range
+            .to_text_range(module.source(), module.index(), snapshot.encoding());
+    Some((module, selection_range))
+}
+
+fn to_lsp_item(
+    item: &CallHierarchyItem,
+    origin: &lsp_types::Url,
+    encoding: PositionEncoding,
+) -> Option<types::CallHierarchyItem> {
+    let module = &item.module;
+    Some(types::CallHierarchyItem {
+        name: item.name.clone(),
+        kind: item.kind,
+        tags: None,
+        detail: item.detail.clone(),
+        uri: lsp_types::Url::from_file_path(module.path()).ok()?,
+        range: item
+            .range
+            .to_range(module.source(), module.index(), encoding),
+        selection_range: item
+            .selection_range
+            .to_range(module.source(), module.index(), encoding),
+        data: serde_json::to_value(origin).ok(),
+    })
+}
+
+fn to_lsp_ranges(
+    module: &ParsedModule,
+    ranges: &[TextRange],
+    encoding: PositionEncoding,
+) -> Vec<types::Range> {
+    ranges
+        .iter()
+        .map(|range| range.to_range(module.source(), module.index(), encoding))
+        .collect()
+}
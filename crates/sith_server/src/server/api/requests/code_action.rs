@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+
+use ruff_source_file::LineIndex;
+
+use crate::analysis::{import_fixes, organize_imports, Edit, ImportFix, ParsedModule};
+use crate::edit::{RangeExt, Replacement, ToRangeExt};
+use crate::lint;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use crate::SOURCE_FIX_ALL_SITH;
+use lsp_types::{self as types, request as req, CodeActionKind};
+
+pub(crate) struct CodeActions;
+
+impl super::RequestHandler for CodeActions {
+    type RequestType = req::CodeActionRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for CodeActions {
+    super::define_document_url!(params: &types::CodeActionParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::CodeActionParams,
+    ) -> Result<Option<types::CodeActionResponse>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+        let only = params.context.only.as_deref();
+
+        let document = snapshot.document();
+        let module = ParsedModule::from_document(path, document);
+        let mut actions = Vec::new();
+
+        if is_requested(only, &CodeActionKind::QUICKFIX) {
+            let range = params.range.to_text_range(
+                document.contents(),
+                document.index(),
+                snapshot.encoding(),
+            );
+            let fixes = import_fixes(
+                &module,
+                snapshot.import_resolver(),
+                snapshot.symbol_index(),
+                range,
+            );
+            let is_preferred = fixes.len() == 1;
+            actions.extend(fixes.into_iter().map(|fix| {
+                import_action(&snapshot, &params.context.diagnostics, fix, is_preferred)
+            }));
+
+            actions.extend(
+                lint::fixable(&module, snapshot.encoding())
+                    .into_iter()
+                    .filter(|(diagnostic, _)| {
+                        diagnostic.range.start <= params.range.end
+                            && params.range.start <= diagnostic.range.end
+                    })
+                    .map(|(diagnostic, fix)| types::CodeAction {
+                        title: fix.title,
+                        kind: Some(CodeActionKind::QUICKFIX),
+                        diagnostics: Some(vec![diagnostic]),
+                        edit: Some(workspace_edit(&snapshot, fix.edits)),
+                        is_preferred: Some(true),
+                        ..Default::default()
+                    }),
+            );
+        }
+
+        if is_requested(only, &SOURCE_FIX_ALL_SITH) {
+            let edits = lint::fix_all(&module);
+            if !edits.is_empty() {
+                actions.push(types::CodeAction {
+                    title: format!("{}: Fix all auto-fixable problems", crate::DIAGNOSTIC_NAME),
+                    kind: Some(SOURCE_FIX_ALL_SITH),
+                    edit: Some(workspace_edit(&snapshot, edits)),
+                    ..Default::default()
+                });
+            }
+        }
+
+        if is_requested(only, &CodeActionKind::SOURCE_ORGANIZE_IMPORTS) {
+            if let Some(organized) = organize_imports(&module, snapshot.import_resolver()) {
+                let organized_index = LineIndex::from_source_text(&organized);
+                let replacement = Replacement::between(
+                    document.contents(),
+                    document.index().line_starts(),
+                    &organized,
+                    organized_index.line_starts(),
+                );
+                let edit = Edit::replacement(
+                    organized[replacement.modified_range].to_string(),
+                    replacement.source_range,
+                );
+                actions.push(types::CodeAction {
+                    title: format!("{}: Organize imports", crate::DIAGNOSTIC_NAME),
+                    kind: Some(CodeActionKind::SOURCE_ORGANIZE_IMPORTS),
+                    edit: Some(workspace_edit(&snapshot, vec![edit])),
+                    ..Default::default()
+                });
+            }
+        }
+
+        if actions.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            actions
+                .into_iter()
+                .map(types::CodeActionOrCommand::CodeAction)
+                .collect(),
+        ))
+    }
+}
+
+/// Returns `true` if the client asked for actions of `kind`, either explicitly or through one
+/// of its parents, e.g. `source` for `source.organizeImports`.
+fn is_requested(only: Option<&[CodeActionKind]>, kind: &CodeActionKind) -> bool {
+    let Some(only) = only else {
+        return true;
+    };
+    only.iter().any(|requested| {
+        kind.as_str()
+            .strip_prefix(requested.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    })
+}
+
+/// Returns the quick fix importing an undefined name, which resolves the diagnostics reported
+/// at the name.
+fn import_action(
+    snapshot: &DocumentSnapshot,
+    diagnostics: &[types::Diagnostic],
+    fix: ImportFix,
+    is_preferred: bool,
+) -> types::CodeAction {
+    let document = snapshot.document();
+    let name_range = fix
+        .range
+        .to_range(document.contents(), document.index(), snapshot.encoding());
+
+    let title = if fix.is_from_import {
+        format!("Import `{}` from `{}`", fix.name, fix.module)
+    } else {
+        format!("Add `import {}`", fix.module)
+    };
+
+    types::CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.range == name_range)
+                .cloned()
+                .collect(),
+        ),
+        edit: Some(workspace_edit(snapshot, vec![fix.edit])),
+        is_preferred: Some(is_preferred),
+        ..Default::default()
+    }
+}
+
+/// Returns the workspace edit applying `edits` to the snapshot's document.
+fn workspace_edit(snapshot: &DocumentSnapshot, edits: Vec<Edit>) -> types::WorkspaceEdit {
+    let document = snapshot.document();
+    let edits = edits
+        .into_iter()
+        .map(|edit| types::TextEdit {
+            range: edit
+                .range
+                .to_range(document.contents(), document.index(), snapshot.encoding()),
+            new_text: edit.content,
+        })
+        .collect();
+
+    types::WorkspaceEdit {
+        changes: Some(HashMap::from([(snapshot.url().clone(), edits)])),
+        ..Default::default()
+    }
+}
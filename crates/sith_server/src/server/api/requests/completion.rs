@@ -0,0 +1,56 @@
+use std::sync::Arc;
+
+use crate::analysis::{completions, DefinitionResolver, ParsedModule};
+use crate::edit::position_to_offset;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+pub(crate) struct Completion;
+
+impl super::RequestHandler for Completion {
+    type RequestType = req::Completion;
+}
+
+impl super::BackgroundDocumentRequestHandler for Completion {
+    fn document_url(params: &types::CompletionParams) -> std::borrow::Cow<lsp_types::Url> {
+        std::borrow::Cow::Borrowed(&params.text_document_position.text_document.uri)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::CompletionParams,
+    ) -> Result<Option<types::CompletionResponse>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.text_document_position.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver());
+        resolver.insert(module.clone());
+
+        let items: Vec<_> = completions(&mut resolver, &module, offset)
+            .into_iter()
+            .map(|completion| types::CompletionItem {
+                label: completion.label,
+                kind: Some(completion.kind),
+                ..Default::default()
+            })
+            .collect();
+
+        if items.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(types::CompletionResponse::Array(items)))
+    }
+}
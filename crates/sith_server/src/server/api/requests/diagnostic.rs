@@ -0,0 +1,53 @@
+use crate::server::api::diagnostics::generate_diagnostics;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+use types::{
+    DocumentDiagnosticReport, DocumentDiagnosticReportResult, FullDocumentDiagnosticReport,
+    RelatedFullDocumentDiagnosticReport, RelatedUnchangedDocumentDiagnosticReport,
+    UnchangedDocumentDiagnosticReport,
+};
+
+pub(crate) struct DocumentDiagnostic;
+
+impl super::RequestHandler for DocumentDiagnostic {
+    type RequestType = req::DocumentDiagnosticRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for DocumentDiagnostic {
+    super::define_document_url!(params: &types::DocumentDiagnosticParams);
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        // The diagnostics depend on the document contents, and on the settings and the files
+        // its imports resolve to, which only change along with the diagnostics generation.
+        let result_id = format!(
+            "{}:{}",
+            snapshot.diagnostics_generation(),
+            snapshot.document().version()
+        );
+
+        if params.previous_result_id.as_ref() == Some(&result_id) {
+            return Ok(DocumentDiagnosticReportResult::Report(
+                DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                        result_id,
+                    },
+                }),
+            ));
+        }
+
+        Ok(DocumentDiagnosticReportResult::Report(
+            DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: Some(result_id),
+                    items: generate_diagnostics(&snapshot),
+                },
+            }),
+        ))
+    }
+}
@@ -0,0 +1,68 @@
+use crate::analysis::{document_symbols, Symbol};
+use crate::edit::{Document, ToRangeExt};
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use crate::PositionEncoding;
+use lsp_types::{self as types, request as req};
+use python_ast::PySourceType;
+
+pub(crate) struct DocumentSymbol;
+
+impl super::RequestHandler for DocumentSymbol {
+    type RequestType = req::DocumentSymbolRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for DocumentSymbol {
+    super::define_document_url!(params: &types::DocumentSymbolParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        _params: types::DocumentSymbolParams,
+    ) -> Result<Option<types::DocumentSymbolResponse>> {
+        let document = snapshot.document();
+        let source_type = snapshot
+            .url()
+            .to_file_path()
+            .map(PySourceType::from)
+            .unwrap_or_default();
+        let parsed = python_parser::parse_unchecked_source(document.contents(), source_type);
+
+        let symbols = document_symbols(parsed.syntax())
+            .into_iter()
+            .map(|symbol| to_document_symbol(symbol, document, snapshot.encoding()))
+            .collect();
+
+        Ok(Some(types::DocumentSymbolResponse::Nested(symbols)))
+    }
+}
+
+fn to_document_symbol(
+    symbol: Symbol,
+    document: &Document,
+    encoding: PositionEncoding,
+) -> types::DocumentSymbol {
+    let children = symbol
+        .children
+        .into_iter()
+        .map(|child| to_document_symbol(child, document, encoding))
+        .collect();
+
+    #[allow(deprecated)]
+    types::DocumentSymbol {
+        name: symbol.name,
+        detail: None,
+        kind: symbol.kind,
+        tags: None,
+        deprecated: None,
+        range: symbol
+            .range
+            .to_range(document.contents(), document.index(), encoding),
+        selection_range: symbol.selection_range.to_range(
+            document.contents(),
+            document.index(),
+            encoding,
+        ),
+        children: Some(children),
+    }
+}
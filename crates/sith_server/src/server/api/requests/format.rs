@@ -0,0 +1,87 @@
+use lsp_types::notification::ShowMessage;
+use ruff_source_file::LineIndex;
+
+use crate::edit::{RangeExt, Replacement, ToRangeExt};
+use crate::server::api::LSPResult;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+pub(crate) struct Format;
+
+impl super::RequestHandler for Format {
+    type RequestType = req::Formatting;
+}
+
+impl super::BackgroundDocumentRequestHandler for Format {
+    super::define_document_url!(params: &types::DocumentFormattingParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        notifier: Notifier,
+        _params: types::DocumentFormattingParams,
+    ) -> Result<Option<Vec<types::TextEdit>>> {
+        format_document(&snapshot, &notifier, None)
+    }
+}
+
+/// Formats the document, or only the lines of `range`, with the configured formatter and
+/// returns the edit replacing the lines that changed.
+///
+/// A formatter failure is shown to the user rather than failing the request.
+pub(super) fn format_document(
+    snapshot: &DocumentSnapshot,
+    notifier: &Notifier,
+    range: Option<types::Range>,
+) -> Result<Option<Vec<types::TextEdit>>> {
+    let settings = &snapshot.client_settings().ruff;
+    if !settings.format.enable {
+        return Ok(None);
+    }
+
+    let document = snapshot.document();
+    let source = document.contents();
+    let encoding = snapshot.encoding();
+    let path = snapshot.url().to_file_path().ok();
+    let range = range.map(|range| range.to_text_range(source, document.index(), encoding));
+
+    let formatted = match crate::format::format(
+        source,
+        document.index(),
+        path.as_deref(),
+        range,
+        snapshot.import_resolver().root(),
+        settings,
+    ) {
+        Ok(formatted) => formatted,
+        Err(err) => {
+            tracing::error!("Failed to format {}: {err:#}", snapshot.url());
+            notifier
+                .notify::<ShowMessage>(types::ShowMessageParams {
+                    typ: types::MessageType::ERROR,
+                    message: format!("{err:#}"),
+                })
+                .with_failure_code(lsp_server::ErrorCode::InternalError)?;
+            return Ok(None);
+        }
+    };
+
+    if formatted == source {
+        return Ok(None);
+    }
+
+    let formatted_index = LineIndex::from_source_text(&formatted);
+    let replacement = Replacement::between(
+        source,
+        document.index().line_starts(),
+        &formatted,
+        formatted_index.line_starts(),
+    );
+
+    Ok(Some(vec![types::TextEdit {
+        range: replacement
+            .source_range
+            .to_range(source, document.index(), encoding),
+        new_text: formatted[replacement.modified_range].to_string(),
+    }]))
+}
@@ -0,0 +1,23 @@
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+use super::format::format_document;
+
+pub(crate) struct FormatRange;
+
+impl super::RequestHandler for FormatRange {
+    type RequestType = req::RangeFormatting;
+}
+
+impl super::BackgroundDocumentRequestHandler for FormatRange {
+    super::define_document_url!(params: &types::DocumentRangeFormattingParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        notifier: Notifier,
+        params: types::DocumentRangeFormattingParams,
+    ) -> Result<Option<Vec<types::TextEdit>>> {
+        format_document(&snapshot, &notifier, Some(params.range))
+    }
+}
@@ -0,0 +1,57 @@
+use std::sync::Arc;
+
+use crate::analysis::{DefinitionResolver, ParsedModule};
+use crate::edit::position_to_offset;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+pub(crate) struct GotoDefinition;
+
+impl super::RequestHandler for GotoDefinition {
+    type RequestType = req::GotoDefinition;
+}
+
+impl super::BackgroundDocumentRequestHandler for GotoDefinition {
+    fn document_url(params: &types::GotoDefinitionParams) -> std::borrow::Cow<lsp_types::Url> {
+        std::borrow::Cow::Borrowed(&params.text_document_position_params.text_document.uri)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::GotoDefinitionParams,
+    ) -> Result<Option<types::GotoDefinitionResponse>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.text_document_position_params.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver());
+        resolver.insert(module.clone());
+
+        let locations: Vec<_> = resolver
+            .definitions_at(&module, offset)
+            .into_iter()
+            .filter_map(|definition| {
+                definition
+                    .module
+                    .location(definition.range, snapshot.encoding())
+            })
+            .collect();
+
+        if locations.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(types::GotoDefinitionResponse::Array(locations)))
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use crate::analysis::{self, DefinitionResolver, ParsedModule};
+use crate::edit::{position_to_offset, ToRangeExt};
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+use ruff_python_resolver::import_result::ImportType;
+
+pub(crate) struct Hover;
+
+impl super::RequestHandler for Hover {
+    type RequestType = req::HoverRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for Hover {
+    fn document_url(params: &types::HoverParams) -> std::borrow::Cow<lsp_types::Url> {
+        std::borrow::Cow::Borrowed(&params.text_document_position_params.text_document.uri)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::HoverParams,
+    ) -> Result<Option<types::Hover>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.text_document_position_params.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver());
+        resolver.insert(module.clone());
+
+        let Some(hover) = analysis::hover(&mut resolver, &module, offset) else {
+            return Ok(None);
+        };
+
+        let contents = if snapshot.resolved_client_capabilities().hover_markdown {
+            types::MarkupContent {
+                kind: types::MarkupKind::Markdown,
+                value: markdown(&hover),
+            }
+        } else {
+            types::MarkupContent {
+                kind: types::MarkupKind::PlainText,
+                value: plain_text(&hover),
+            }
+        };
+
+        Ok(Some(types::Hover {
+            contents: types::HoverContents::Markup(contents),
+            range: Some(hover.range.to_range(
+                document.contents(),
+                document.index(),
+                snapshot.encoding(),
+            )),
+        }))
+    }
+}
+
+fn markdown(hover: &analysis::Hover) -> String {
+    let mut sections = vec![format!("```python\n{}\n```", hover.signature)];
+    if let Some(documentation) = &hover.documentation {
+        sections.push(documentation.clone());
+    }
+    if let Some((path, import_type)) = &hover.import {
+        sections.push(format!(
+            "Resolved to `{}` ({})",
+            path.display(),
+            import_type_label(*import_type)
+        ));
+    }
+    sections.join("\n\n---\n\n")
+}
+
+fn plain_text(hover: &analysis::Hover) -> String {
+    let mut sections = vec![hover.signature.clone()];
+    if let Some(documentation) = &hover.documentation {
+        sections.push(documentation.clone());
+    }
+    if let Some((path, import_type)) = &hover.import {
+        sections.push(format!(
+            "Resolved to {} ({})",
+            path.display(),
+            import_type_label(*import_type)
+        ));
+    }
+    sections.join("\n\n")
+}
+
+fn import_type_label(import_type: ImportType) -> &'static str {
+    match import_type {
+        ImportType::BuiltIn => "built-in",
+        ImportType::ThirdParty => "third-party",
+        ImportType::Local => "local",
+    }
+}
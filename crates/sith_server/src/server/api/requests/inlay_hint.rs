@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use crate::analysis::{self, DefinitionResolver, InlayHintKind, ParsedModule};
+use crate::edit::{RangeExt, ToLocation, ToRangeExt};
+use crate::server::{
+    client::{Notifier, Requester},
+    Result,
+};
+use crate::session::{DocumentSnapshot, Session};
+use lsp_types::{self as types, request as req};
+use ruff_text_size::TextRange;
+
+pub(crate) struct InlayHints;
+
+impl super::RequestHandler for InlayHints {
+    type RequestType = req::InlayHintRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for InlayHints {
+    super::define_document_url!(params: &types::InlayHintParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::InlayHintParams,
+    ) -> Result<Option<Vec<types::InlayHint>>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let encoding = snapshot.encoding();
+        let range = params
+            .range
+            .to_text_range(document.contents(), document.index(), encoding);
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver());
+        resolver.insert(module.clone());
+
+        let settings = &snapshot.client_settings().inlay_hints;
+        let hints = analysis::inlay_hints(&mut resolver, &module, range, settings)
+            .into_iter()
+            .map(|hint| {
+                // The location of the parameter is only attached when the hint is resolved.
+                let data = hint.target.and_then(|(target, range)| {
+                    let uri = types::Url::from_file_path(target.path()).ok()?;
+                    let location =
+                        range.to_location(uri, target.source(), target.index(), encoding);
+                    serde_json::to_value(location).ok()
+                });
+
+                types::InlayHint {
+                    position: TextRange::empty(hint.offset)
+                        .to_range(document.contents(), document.index(), encoding)
+                        .start,
+                    label: types::InlayHintLabel::String(hint.label),
+                    kind: Some(match hint.kind {
+                        InlayHintKind::Parameter => types::InlayHintKind::PARAMETER,
+                        InlayHintKind::Type => types::InlayHintKind::TYPE,
+                    }),
+                    text_edits: None,
+                    tooltip: None,
+                    padding_left: None,
+                    padding_right: None,
+                    data,
+                }
+            })
+            .collect();
+
+        Ok(Some(hints))
+    }
+}
+
+pub(crate) struct InlayHintResolve;
+
+impl super::RequestHandler for InlayHintResolve {
+    type RequestType = req::InlayHintResolveRequest;
+}
+
+impl super::SyncRequestHandler for InlayHintResolve {
+    fn run(
+        _session: &mut Session,
+        _notifier: Notifier,
+        _requester: &mut Requester,
+        mut hint: types::InlayHint,
+    ) -> Result<types::InlayHint> {
+        let location = hint
+            .data
+            .take()
+            .and_then(|data| serde_json::from_value::<types::Location>(data).ok());
+
+        // Turning the label into a part with a location makes it navigable to the parameter.
+        if let (Some(location), types::InlayHintLabel::String(label)) = (location, &hint.label) {
+            hint.label = types::InlayHintLabel::LabelParts(vec![types::InlayHintLabelPart {
+                value: label.clone(),
+                tooltip: None,
+                location: Some(location),
+                command: None,
+            }]);
+        }
+
+        Ok(hint)
+    }
+}
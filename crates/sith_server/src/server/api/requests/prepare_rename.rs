@@ -0,0 +1,51 @@
+use std::sync::Arc;
+
+use crate::analysis::{prepare_rename, DefinitionResolver, ParsedModule};
+use crate::edit::{position_to_offset, ToRangeExt};
+use crate::server::api::LSPResult;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+pub(crate) struct PrepareRename;
+
+impl super::RequestHandler for PrepareRename {
+    type RequestType = req::PrepareRenameRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for PrepareRename {
+    super::define_document_url!(params: &types::TextDocumentPositionParams);
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::TextDocumentPositionParams,
+    ) -> Result<Option<types::PrepareRenameResponse>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver());
+        resolver.insert(module.clone());
+
+        let range = prepare_rename(&mut resolver, &module, offset)
+            .map_err(|err| anyhow::anyhow!("{err}"))
+            .with_failure_code(lsp_server::ErrorCode::RequestFailed)?;
+
+        Ok(range.map(|range| {
+            types::PrepareRenameResponse::Range(range.to_range(
+                document.contents(),
+                document.index(),
+                snapshot.encoding(),
+            ))
+        }))
+    }
+}
@@ -0,0 +1,61 @@
+use std::sync::Arc;
+
+use crate::analysis::{find_references, python_files, DefinitionResolver, ParsedModule};
+use crate::edit::position_to_offset;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+pub(crate) struct References;
+
+impl super::RequestHandler for References {
+    type RequestType = req::References;
+}
+
+impl super::BackgroundDocumentRequestHandler for References {
+    fn document_url(params: &types::ReferenceParams) -> std::borrow::Cow<lsp_types::Url> {
+        std::borrow::Cow::Borrowed(&params.text_document_position.text_document.uri)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::ReferenceParams,
+    ) -> Result<Option<Vec<types::Location>>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.text_document_position.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver())
+            .with_documents(snapshot.workspace_documents());
+        resolver.insert(module.clone());
+
+        let paths = python_files(snapshot.import_resolver().root());
+        let include_declaration = params.context.include_declaration;
+
+        let locations: Vec<_> = find_references(&mut resolver, &module, offset, &paths)
+            .into_iter()
+            .filter(|reference| include_declaration || !reference.is_declaration)
+            .filter_map(|reference| {
+                reference
+                    .module
+                    .location(reference.range, snapshot.encoding())
+            })
+            .collect();
+
+        if locations.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(locations))
+    }
+}
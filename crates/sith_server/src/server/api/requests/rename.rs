@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::analysis::{python_files, rename, DefinitionResolver, ParsedModule, RenameError};
+use crate::edit::position_to_offset;
+use crate::server::api::LSPResult;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+pub(crate) struct Rename;
+
+impl super::RequestHandler for Rename {
+    type RequestType = req::Rename;
+}
+
+impl super::BackgroundDocumentRequestHandler for Rename {
+    fn document_url(params: &types::RenameParams) -> std::borrow::Cow<lsp_types::Url> {
+        std::borrow::Cow::Borrowed(&params.text_document_position.text_document.uri)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::RenameParams,
+    ) -> Result<Option<types::WorkspaceEdit>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.text_document_position.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver())
+            .with_documents(snapshot.workspace_documents());
+        resolver.insert(module.clone());
+
+        let paths = python_files(snapshot.import_resolver().root());
+        let edits = match rename(&mut resolver, &module, offset, &params.new_name, &paths) {
+            Ok(edits) => edits,
+            Err(err @ RenameError::InvalidName(_)) => {
+                return Err(anyhow::anyhow!("{err}"))
+                    .with_failure_code(lsp_server::ErrorCode::InvalidParams);
+            }
+            Err(err) => {
+                return Err(anyhow::anyhow!("{err}"))
+                    .with_failure_code(lsp_server::ErrorCode::RequestFailed);
+            }
+        };
+
+        if edits.is_empty() {
+            return Ok(None);
+        }
+
+        let mut changes: HashMap<types::Url, Vec<types::TextEdit>> = HashMap::new();
+        for (module, range) in edits {
+            let Some(location) = module.location(range, snapshot.encoding()) else {
+                continue;
+            };
+            changes
+                .entry(location.uri)
+                .or_default()
+                .push(types::TextEdit {
+                    range: location.range,
+                    new_text: params.new_name.clone(),
+                });
+        }
+
+        Ok(Some(types::WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }))
+    }
+}
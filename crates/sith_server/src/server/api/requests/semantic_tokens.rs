@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use crate::analysis::{self, DefinitionResolver, ParsedModule};
+use crate::edit::{RangeExt, ToRangeExt};
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+use ruff_text_size::{TextRange, TextSize};
+
+pub(crate) struct SemanticTokensFull;
+
+impl super::RequestHandler for SemanticTokensFull {
+    type RequestType = req::SemanticTokensFullRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for SemanticTokensFull {
+    super::define_document_url!(params: &types::SemanticTokensParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        _params: types::SemanticTokensParams,
+    ) -> Result<Option<types::SemanticTokensResult>> {
+        let Some(data) = semantic_tokens(&snapshot, None) else {
+            return Ok(None);
+        };
+        let result_id = snapshot
+            .semantic_tokens()
+            .insert(snapshot.url(), data.clone());
+
+        Ok(Some(types::SemanticTokensResult::Tokens(
+            types::SemanticTokens {
+                result_id: Some(result_id),
+                data,
+            },
+        )))
+    }
+}
+
+pub(crate) struct SemanticTokensRange;
+
+impl super::RequestHandler for SemanticTokensRange {
+    type RequestType = req::SemanticTokensRangeRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for SemanticTokensRange {
+    super::define_document_url!(params: &types::SemanticTokensRangeParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::SemanticTokensRangeParams,
+    ) -> Result<Option<types::SemanticTokensRangeResult>> {
+        let Some(data) = semantic_tokens(&snapshot, Some(params.range)) else {
+            return Ok(None);
+        };
+
+        Ok(Some(types::SemanticTokensRangeResult::Tokens(
+            types::SemanticTokens {
+                result_id: None,
+                data,
+            },
+        )))
+    }
+}
+
+pub(crate) struct SemanticTokensDelta;
+
+impl super::RequestHandler for SemanticTokensDelta {
+    type RequestType = req::SemanticTokensFullDeltaRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for SemanticTokensDelta {
+    super::define_document_url!(params: &types::SemanticTokensDeltaParams);
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::SemanticTokensDeltaParams,
+    ) -> Result<Option<types::SemanticTokensFullDeltaResult>> {
+        let Some(data) = semantic_tokens(&snapshot, None) else {
+            return Ok(None);
+        };
+        let cache = snapshot.semantic_tokens();
+        let previous = cache.get(snapshot.url(), &params.previous_result_id);
+        let result_id = Some(cache.insert(snapshot.url(), data.clone()));
+
+        // Send all the tokens if the client's result isn't the last one sent.
+        let Some(previous) = previous else {
+            return Ok(Some(types::SemanticTokensFullDeltaResult::Tokens(
+                types::SemanticTokens { result_id, data },
+            )));
+        };
+
+        Ok(Some(types::SemanticTokensFullDeltaResult::TokensDelta(
+            types::SemanticTokensDelta {
+                result_id,
+                edits: edits(&previous, &data),
+            },
+        )))
+    }
+}
+
+/// Returns the encoded semantic tokens of the document, or of the tokens in `range`.
+fn semantic_tokens(
+    snapshot: &DocumentSnapshot,
+    range: Option<types::Range>,
+) -> Option<Vec<types::SemanticToken>> {
+    let path = snapshot.url().to_file_path().ok()?;
+    let document = snapshot.document();
+    let encoding = snapshot.encoding();
+
+    let range = match range {
+        Some(range) => range.to_text_range(document.contents(), document.index(), encoding),
+        None => TextRange::up_to(TextSize::of(document.contents())),
+    };
+
+    let module = Arc::new(ParsedModule::from_document(path, document));
+    let mut resolver = DefinitionResolver::new(snapshot.import_resolver());
+    resolver.insert(module.clone());
+
+    let mut data = Vec::new();
+    let mut previous = types::Position::default();
+    for token in analysis::semantic_tokens(&mut resolver, &module, range) {
+        let range = token
+            .range
+            .to_range(document.contents(), document.index(), encoding);
+        // Names don't span multiple lines.
+        let delta_start = if range.start.line == previous.line {
+            range.start.character - previous.character
+        } else {
+            range.start.character
+        };
+        data.push(types::SemanticToken {
+            delta_line: range.start.line - previous.line,
+            delta_start,
+            length: range.end.character - range.start.character,
+            token_type: token.token_type as u32,
+            token_modifiers_bitset: token.modifiers,
+        });
+        previous = range.start;
+    }
+
+    Some(data)
+}
+
+/// Returns the edit turning the `previous` tokens into the `current` ones, replacing the
+/// tokens between their common prefix and suffix.
+fn edits(
+    previous: &[types::SemanticToken],
+    current: &[types::SemanticToken],
+) -> Vec<types::SemanticTokensEdit> {
+    let prefix = previous
+        .iter()
+        .zip(current)
+        .take_while(|(previous, current)| previous == current)
+        .count();
+    if prefix == previous.len() && prefix == current.len() {
+        return Vec::new();
+    }
+    let suffix = previous[prefix..]
+        .iter()
+        .rev()
+        .zip(current[prefix..].iter().rev())
+        .take_while(|(previous, current)| previous == current)
+        .count();
+
+    // The edits index the integers of the encoded tokens, five per token.
+    vec![types::SemanticTokensEdit {
+        start: (prefix * 5) as u32,
+        delete_count: ((previous.len() - prefix - suffix) * 5) as u32,
+        data: Some(current[prefix..current.len() - suffix].to_vec()),
+    }]
+}
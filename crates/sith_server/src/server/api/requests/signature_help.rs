@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use crate::analysis::{self, DefinitionResolver, ParsedModule};
+use crate::edit::position_to_offset;
+use crate::server::{client::Notifier, Result};
+use crate::session::DocumentSnapshot;
+use lsp_types::{self as types, request as req};
+
+pub(crate) struct SignatureHelp;
+
+impl super::RequestHandler for SignatureHelp {
+    type RequestType = req::SignatureHelpRequest;
+}
+
+impl super::BackgroundDocumentRequestHandler for SignatureHelp {
+    fn document_url(params: &types::SignatureHelpParams) -> std::borrow::Cow<lsp_types::Url> {
+        std::borrow::Cow::Borrowed(&params.text_document_position_params.text_document.uri)
+    }
+
+    fn run_with_snapshot(
+        snapshot: DocumentSnapshot,
+        _notifier: Notifier,
+        params: types::SignatureHelpParams,
+    ) -> Result<Option<types::SignatureHelp>> {
+        let Ok(path) = snapshot.url().to_file_path() else {
+            return Ok(None);
+        };
+
+        let document = snapshot.document();
+        let offset = position_to_offset(
+            params.text_document_position_params.position,
+            document.contents(),
+            document.index(),
+            snapshot.encoding(),
+        );
+
+        let module = Arc::new(ParsedModule::from_document(path, document));
+        let mut resolver = DefinitionResolver::new(snapshot.import_resolver());
+        resolver.insert(module.clone());
+
+        let Some(help) = analysis::signature_help(&mut resolver, &module, offset) else {
+            return Ok(None);
+        };
+
+        let label_offsets = snapshot
+            .resolved_client_capabilities()
+            .signature_label_offsets;
+        let parameters = help
+            .parameters
+            .iter()
+            .map(|&range| {
+                let label = if label_offsets {
+                    // The offsets are in UTF-16 code units, regardless of the position encoding.
+                    let start = help.label[..range.start().to_usize()]
+                        .encode_utf16()
+                        .count();
+                    let length = help.label[range].encode_utf16().count();
+                    types::ParameterLabel::LabelOffsets([start as u32, (start + length) as u32])
+                } else {
+                    types::ParameterLabel::Simple(help.label[range].to_string())
+                };
+                types::ParameterInformation {
+                    label,
+                    documentation: None,
+                }
+            })
+            .collect();
+        let active_parameter = help.active_parameter.map(|index| index as u32);
+
+        Ok(Some(types::SignatureHelp {
+            signatures: vec![types::SignatureInformation {
+                label: help.label,
+                documentation: help.documentation.map(types::Documentation::String),
+                parameters: Some(parameters),
+                active_parameter,
+            }],
+            active_signature: Some(0),
+            active_parameter,
+        }))
+    }
+}
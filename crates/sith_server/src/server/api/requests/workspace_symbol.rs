@@ -0,0 +1,59 @@
+use crate::server::{client::Notifier, Result};
+use crate::session::{fuzzy_score, IndexSnapshot};
+use lsp_types::{self as types, request as req};
+
+/// The maximum number of symbols returned for a query, the best matches are kept.
+const MAX_SYMBOLS: usize = 256;
+
+pub(crate) struct WorkspaceSymbol;
+
+impl super::RequestHandler for WorkspaceSymbol {
+    type RequestType = req::WorkspaceSymbolRequest;
+}
+
+impl super::BackgroundIndexRequestHandler for WorkspaceSymbol {
+    fn run_with_snapshot(
+        snapshot: IndexSnapshot,
+        _notifier: Notifier,
+        params: types::WorkspaceSymbolParams,
+    ) -> Result<Option<types::WorkspaceSymbolResponse>> {
+        let query = params.query.as_str();
+        let mut matches: Vec<_> = snapshot
+            .indexed_files()
+            .flat_map(|(path, file)| {
+                file.symbols.iter().filter_map(move |symbol| {
+                    let score = fuzzy_score(query, &symbol.name)?;
+                    Some((score, path, file, symbol))
+                })
+            })
+            .collect();
+
+        matches.sort_by(|(score_a, _, _, a), (score_b, _, _, b)| {
+            score_b
+                .cmp(score_a)
+                .then_with(|| a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        matches.truncate(MAX_SYMBOLS);
+
+        #[allow(deprecated)]
+        let symbols = matches
+            .into_iter()
+            .filter_map(|(_, path, file, symbol)| {
+                Some(types::SymbolInformation {
+                    name: symbol.name.clone(),
+                    kind: symbol.kind,
+                    tags: None,
+                    deprecated: None,
+                    location: types::Location {
+                        uri: types::Url::from_file_path(path).ok()?,
+                        range: symbol.range,
+                    },
+                    container_name: Some(file.module.clone()),
+                })
+            })
+            .collect();
+
+        Ok(Some(types::WorkspaceSymbolResponse::Flat(symbols)))
+    }
+}
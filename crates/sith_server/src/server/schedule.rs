@@ -1,7 +1,11 @@
 use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::time::Instant;
 
 use crossbeam::channel::Sender;
 
+use lsp_types::Url;
+
 use crate::session::Session;
 
 mod task;
@@ -70,6 +74,21 @@ impl<'scheduler> Scheduler<'scheduler> {
         self.client.requester.request::<R>(params, response_handler)
     }
 
+    /// Returns the time at which the next pending diagnostics are due.
+    pub(super) fn diagnostics_deadline(&self) -> Option<Instant> {
+        self.session.diagnostics_deadline()
+    }
+
+    /// Removes and returns the documents whose pending diagnostics are due.
+    pub(super) fn take_due_diagnostics(&mut self) -> Vec<Url> {
+        self.session.take_due_diagnostics(Instant::now())
+    }
+
+    /// Removes and returns the roots of the workspaces whose symbol index should be built.
+    pub(super) fn take_unindexed_workspaces(&mut self) -> Vec<PathBuf> {
+        self.session.take_unindexed_workspaces()
+    }
+
     /// Creates a task to handle a response from the client.
     pub(super) fn response(&mut self, response: lsp_server::Response) -> Task<'scheduler> {
         self.client.requester.pop_response_task(response)
@@ -80,10 +99,13 @@ impl<'scheduler> Scheduler<'scheduler> {
     pub(super) fn dispatch(&mut self, task: task::Task<'scheduler>) {
         match task {
             Task::Sync(SyncTask { func }) => {
+                let notifier = self.client.notifier();
+                let responder = self.client.responder();
                 func(
                     self.session,
-                    self.client.notifier(),
-                    self.client.responder(),
+                    notifier,
+                    &mut self.client.requester,
+                    responder,
                 );
             }
             Task::Background(BackgroundTaskBuilder {
@@ -106,6 +128,11 @@ impl<'scheduler> Scheduler<'scheduler> {
                     }
                 }
             }
+            Task::Sequence(tasks) => {
+                for task in tasks {
+                    self.dispatch(task);
+                }
+            }
         }
     }
 }
@@ -2,11 +2,11 @@ use lsp_server::RequestId;
 use serde::Serialize;
 
 use crate::{
-    server::client::{Notifier, Responder},
+    server::client::{Notifier, Requester, Responder},
     session::Session,
 };
 
-type LocalFn<'s> = Box<dyn FnOnce(&mut Session, Notifier, Responder) + 's>;
+type LocalFn<'s> = Box<dyn FnOnce(&mut Session, Notifier, &mut Requester, Responder) + 's>;
 
 type BackgroundFn = Box<dyn FnOnce(Notifier, Responder) + Send + 'static>;
 
@@ -37,6 +37,8 @@ pub(in crate::server) enum BackgroundSchedule {
 pub(in crate::server) enum Task<'s> {
     Background(BackgroundTaskBuilder<'s>),
     Sync(SyncTask<'s>),
+    /// A list of tasks that are dispatched one after the other, in order.
+    Sequence(Vec<Task<'s>>),
 }
 
 // The reason why this isn't just a 'static background closure
@@ -68,7 +70,9 @@ impl<'s> Task<'s> {
         })
     }
     /// Creates a new local task.
-    pub(crate) fn local(func: impl FnOnce(&mut Session, Notifier, Responder) + 's) -> Self {
+    pub(crate) fn local(
+        func: impl FnOnce(&mut Session, Notifier, &mut Requester, Responder) + 's,
+    ) -> Self {
         Self::Sync(SyncTask {
             func: Box::new(func),
         })
@@ -79,7 +83,7 @@ impl<'s> Task<'s> {
     where
         R: Serialize + Send + 'static,
     {
-        Self::local(move |_, _, responder| {
+        Self::local(move |_, _, _, responder| {
             if let Err(err) = responder.respond(id, result) {
                 tracing::error!("Unable to send immediate response: {err}");
             }
@@ -87,6 +91,18 @@ impl<'s> Task<'s> {
     }
     /// Creates a local task that does nothing.
     pub(crate) fn nothing() -> Self {
-        Self::local(move |_, _, _| {})
+        Self::local(move |_, _, _, _| {})
+    }
+    /// Creates a task that dispatches `self` and then `next`. Since local tasks run
+    /// immediately, a background task chained after a local task takes its snapshot
+    /// of the session *after* the local task made its changes.
+    pub(crate) fn then(self, next: Task<'s>) -> Self {
+        match self {
+            Self::Sequence(mut tasks) => {
+                tasks.push(next);
+                Self::Sequence(tasks)
+            }
+            task => Self::Sequence(vec![task, next]),
+        }
     }
 }
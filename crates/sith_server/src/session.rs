@@ -1,21 +1,31 @@
 //! Data model, state management, and configuration resolution.
 
+mod index;
+mod semantic_tokens;
 mod settings;
 
 use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 use std::{ops::Deref, sync::Arc};
 
 use anyhow::{anyhow, Context};
-use lsp_types::{ClientCapabilities, ServerCapabilities, Url};
-use python_utils::get_python_search_paths;
+use lsp_types::{ClientCapabilities, FileChangeType, ServerCapabilities, Url};
+use python_utils::PythonHost;
 use rustc_hash::FxHashMap;
 use semantic_model::db::Source;
 
 use crate::edit::{Document, DocumentVersion};
+use crate::resolve::{ImportResolver, ProjectOptions, CONFIGURATION_FILES};
 use crate::PositionEncoding;
 
+pub(crate) use self::index::SymbolIndex;
+pub(crate) use self::index::{fuzzy_score, is_python_file, IndexedFile};
+use self::semantic_tokens::SemanticTokensCache;
 use self::settings::ResolvedClientCapabilities;
+pub(crate) use self::settings::{
+    ClientSettings, InlayHintsSettings, ResolvedClientSettings, RuffSettings,
+};
 
 /// The global state for the LSP
 pub(crate) struct Session {
@@ -25,14 +35,38 @@ pub(crate) struct Session {
     position_encoding: PositionEncoding,
     /// Tracks what LSP features the client supports and doesn't support.
     resolved_client_capabilities: Arc<ResolvedClientCapabilities>,
+    /// The settings sent by the client that aren't specific to a workspace folder, the
+    /// defaults of the folders whose settings weren't pulled.
+    global_settings: ClientSettings,
+    /// The semantic tokens last sent for each open document.
+    semantic_tokens: Arc<SemanticTokensCache>,
+    /// The open documents whose diagnostics are published once their deadline passes, when
+    /// the client doesn't pull them.
+    pending_diagnostics: FxHashMap<Url, Instant>,
+    /// The roots of the workspaces whose symbol index hasn't started building yet.
+    unindexed_workspaces: Vec<PathBuf>,
+    /// Bumped whenever a change outside of the open documents, e.g. to the settings, to a
+    /// configuration file or to a Python file on disk, may change their diagnostics.
+    diagnostics_generation: u64,
+}
+
+/// An immutable snapshot of the symbol indexes of every workspace.
+pub(crate) struct IndexSnapshot {
+    symbol_indexes: Vec<Arc<SymbolIndex>>,
 }
 
 /// An immutable snapshot of `Session` that references
 /// a specific document.
 pub(crate) struct DocumentSnapshot {
-    // TODO: add configuration field here
     resolved_client_capabilities: Arc<ResolvedClientCapabilities>,
+    client_settings: Arc<ResolvedClientSettings>,
+    import_resolver: Arc<ImportResolver>,
+    symbol_index: Arc<SymbolIndex>,
+    semantic_tokens: Arc<SemanticTokensCache>,
+    diagnostics_generation: u64,
     document_ref: DocumentRef,
+    /// The documents open in the same workspace, including this one.
+    workspace_documents: Vec<(Url, DocumentRef)>,
     position_encoding: PositionEncoding,
     url: Url,
 }
@@ -43,7 +77,12 @@ pub(crate) struct Workspaces(BTreeMap<PathBuf, Workspace>);
 #[derive(Debug)]
 pub(crate) struct Workspace {
     open_documents: OpenDocuments,
-    // TODO: add configuration field here
+    import_resolver: Arc<ImportResolver>,
+    symbol_index: Arc<SymbolIndex>,
+    /// The changes to the workspace's files made while its symbol index is being built, they
+    /// are applied to the index once it's built. `None` when the index isn't being built.
+    pending_index_changes: Option<Vec<(PathBuf, FileChangeType)>>,
+    settings: Arc<ResolvedClientSettings>,
 }
 
 #[derive(Default, Debug)]
@@ -70,24 +109,41 @@ impl Session {
         client_capabilities: &ClientCapabilities,
         server_capabilities: &ServerCapabilities,
         workspaces: &[Url],
+        global_settings: ClientSettings,
     ) -> crate::Result<Self> {
+        let position_encoding = server_capabilities
+            .position_encoding
+            .as_ref()
+            .and_then(|encoding| encoding.try_into().ok())
+            .unwrap_or_default();
+
+        let workspaces = Workspaces::new(workspaces, position_encoding, &global_settings)?;
+
         Ok(Self {
-            position_encoding: server_capabilities
-                .position_encoding
-                .as_ref()
-                .and_then(|encoding| encoding.try_into().ok())
-                .unwrap_or_default(),
+            position_encoding,
             resolved_client_capabilities: Arc::new(ResolvedClientCapabilities::new(
                 client_capabilities,
             )),
-            workspaces: Workspaces::new(workspaces)?,
+            unindexed_workspaces: workspaces.0.keys().cloned().collect(),
+            workspaces,
+            global_settings,
+            semantic_tokens: Arc::default(),
+            pending_diagnostics: FxHashMap::default(),
+            diagnostics_generation: 0,
         })
     }
 
     pub(crate) fn take_snapshot(&self, url: &Url) -> Option<DocumentSnapshot> {
+        let workspace = self.workspaces.workspace_for_url(url)?;
         Some(DocumentSnapshot {
             resolved_client_capabilities: self.resolved_client_capabilities.clone(),
-            document_ref: self.workspaces.document_snapshot(url)?,
+            client_settings: workspace.settings.clone(),
+            import_resolver: workspace.import_resolver.clone(),
+            symbol_index: workspace.symbol_index.clone(),
+            semantic_tokens: self.semantic_tokens.clone(),
+            diagnostics_generation: self.diagnostics_generation,
+            document_ref: workspace.open_documents.snapshot(url)?,
+            workspace_documents: workspace.open_documents.snapshots(),
             position_encoding: self.position_encoding,
             url: url.clone(),
         })
@@ -99,9 +155,51 @@ impl Session {
 
     pub(crate) fn close_document(&mut self, url: &Url) -> crate::Result<()> {
         self.workspaces.close(url)?;
+        self.semantic_tokens.remove(url);
+        self.pending_diagnostics.remove(url);
         Ok(())
     }
 
+    /// Schedules the publication of the diagnostics of the document at `url` at `deadline`,
+    /// replacing the publication that was pending for it.
+    pub(crate) fn schedule_diagnostics(&mut self, url: &Url, deadline: Instant) {
+        self.pending_diagnostics.insert(url.clone(), deadline);
+    }
+
+    /// Marks the diagnostics of every open document as outdated after a change outside of
+    /// them, and schedules their publication right away when the client doesn't pull them.
+    pub(crate) fn invalidate_diagnostics(&mut self) {
+        self.diagnostics_generation += 1;
+        if self.resolved_client_capabilities.pull_diagnostics {
+            return;
+        }
+
+        let now = Instant::now();
+        for workspace in self.workspaces.0.values() {
+            for url in workspace.open_documents.documents.keys() {
+                self.pending_diagnostics.insert(url.clone(), now);
+            }
+        }
+    }
+
+    /// Returns the earliest deadline of the pending diagnostics.
+    pub(crate) fn diagnostics_deadline(&self) -> Option<Instant> {
+        self.pending_diagnostics.values().min().copied()
+    }
+
+    /// Removes and returns the documents whose diagnostics are due at `now`.
+    pub(crate) fn take_due_diagnostics(&mut self, now: Instant) -> Vec<Url> {
+        let mut due = Vec::new();
+        self.pending_diagnostics.retain(|url, deadline| {
+            if *deadline > now {
+                return true;
+            }
+            due.push(url.clone());
+            false
+        });
+        due
+    }
+
     pub(crate) fn document_controller(
         &mut self,
         url: &Url,
@@ -111,8 +209,25 @@ impl Session {
             .ok_or_else(|| anyhow!("Tried to open unavailable document `{url}`"))
     }
 
+    /// Takes a snapshot of the symbol indexes of every workspace.
+    pub(crate) fn take_index_snapshot(&self) -> IndexSnapshot {
+        IndexSnapshot {
+            symbol_indexes: self
+                .workspaces
+                .0
+                .values()
+                .map(|workspace| workspace.symbol_index.clone())
+                .collect(),
+        }
+    }
+
     pub(crate) fn open_workspace_folder(&mut self, url: &Url) -> crate::Result<()> {
-        self.workspaces.open_workspace_folder(url)?;
+        let root = self.workspaces.open_workspace_folder(
+            url,
+            self.position_encoding,
+            &self.global_settings,
+        )?;
+        self.unindexed_workspaces.push(root);
         Ok(())
     }
 
@@ -121,9 +236,130 @@ impl Session {
         Ok(())
     }
 
+    /// Removes and returns the roots of the workspaces whose symbol index should start
+    /// building. Until it's built, the index of a workspace is empty.
+    pub(crate) fn take_unindexed_workspaces(&mut self) -> Vec<PathBuf> {
+        let roots = std::mem::take(&mut self.unindexed_workspaces);
+        for root in &roots {
+            if let Some(workspace) = self.workspaces.0.get_mut(root) {
+                workspace.pending_index_changes.get_or_insert_with(Vec::new);
+            }
+        }
+        roots
+    }
+
+    /// Replaces the symbol index of the workspace at `root` with the `index` built in the
+    /// background, updated with the changes made in the meantime.
+    pub(crate) fn finish_symbol_index(&mut self, root: &Path, mut index: SymbolIndex) {
+        let Some(workspace) = self.workspaces.0.get_mut(root) else {
+            return;
+        };
+
+        if let Some(changes) = workspace.pending_index_changes.take() {
+            index.apply_changes(&changes);
+        }
+        workspace.symbol_index = Arc::new(index);
+    }
+
+    /// Updates the symbol indexes after the files or directories at the `changes` URLs were
+    /// created, changed or deleted on disk.
+    pub(crate) fn update_indexed_files(&mut self, changes: &[lsp_types::FileEvent]) {
+        let mut changes_by_workspace: BTreeMap<PathBuf, Vec<(PathBuf, FileChangeType)>> =
+            BTreeMap::new();
+        for change in changes {
+            let Ok(path) = change.uri.to_file_path() else {
+                continue;
+            };
+            let Some((root, _)) = self.workspaces.entry_for_url(&change.uri) else {
+                continue;
+            };
+            changes_by_workspace
+                .entry(root.to_path_buf())
+                .or_default()
+                .push((path, change.typ));
+        }
+
+        for (root, changes) in changes_by_workspace {
+            let Some(workspace) = self.workspaces.0.get_mut(&root) else {
+                continue;
+            };
+            match &mut workspace.pending_index_changes {
+                Some(pending) => pending.extend(changes),
+                None => Arc::make_mut(&mut workspace.symbol_index).apply_changes(&changes),
+            }
+        }
+    }
+
+    /// Reloads the import resolver of the workspace if `url` is the project's configuration
+    /// file, e.g. its `pyproject.toml`, and returns `true` if it was reloaded.
+    pub(crate) fn update_configuration_file(&mut self, url: &Url) -> bool {
+        let Ok(path) = url.to_file_path() else {
+            return false;
+        };
+        let is_configuration_file = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| CONFIGURATION_FILES.contains(&name));
+        if !is_configuration_file {
+            return false;
+        }
+        let Some((root, workspace)) = self.workspaces.entry_for_url_mut(url) else {
+            return false;
+        };
+
+        if path.parent() != Some(root) {
+            return false;
+        }
+        tracing::info!("Reloading the configuration of `{}`", root.display());
+        workspace.import_resolver = Arc::new(import_resolver(root, &workspace.settings));
+        true
+    }
+
+    /// Returns the URLs of the workspace folders.
+    pub(crate) fn workspace_urls(&self) -> Vec<Url> {
+        self.workspaces
+            .0
+            .keys()
+            .filter_map(|path| Url::from_file_path(path).ok())
+            .collect()
+    }
+
+    /// Replaces the settings of the workspace folder at `url` and returns `true` if they changed.
+    pub(crate) fn update_workspace_settings(
+        &mut self,
+        url: &Url,
+        settings: &ClientSettings,
+    ) -> bool {
+        let Ok(path) = url.to_file_path() else {
+            return false;
+        };
+        match self.workspaces.0.get_mut(&path) {
+            Some(workspace) => workspace.update_settings(&path, settings),
+            None => {
+                tracing::warn!("Received settings for unknown workspace folder `{url}`");
+                false
+            }
+        }
+    }
+
+    /// Replaces the global settings, and the settings of every workspace folder with them.
+    /// Returns `true` if the settings of a workspace folder changed.
+    pub(crate) fn update_global_settings(&mut self, settings: ClientSettings) -> bool {
+        let mut changed = false;
+        for (path, workspace) in &mut self.workspaces.0 {
+            changed |= workspace.update_settings(path, &settings);
+        }
+        self.global_settings = settings;
+        changed
+    }
+
     pub(crate) fn encoding(&self) -> PositionEncoding {
         self.position_encoding
     }
+
+    pub(crate) fn resolved_client_capabilities(&self) -> &ResolvedClientCapabilities {
+        &self.resolved_client_capabilities
+    }
 }
 
 impl OpenDocuments {
@@ -131,6 +367,13 @@ impl OpenDocuments {
         Some(self.documents.get(url)?.make_ref())
     }
 
+    fn snapshots(&self) -> Vec<(Url, DocumentRef)> {
+        self.documents
+            .iter()
+            .map(|(url, controller)| (url.clone(), controller.make_ref()))
+            .collect()
+    }
+
     fn controller(&mut self, url: &Url) -> Option<&mut DocumentController> {
         self.documents.get_mut(url)
     }
@@ -192,10 +435,39 @@ impl DocumentSnapshot {
         &self.resolved_client_capabilities
     }
 
+    pub(crate) fn client_settings(&self) -> &ResolvedClientSettings {
+        &self.client_settings
+    }
+
     pub(crate) fn document(&self) -> &DocumentRef {
         &self.document_ref
     }
 
+    /// Returns the documents open in the snapshot's workspace, by path.
+    pub(crate) fn workspace_documents(&self) -> impl Iterator<Item = (PathBuf, &Document)> {
+        self.workspace_documents
+            .iter()
+            .filter_map(|(url, document)| Some((url.to_file_path().ok()?, &**document)))
+    }
+
+    pub(crate) fn import_resolver(&self) -> &ImportResolver {
+        &self.import_resolver
+    }
+
+    pub(crate) fn symbol_index(&self) -> &SymbolIndex {
+        &self.symbol_index
+    }
+
+    pub(crate) fn semantic_tokens(&self) -> &SemanticTokensCache {
+        &self.semantic_tokens
+    }
+
+    /// Returns the generation of the changes outside of the open documents the snapshot was
+    /// taken at, see [`Session::invalidate_diagnostics`].
+    pub(crate) fn diagnostics_generation(&self) -> u64 {
+        self.diagnostics_generation
+    }
+
     pub(crate) fn encoding(&self) -> PositionEncoding {
         self.position_encoding
     }
@@ -205,19 +477,36 @@ impl DocumentSnapshot {
     }
 }
 
+impl IndexSnapshot {
+    /// Returns the indexed files of every workspace.
+    pub(crate) fn indexed_files(&self) -> impl Iterator<Item = (&Path, &IndexedFile)> {
+        self.symbol_indexes.iter().flat_map(|index| index.files())
+    }
+}
+
 impl Workspaces {
-    fn new(urls: &[Url]) -> crate::Result<Self> {
+    fn new(
+        urls: &[Url],
+        encoding: PositionEncoding,
+        settings: &ClientSettings,
+    ) -> crate::Result<Self> {
         Ok(Self(
             urls.iter()
-                .map(Workspace::new)
+                .map(|url| Workspace::new(url, encoding, settings))
                 .collect::<crate::Result<_>>()?,
         ))
     }
 
-    fn open_workspace_folder(&mut self, folder_url: &Url) -> crate::Result<()> {
-        let (path, workspace) = Workspace::new(folder_url)?;
-        self.0.insert(path, workspace);
-        Ok(())
+    /// Opens the workspace folder at `folder_url` and returns its root.
+    fn open_workspace_folder(
+        &mut self,
+        folder_url: &Url,
+        encoding: PositionEncoding,
+        settings: &ClientSettings,
+    ) -> crate::Result<PathBuf> {
+        let (path, workspace) = Workspace::new(folder_url, encoding, settings)?;
+        self.0.insert(path.clone(), workspace);
+        Ok(path)
     }
 
     fn close_workspace_folder(&mut self, folder_url: &Url) -> crate::Result<()> {
@@ -230,12 +519,6 @@ impl Workspaces {
         Ok(())
     }
 
-    fn document_snapshot(&self, document_url: &Url) -> Option<DocumentRef> {
-        self.workspace_for_url(document_url)?
-            .open_documents
-            .snapshot(document_url)
-    }
-
     fn controller(&mut self, document_url: &Url) -> Option<&mut DocumentController> {
         self.workspace_for_url_mut(document_url)?
             .open_documents
@@ -281,17 +564,57 @@ impl Workspaces {
 }
 
 impl Workspace {
-    pub(crate) fn new(root: &Url) -> crate::Result<(PathBuf, Self)> {
+    pub(crate) fn new(
+        root: &Url,
+        encoding: PositionEncoding,
+        settings: &ClientSettings,
+    ) -> crate::Result<(PathBuf, Self)> {
         let path = root
             .to_file_path()
             .map_err(|()| anyhow!("workspace URL was not a file path!"))?;
 
+        let settings = ResolvedClientSettings::new(settings, &path);
+        let import_resolver = Arc::new(import_resolver(&path, &settings));
+        let symbol_index = Arc::new(SymbolIndex::new(&path, encoding));
 
         Ok((
-            path.clone(),
+            path,
             Self {
                 open_documents: OpenDocuments::default(),
+                import_resolver,
+                symbol_index,
+                pending_index_changes: None,
+                settings: Arc::new(settings),
             },
         ))
     }
+
+    /// Replaces the settings of the workspace at `root` with the resolved `settings`, rebuilding
+    /// the import resolver if they changed. Returns `true` if they changed.
+    fn update_settings(&mut self, root: &Path, settings: &ClientSettings) -> bool {
+        let settings = ResolvedClientSettings::new(settings, root);
+        if *self.settings == settings {
+            return false;
+        }
+
+        self.import_resolver = Arc::new(import_resolver(root, &settings));
+        self.settings = Arc::new(settings);
+        true
+    }
+}
+
+/// Creates the import resolver of the workspace at `root`, resolving third-party imports from
+/// the Python environment found with the interpreter `settings`, configured by the project's
+/// configuration file.
+fn import_resolver(root: &Path, settings: &ResolvedClientSettings) -> ImportResolver {
+    let project = ProjectOptions::load(root)
+        .inspect_err(|err| tracing::error!("Failed to load the project configuration: {err:#}"))
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+
+    ImportResolver::new(root.to_path_buf())
+        .with_host(PythonHost::discover(root, &settings.interpreter))
+        .with_project(&project)
+        .with_extra_paths(settings.extra_paths.clone())
 }
@@ -0,0 +1,274 @@
+//! Index of the top-level symbols defined by the Python files of a workspace.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use lsp_types::{FileChangeType, Range, SymbolKind};
+use python_ast::PySourceType;
+use rayon::prelude::*;
+use ruff_source_file::LineIndex;
+use rustc_hash::FxHashMap;
+
+use crate::analysis::{document_symbols, is_workspace_python_file, python_files};
+use crate::edit::ToRangeExt;
+use crate::PositionEncoding;
+
+/// A top-level class, function or constant of a module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct IndexedSymbol {
+    pub(crate) name: String,
+    pub(crate) kind: SymbolKind,
+    pub(crate) range: Range,
+}
+
+/// The symbols of a file, along with the name of its module.
+#[derive(Debug, Clone)]
+pub(crate) struct IndexedFile {
+    pub(crate) module: String,
+    pub(crate) symbols: Vec<IndexedSymbol>,
+}
+
+/// The symbols of every Python file under a workspace root.
+///
+/// The index is built in the background when the workspace is opened and then kept up to date
+/// with the file changes reported by the client. The files are shared between the clones of
+/// the index, so that updating a file doesn't copy the symbols of the others.
+#[derive(Debug, Clone)]
+pub(crate) struct SymbolIndex {
+    root: PathBuf,
+    encoding: PositionEncoding,
+    files: FxHashMap<PathBuf, Arc<IndexedFile>>,
+}
+
+impl SymbolIndex {
+    /// Creates an empty index of the files under `root`.
+    pub(crate) fn new(root: &Path, encoding: PositionEncoding) -> Self {
+        Self {
+            root: root.to_path_buf(),
+            encoding,
+            files: FxHashMap::default(),
+        }
+    }
+
+    /// Indexes the Python files under `root` in parallel, skipping ignored files.
+    pub(crate) fn build(root: &Path, encoding: PositionEncoding) -> Self {
+        let files = python_files(root)
+            .into_par_iter()
+            .filter_map(|path| {
+                let file = index_file(root, &path, encoding)?;
+                Some((path, Arc::new(file)))
+            })
+            .collect();
+
+        Self {
+            files,
+            ..Self::new(root, encoding)
+        }
+    }
+
+    /// Applies the `changes` made to the files and directories under the workspace root.
+    pub(crate) fn apply_changes(&mut self, changes: &[(PathBuf, FileChangeType)]) {
+        for (path, change) in changes {
+            if *change == FileChangeType::DELETED {
+                self.remove(path);
+            } else if is_python_file(path) {
+                self.update(path);
+            }
+        }
+    }
+
+    /// Re-indexes the file at `path` after it was created or changed.
+    pub(crate) fn update(&mut self, path: &Path) {
+        if !self.files.contains_key(path) && !is_workspace_python_file(&self.root, path) {
+            return;
+        }
+
+        match index_file(&self.root, path, self.encoding) {
+            Some(file) => {
+                self.files.insert(path.to_path_buf(), Arc::new(file));
+            }
+            None => self.remove(path),
+        }
+    }
+
+    /// Removes the file at `path`, or the files in the directory at `path`, from the index.
+    pub(crate) fn remove(&mut self, path: &Path) {
+        self.files.retain(|file, _| !file.starts_with(path));
+    }
+
+    /// Returns the indexed files and their symbols.
+    pub(crate) fn files(&self) -> impl Iterator<Item = (&Path, &IndexedFile)> {
+        self.files
+            .iter()
+            .map(|(path, file)| (path.as_path(), file.as_ref()))
+    }
+}
+
+pub(crate) fn is_python_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension == "py" || extension == "pyi")
+}
+
+/// Reads the file at `path` and returns its top-level classes, functions and constants.
+fn index_file(root: &Path, path: &Path, encoding: PositionEncoding) -> Option<IndexedFile> {
+    let source = std::fs::read_to_string(path)
+        .inspect_err(|err| tracing::debug!("Failed to index `{}`: {err}", path.display()))
+        .ok()?;
+    let index = LineIndex::from_source_text(&source);
+    let parsed = python_parser::parse_unchecked_source(&source, PySourceType::from(path));
+
+    let symbols = document_symbols(parsed.syntax())
+        .into_iter()
+        .filter(|symbol| {
+            matches!(
+                symbol.kind,
+                SymbolKind::CLASS | SymbolKind::FUNCTION | SymbolKind::CONSTANT
+            )
+        })
+        .map(|symbol| IndexedSymbol {
+            range: symbol.selection_range.to_range(&source, &index, encoding),
+            name: symbol.name,
+            kind: symbol.kind,
+        })
+        .collect();
+
+    Some(IndexedFile {
+        module: module_path(root, path),
+        symbols,
+    })
+}
+
+/// Returns the dotted name of the module at `path` relative to `root`, e.g. `foo.bar` for
+/// both `foo/bar.py` and `foo/bar/__init__.py`.
+fn module_path(root: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(root).unwrap_or(path).with_extension("");
+    let mut parts: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    if parts.len() > 1 && parts.last().is_some_and(|part| part == "__init__") {
+        parts.pop();
+    }
+    parts.join(".")
+}
+
+/// Scores how well `name` matches `query`, higher is better. The characters of `query` must
+/// appear in `name` in order, ignoring case. Consecutive characters and characters at the
+/// start of a word, e.g. `P` and `M` of `parse_module` or `ParsedModule`, score higher.
+///
+/// Returns `None` if `name` doesn't match.
+pub(crate) fn fuzzy_score(query: &str, name: &str) -> Option<u32> {
+    let name: Vec<char> = name.chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous_match = None;
+
+    for query_char in query.chars() {
+        let index = (position..name.len())
+            .find(|&index| name[index].to_lowercase().eq(query_char.to_lowercase()))?;
+
+        score += 1;
+        if previous_match.is_some_and(|previous| previous + 1 == index) {
+            score += 2;
+        }
+        let is_word_start = index == 0
+            || name[index - 1] == '_'
+            || (name[index].is_uppercase() && name[index - 1].is_lowercase());
+        if is_word_start {
+            score += 3;
+        }
+        if name[index] == query_char {
+            score += 1;
+        }
+
+        previous_match = Some(index);
+        position = index + 1;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::SymbolKind;
+
+    use crate::PositionEncoding;
+
+    use super::{fuzzy_score, SymbolIndex};
+
+    fn symbols(index: &SymbolIndex) -> Vec<(String, String, SymbolKind)> {
+        let mut symbols: Vec<_> = index
+            .files()
+            .flat_map(|(_, file)| {
+                file.symbols
+                    .iter()
+                    .map(|symbol| (file.module.clone(), symbol.name.clone(), symbol.kind))
+            })
+            .collect();
+        symbols.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        symbols
+    }
+
+    #[test]
+    fn indexes_top_level_symbols() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir_all(root.join(".git")).unwrap();
+        std::fs::create_dir_all(root.join("pkg")).unwrap();
+        std::fs::create_dir_all(root.join("build")).unwrap();
+        std::fs::create_dir_all(root.join("pkg/generated")).unwrap();
+        std::fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        std::fs::write(root.join("pkg/.gitignore"), "generated/\n").unwrap();
+        std::fs::write(
+            root.join("pkg/__init__.py"),
+            "MAX_SIZE = 1\nvalue = 2\n\nclass Parser:\n    def parse(self): ...\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("pkg/util.py"), "def helper(): ...\n").unwrap();
+        std::fs::write(root.join("build/generated.py"), "def generated(): ...\n").unwrap();
+
+        let mut index = SymbolIndex::build(root, PositionEncoding::UTF16);
+        assert_eq!(
+            symbols(&index),
+            vec![
+                (
+                    "pkg".to_string(),
+                    "MAX_SIZE".to_string(),
+                    SymbolKind::CONSTANT
+                ),
+                ("pkg".to_string(), "Parser".to_string(), SymbolKind::CLASS),
+                (
+                    "pkg.util".to_string(),
+                    "helper".to_string(),
+                    SymbolKind::FUNCTION
+                ),
+            ]
+        );
+
+        std::fs::write(root.join("pkg/util.py"), "def renamed(): ...\n").unwrap();
+        index.update(&root.join("pkg/util.py"));
+        std::fs::write(root.join("build/other.py"), "def ignored(): ...\n").unwrap();
+        index.update(&root.join("build/other.py"));
+        std::fs::write(root.join("pkg/generated/nested.py"), "def nested(): ...\n").unwrap();
+        index.update(&root.join("pkg/generated/nested.py"));
+        index.remove(&root.join("pkg/__init__.py"));
+
+        assert_eq!(
+            symbols(&index),
+            vec![(
+                "pkg.util".to_string(),
+                "renamed".to_string(),
+                SymbolKind::FUNCTION
+            )]
+        );
+    }
+
+    #[test]
+    fn fuzzy_matching() {
+        assert_eq!(fuzzy_score("pm", "resolve"), None);
+        assert!(fuzzy_score("pm", "ParsedModule").is_some());
+        assert!(fuzzy_score("pm", "parse_module") > fuzzy_score("pm", "compare"));
+        assert!(fuzzy_score("Parse", "Parser") > fuzzy_score("Parse", "SparseArray"));
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+    }
+}
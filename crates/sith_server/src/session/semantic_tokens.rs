@@ -0,0 +1,40 @@
+//! The semantic tokens last sent for each document, the base of the delta requests.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use lsp_types::{SemanticToken, Url};
+use rustc_hash::FxHashMap;
+
+#[derive(Debug, Default)]
+pub(crate) struct SemanticTokensCache {
+    next_result_id: AtomicU64,
+    documents: Mutex<FxHashMap<Url, (String, Vec<SemanticToken>)>>,
+}
+
+impl SemanticTokensCache {
+    /// Remembers the tokens sent for the document at `url` and returns their result ID.
+    pub(crate) fn insert(&self, url: &Url, tokens: Vec<SemanticToken>) -> String {
+        let result_id = self
+            .next_result_id
+            .fetch_add(1, Ordering::Relaxed)
+            .to_string();
+        self.documents
+            .lock()
+            .unwrap()
+            .insert(url.clone(), (result_id.clone(), tokens));
+        result_id
+    }
+
+    /// Returns the tokens sent for the document at `url`, if they're still the ones with
+    /// `result_id`.
+    pub(crate) fn get(&self, url: &Url, result_id: &str) -> Option<Vec<SemanticToken>> {
+        let documents = self.documents.lock().unwrap();
+        let (id, tokens) = documents.get(url)?;
+        (id == result_id).then(|| tokens.clone())
+    }
+
+    pub(crate) fn remove(&self, url: &Url) {
+        self.documents.lock().unwrap().remove(url);
+    }
+}
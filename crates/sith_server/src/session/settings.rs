@@ -0,0 +1,280 @@
+use std::path::{Path, PathBuf};
+
+use lsp_types::{ClientCapabilities, MarkupKind};
+use serde::Deserialize;
+
+/// Tracks the LSP features that the client supports, resolved from the
+/// [`ClientCapabilities`] sent during initialization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolvedClientCapabilities {
+    pub(crate) pull_diagnostics: bool,
+    /// Whether hover contents are rendered as Markdown rather than plain text.
+    pub(crate) hover_markdown: bool,
+    /// Whether signature help parameters can be given as offsets into the signature's label.
+    pub(crate) signature_label_offsets: bool,
+    /// Whether the settings of each workspace folder can be pulled with `workspace/configuration`.
+    pub(crate) workspace_configuration: bool,
+    /// Whether the client can be asked to pull the diagnostics of every document again.
+    pub(crate) diagnostic_refresh: bool,
+    /// Whether the client can be asked to request the inlay hints of every document again.
+    pub(crate) inlay_hint_refresh: bool,
+}
+
+impl ResolvedClientCapabilities {
+    pub(super) fn new(client_capabilities: &ClientCapabilities) -> Self {
+        let pull_diagnostics = client_capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.diagnostic.as_ref())
+            .is_some();
+
+        // The formats are listed in the client's order of preference.
+        let hover_markdown = client_capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.hover.as_ref())
+            .and_then(|hover| hover.content_format.as_ref())
+            .and_then(|formats| formats.first())
+            .is_some_and(|format| *format == MarkupKind::Markdown);
+
+        let signature_label_offsets = client_capabilities
+            .text_document
+            .as_ref()
+            .and_then(|text_document| text_document.signature_help.as_ref())
+            .and_then(|signature_help| signature_help.signature_information.as_ref())
+            .and_then(|information| information.parameter_information.as_ref())
+            .and_then(|parameter| parameter.label_offset_support)
+            .unwrap_or_default();
+
+        let workspace_configuration = client_capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.configuration)
+            .unwrap_or_default();
+
+        let diagnostic_refresh = client_capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.diagnostic.as_ref())
+            .and_then(|diagnostic| diagnostic.refresh_support)
+            .unwrap_or_default();
+
+        let inlay_hint_refresh = client_capabilities
+            .workspace
+            .as_ref()
+            .and_then(|workspace| workspace.inlay_hint.as_ref())
+            .and_then(|inlay_hint| inlay_hint.refresh_support)
+            .unwrap_or_default();
+
+        Self {
+            pull_diagnostics,
+            hover_markdown,
+            signature_label_offsets,
+            workspace_configuration,
+            diagnostic_refresh,
+            inlay_hint_refresh,
+        }
+    }
+}
+
+/// The `sith` section of the client's configuration, e.g. `sith.ruff.format.enable`, as sent
+/// in the `initializationOptions`, in response to `workspace/configuration` or with
+/// `workspace/didChangeConfiguration`.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct ClientSettings {
+    /// The candidate paths of the Python interpreter, the first existing one is used.
+    interpreter: Vec<String>,
+    /// Additional directories to resolve imports from.
+    extra_paths: Vec<String>,
+    ruff: RuffOptions,
+    diagnostics: DiagnosticsSettings,
+    inlay_hints: InlayHintsSettings,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct RuffOptions {
+    path: Vec<String>,
+    format: FormatSettings,
+}
+
+impl ClientSettings {
+    /// Reads the settings sent by the client, falling back to the defaults if they're invalid.
+    pub(crate) fn from_value(value: serde_json::Value) -> Self {
+        if value.is_null() {
+            return Self::default();
+        }
+        serde_json::from_value(value)
+            .inspect_err(|err| tracing::error!("Failed to deserialize the client settings: {err}"))
+            .unwrap_or_default()
+    }
+}
+
+/// The settings of a workspace, with the paths resolved relative to its root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ResolvedClientSettings {
+    pub(crate) interpreter: Vec<PathBuf>,
+    pub(crate) extra_paths: Vec<PathBuf>,
+    pub(crate) ruff: RuffSettings,
+    pub(crate) diagnostics: DiagnosticsSettings,
+    pub(crate) inlay_hints: InlayHintsSettings,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct RuffSettings {
+    /// The candidate paths of the formatter executable, the first existing one is used.
+    pub(crate) path: Vec<PathBuf>,
+    pub(crate) format: FormatSettings,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct FormatSettings {
+    pub(crate) enable: bool,
+    /// Additional arguments passed to the formatter.
+    pub(crate) args: Vec<String>,
+}
+
+impl Default for FormatSettings {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            args: Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct DiagnosticsSettings {
+    /// Whether diagnostics are reported at all.
+    pub(crate) enable: bool,
+}
+
+impl Default for DiagnosticsSettings {
+    fn default() -> Self {
+        Self { enable: true }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub(crate) struct InlayHintsSettings {
+    /// Whether the names of the parameters are shown before the literal positional arguments.
+    pub(crate) parameter_names: bool,
+    /// Whether the inferred types of variables and return types of functions are shown.
+    pub(crate) types: bool,
+}
+
+impl Default for InlayHintsSettings {
+    fn default() -> Self {
+        Self {
+            parameter_names: true,
+            types: true,
+        }
+    }
+}
+
+impl ResolvedClientSettings {
+    pub(crate) fn new(settings: &ClientSettings, root: &Path) -> Self {
+        let resolve_all =
+            |paths: &[String]| paths.iter().map(|path| resolve_path(path, root)).collect();
+
+        Self {
+            interpreter: resolve_all(&settings.interpreter),
+            extra_paths: resolve_all(&settings.extra_paths),
+            ruff: RuffSettings {
+                // A bare executable name is looked up in `PATH`, rather than in the workspace.
+                path: settings
+                    .ruff
+                    .path
+                    .iter()
+                    .map(|path| {
+                        if Path::new(path).components().count() == 1 {
+                            PathBuf::from(path)
+                        } else {
+                            resolve_path(path, root)
+                        }
+                    })
+                    .collect(),
+                format: settings.ruff.format.clone(),
+            },
+            diagnostics: settings.diagnostics.clone(),
+            inlay_hints: settings.inlay_hints.clone(),
+        }
+    }
+}
+
+/// Resolves a path of the settings, expanding `~` and `${workspaceFolder}`. Relative paths are
+/// relative to the workspace `root`.
+fn resolve_path(path: &str, root: &Path) -> PathBuf {
+    let path = path.replace("${workspaceFolder}", &root.to_string_lossy());
+    let path = match path.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => match home_directory() {
+            Some(home) => home.join(rest.trim_start_matches(['/', '\\'])),
+            None => PathBuf::from(path),
+        },
+        _ => PathBuf::from(path),
+    };
+    root.join(path)
+}
+
+fn home_directory() -> Option<PathBuf> {
+    std::env::var_os(if cfg!(windows) { "USERPROFILE" } else { "HOME" }).map(PathBuf::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use serde_json::json;
+
+    use super::{ClientSettings, ResolvedClientSettings};
+
+    #[test]
+    fn deserialize_settings() {
+        let settings = ClientSettings::from_value(json!({
+            "interpreter": ["${workspaceFolder}/.venv/bin/python"],
+            "extraPaths": ["src", "/opt/lib"],
+            "ruff": {
+                "path": ["ruff", "tools/black"],
+                "format": { "args": ["--line-length=100"] }
+            },
+            "diagnostics": { "enable": false },
+            "inlayHints": { "types": false },
+            "trace": { "server": "verbose" }
+        }));
+        let resolved = ResolvedClientSettings::new(&settings, Path::new("/project"));
+
+        assert_eq!(
+            resolved.interpreter,
+            [PathBuf::from("/project/.venv/bin/python")]
+        );
+        assert_eq!(
+            resolved.extra_paths,
+            [PathBuf::from("/project/src"), PathBuf::from("/opt/lib")]
+        );
+        assert_eq!(
+            resolved.ruff.path,
+            [PathBuf::from("ruff"), PathBuf::from("/project/tools/black")]
+        );
+        assert!(resolved.ruff.format.enable);
+        assert_eq!(resolved.ruff.format.args, ["--line-length=100"]);
+        assert!(!resolved.diagnostics.enable);
+        assert!(resolved.inlay_hints.parameter_names);
+        assert!(!resolved.inlay_hints.types);
+    }
+
+    #[test]
+    fn invalid_settings_fall_back_to_defaults() {
+        assert_eq!(
+            ClientSettings::from_value(json!({ "ruff": { "path": "ruff" } })),
+            ClientSettings::default()
+        );
+        assert_eq!(
+            ClientSettings::from_value(serde_json::Value::Null),
+            ClientSettings::default()
+        );
+    }
+}
@@ -1,6 +1,7 @@
 const PANDAS_HTML_SRC: &str = include_str!("../resources/test/fixtures/pandas_html.py");
 
 use lsp_types::{Position, Range, TextDocumentContentChangeEvent};
+use ruff_source_file::LineIndex;
 use sith_server::{Document, PositionEncoding};
 
 #[test]
@@ -89,3 +90,60 @@ fn delete_lines_pandas_html() {
 
     insta::assert_snapshot!(document.contents());
 }
+
+#[test]
+fn edits_supersede_older_snapshots() {
+    let document = Document::new("x = 1\n".to_string(), 1);
+    assert!(!document.is_outdated());
+
+    let mut edited = document.clone();
+    edited.apply_changes(
+        vec![TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "x = 2\n".into(),
+        }],
+        2,
+        PositionEncoding::UTF16,
+    );
+
+    assert!(document.is_outdated());
+    assert!(!edited.is_outdated());
+}
+
+#[test]
+fn incremental_edits_update_line_index() {
+    let mut document = Document::new(PANDAS_HTML_SRC.to_string(), 1);
+
+    let edit = |start: (u32, u32), end: (u32, u32), text: &str| TextDocumentContentChangeEvent {
+        range: Some(Range {
+            start: Position {
+                line: start.0,
+                character: start.1,
+            },
+            end: Position {
+                line: end.0,
+                character: end.1,
+            },
+        }),
+        range_length: None,
+        text: text.into(),
+    };
+
+    document.apply_changes(
+        vec![
+            edit((0, 0), (0, 0), "import os\r\n\n"),
+            edit((10, 4), (25, 0), ""),
+            edit((30, 2), (30, 2), "\n    x = '🫣'\n"),
+            edit((1, 0), (1, 0), "\r"),
+        ],
+        2,
+        PositionEncoding::UTF16,
+    );
+
+    assert!(document.contents().starts_with("import os\r\n\r\n"));
+    assert_eq!(
+        document.index().line_starts(),
+        LineIndex::from_source_text(document.contents()).line_starts()
+    );
+}
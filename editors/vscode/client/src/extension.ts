@@ -33,6 +33,10 @@ export async function activate(context: ExtensionContext) {
     let clientOptions: LanguageClientOptions = {
         // Register the server for plain text documents
         documentSelector: [{ scheme: "file", language: "python" }],
+        // The `sith` settings, e.g. `sith.ruff.format.enable`.
+        initializationOptions: workspace.getConfiguration("sith"),
+        // Notifies the server when the `sith` settings change.
+        synchronize: { configurationSection: "sith" },
         traceOutputChannel,
     };
 